@@ -1,10 +1,14 @@
 // src-tauri/src/api/discord/bulk/messages.rs
 
+use super::permissions::{BULK_DELETE_LIMIT, bulk_deletable, can_manage_messages};
 use crate::api::rate_limiter::ApiHandle;
+use crate::auth::types::DiscordError;
+use crate::core::cache::{CacheManager, PurgeTargetFilter};
 use crate::core::error::AppError;
 use crate::core::logger::Logger;
 use crate::core::op_manager::{OperationManager, OperationState};
 use crate::core::vault::Vault;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Emitter, Manager, Window};
 
@@ -26,6 +30,303 @@ pub struct PurgeOptions {
     pub only_attachments: bool,
     #[serde(alias = "closeEmptyDms")]
     pub close_empty_dms: bool,
+    /// Build targets from the local index for channels with a fresh sync cursor.
+    #[serde(default, alias = "useCache")]
+    pub use_cache: bool,
+    /// Maximum age in seconds of a sync cursor before the channel is rescanned.
+    #[serde(default, alias = "cacheMaxAge")]
+    pub cache_max_age: Option<u64>,
+    /// Edit each message to a placeholder, dropping its attachments, before deleting it,
+    /// so loggers that only keep the latest revision never see the original.
+    #[serde(default, alias = "scrubBeforeDelete")]
+    pub scrub_before_delete: bool,
+    /// Text written by the scrub edit; defaults to [`DEFAULT_SCRUB_PLACEHOLDER`].
+    #[serde(default, alias = "scrubPlaceholder")]
+    pub scrub_placeholder: Option<String>,
+    #[serde(default, alias = "pinnedMode")]
+    pub pinned: PinnedMode,
+    /// Explicit targets keyed by channel. When set, only these messages are deleted, and
+    /// only those the local index lists as ours.
+    #[serde(default, alias = "messageIds")]
+    pub message_ids: Option<HashMap<String, Vec<String>>>,
+}
+
+/// How a purge treats messages pinned in their channel.
+#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PinnedMode {
+    /// Pinned messages are deleted like any other.
+    #[default]
+    Include,
+    /// Pinned messages are left in place.
+    Skip,
+    /// Only pinned messages are deleted.
+    Only,
+    /// Pinned messages are unpinned first, then deleted.
+    UnpinThenDelete,
+}
+
+impl PinnedMode {
+    /// Whether a message with the given pin state is a purge target.
+    pub fn allows(self, pinned: bool) -> bool {
+        match self {
+            PinnedMode::Skip => !pinned,
+            PinnedMode::Only => pinned,
+            PinnedMode::Include | PinnedMode::UnpinThenDelete => true,
+        }
+    }
+
+    /// The pin list a purge works from. `Include` needs none; for every other mode a
+    /// failed fetch (`None`) means the channel is skipped rather than purged without it.
+    pub fn usable_pins(
+        self,
+        fetched: Option<Vec<serde_json::Value>>,
+    ) -> Option<Vec<serde_json::Value>> {
+        match self {
+            PinnedMode::Include => Some(Vec::new()),
+            _ => fetched,
+        }
+    }
+}
+
+/// Cursors older than this are considered stale when no explicit age is given.
+pub(super) const DEFAULT_CACHE_MAX_AGE_SECS: u64 = 86400;
+
+/// Discord's "Unknown Message" code; the target is already gone.
+pub(super) const UNKNOWN_MESSAGE_CODE: u32 = 10008;
+
+/// "Cannot execute action on a system message"; such messages can be deleted but not edited.
+const SYSTEM_MESSAGE_CODE: u32 = 50021;
+
+/// "Missing Permissions"; returned with a 403 when we may no longer manage messages.
+const MISSING_PERMISSIONS_CODE: u32 = 50013;
+
+pub const DEFAULT_SCRUB_PLACEHOLDER: &str = ".";
+
+/// The channel's pinned messages. Only fetched when the purge cares about pins.
+async fn fetch_pins(
+    api: &ApiHandle,
+    channel_id: &str,
+    token: &str,
+    is_bearer: bool,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    let url = format!("https://discord.com/api/v9/channels/{}/pins", channel_id);
+    let pins = api
+        .send_request_json(reqwest::Method::GET, &url, None, token, is_bearer, None)
+        .await?;
+    Ok(serde_json::from_value(pins)?)
+}
+
+/// Unpins and scrubs a message as the options require, ahead of its deletion. `msg` is
+/// the message as fetched, if known, so already scrubbed ones are not edited again.
+#[allow(clippy::too_many_arguments)]
+async fn prepare_message(
+    api: &ApiHandle,
+    options: &PurgeOptions,
+    channel_id: &str,
+    msg_id: &str,
+    msg: Option<&serde_json::Value>,
+    pinned: bool,
+    token: &str,
+    is_bearer: bool,
+) -> Result<(), AppError> {
+    let url = format!(
+        "https://discord.com/api/v9/channels/{}/messages/{}",
+        channel_id, msg_id
+    );
+
+    if pinned && options.pinned == PinnedMode::UnpinThenDelete {
+        let unpin_url = format!(
+            "https://discord.com/api/v9/channels/{}/pins/{}",
+            channel_id, msg_id
+        );
+        api.send_request_json(
+            reqwest::Method::DELETE,
+            &unpin_url,
+            None,
+            token,
+            is_bearer,
+            None,
+        )
+        .await?;
+    }
+
+    if options.scrub_before_delete {
+        let placeholder = options
+            .scrub_placeholder
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or(DEFAULT_SCRUB_PLACEHOLDER);
+        let scrubbed = msg.is_some_and(|m| {
+            m["content"].as_str() == Some(placeholder)
+                && m["attachments"].as_array().is_none_or(|a| a.is_empty())
+        });
+        if !scrubbed {
+            let body = serde_json::json!({ "content": placeholder, "attachments": [] });
+            match api
+                .send_request_json(
+                    reqwest::Method::PATCH,
+                    &url,
+                    Some(body),
+                    token,
+                    is_bearer,
+                    None,
+                )
+                .await
+            {
+                Ok(_) => {}
+                Err(e) if e.discord_code == Some(SYSTEM_MESSAGE_CODE) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_message(
+    api: &ApiHandle,
+    channel_id: &str,
+    msg_id: &str,
+    token: &str,
+    is_bearer: bool,
+) -> Result<(), AppError> {
+    let url = format!(
+        "https://discord.com/api/v9/channels/{}/messages/{}",
+        channel_id, msg_id
+    );
+    api.send_request_json(reqwest::Method::DELETE, &url, None, token, is_bearer, None)
+        .await
+        .map(|_| ())
+}
+
+/// Collects recent messages for `POST /messages/bulk-delete` in channels where we can
+/// manage messages. Older messages, and everything elsewhere, are deleted one by one.
+struct BulkDeleteQueue<'a> {
+    app: &'a AppHandle,
+    state: &'a OperationState,
+    enabled: bool,
+    pending: Vec<String>,
+}
+
+impl<'a> BulkDeleteQueue<'a> {
+    fn new(app: &'a AppHandle, state: &'a OperationState, enabled: bool) -> Self {
+        Self {
+            app,
+            state,
+            enabled,
+            pending: Vec::new(),
+        }
+    }
+
+    fn accepts(&self, msg_id: &str) -> bool {
+        self.enabled && bulk_deletable(msg_id, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// Deletes the queued messages and returns the IDs that are gone. A rejected batch
+    /// is retried one by one; only a permission error disables the queue for the rest
+    /// of the channel. Nothing is sent once the operation is aborted.
+    async fn flush(
+        &mut self,
+        api: &ApiHandle,
+        channel_id: &str,
+        token: &str,
+        is_bearer: bool,
+    ) -> Vec<String> {
+        if self.state.should_abort.load(Ordering::SeqCst) {
+            self.pending.clear();
+            return Vec::new();
+        }
+        let ids = std::mem::take(&mut self.pending);
+        if ids.len() > 1 {
+            let url = format!(
+                "https://discord.com/api/v9/channels/{}/messages/bulk-delete",
+                channel_id
+            );
+            let body = serde_json::json!({ "messages": ids });
+            match api
+                .send_request_json(
+                    reqwest::Method::POST,
+                    &url,
+                    Some(body),
+                    token,
+                    is_bearer,
+                    None,
+                )
+                .await
+            {
+                Ok(_) => return ids,
+                Err(e)
+                    if e.discord_code == Some(MISSING_PERMISSIONS_CODE)
+                        || matches!(e.semantic_error, Some(DiscordError::MissingAccess)) =>
+                {
+                    self.enabled = false;
+                    Logger::warn(
+                        self.app,
+                        &format!(
+                            "[OP] Bulk-delete refused in {}, deleting one by one: {}",
+                            channel_id, e
+                        ),
+                        None,
+                    );
+                }
+                Err(e) => {
+                    Logger::debug(
+                        self.app,
+                        &format!(
+                            "[OP] Bulk-delete batch failed in {}, retrying one by one: {}",
+                            channel_id, e
+                        ),
+                        None,
+                    );
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        for id in ids {
+            if self.state.should_abort.load(Ordering::SeqCst) {
+                break;
+            }
+            match delete_message(api, channel_id, &id, token, is_bearer).await {
+                Ok(()) => removed.push(id),
+                Err(e) if e.discord_code == Some(UNKNOWN_MESSAGE_CODE) => removed.push(id),
+                Err(_) => {}
+            }
+        }
+        removed
+    }
+}
+
+/// Deletes one of our messages, first unpinning or overwriting it as the options require.
+/// When the unpin or edit fails the message is left in place, so a later run can retry.
+/// Messages the queue accepts are only prepared; the returned IDs are those actually
+/// removed, which includes a batch when the queue fills up.
+#[allow(clippy::too_many_arguments)]
+async fn remove_message(
+    api: &ApiHandle,
+    options: &PurgeOptions,
+    queue: &mut BulkDeleteQueue<'_>,
+    channel_id: &str,
+    msg_id: &str,
+    msg: Option<&serde_json::Value>,
+    pinned: bool,
+    token: &str,
+    is_bearer: bool,
+) -> Result<Vec<String>, AppError> {
+    prepare_message(
+        api, options, channel_id, msg_id, msg, pinned, token, is_bearer,
+    )
+    .await?;
+    if !queue.accepts(msg_id) {
+        delete_message(api, channel_id, msg_id, token, is_bearer).await?;
+        return Ok(vec![msg_id.to_string()]);
+    }
+    queue.pending.push(msg_id.to_string());
+    if queue.pending.len() < BULK_DELETE_LIMIT {
+        return Ok(Vec::new());
+    }
+    Ok(queue.flush(api, channel_id, token, is_bearer).await)
 }
 
 #[tauri::command]
@@ -100,9 +401,178 @@ pub async fn bulk_delete_messages(
     Ok(())
 }
 
+/// Returns the cached purge targets for a channel, or `None` when the channel
+/// has never been deep scanned or its cursor is older than the allowed age.
+fn resolve_cached_targets(
+    app: &AppHandle,
+    options: &PurgeOptions,
+    channel_id: &str,
+    user_id: &str,
+) -> Option<Vec<String>> {
+    let cursor = match CacheManager::get_sync_cursor(app, user_id, channel_id) {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            Logger::debug(
+                app,
+                &format!(
+                    "[OP] No sync cursor for {}, falling back to scan",
+                    channel_id
+                ),
+                None,
+            );
+            return None;
+        }
+        Err(e) => {
+            Logger::warn(
+                app,
+                &format!("[OP] Cursor lookup failed for {}: {}", channel_id, e),
+                None,
+            );
+            return None;
+        }
+    };
+
+    let max_age = options.cache_max_age.unwrap_or(DEFAULT_CACHE_MAX_AGE_SECS) as i64;
+    if chrono::Utc::now().timestamp() - cursor.last_scanned > max_age {
+        Logger::debug(
+            app,
+            &format!(
+                "[OP] Sync cursor for {} is stale, falling back to scan",
+                channel_id
+            ),
+            None,
+        );
+        return None;
+    }
+
+    let filter = PurgeTargetFilter {
+        channel_id: channel_id.to_string(),
+        author_id: user_id.to_string(),
+        start_time: options.start_time.map(|t| t as i64),
+        end_time: options.end_time.map(|t| t as i64),
+        search_query: options.search_query.clone(),
+        only_attachments: options.only_attachments,
+    };
+
+    match CacheManager::query_purge_targets(app, user_id, &filter) {
+        Ok(ids) => Some(ids),
+        Err(e) => {
+            Logger::warn(
+                app,
+                &format!("[OP] Cache query failed for {}: {}", channel_id, e),
+                None,
+            );
+            None
+        }
+    }
+}
+
+/// Deletes a precomputed list of message IDs, from the cache or the pin list, without
+/// touching the history endpoints.
+#[allow(clippy::too_many_arguments)]
+async fn purge_cached_targets(
+    app: &AppHandle,
+    window: &Window,
+    options: &PurgeOptions,
+    channel_id: &str,
+    index: usize,
+    token: &str,
+    is_bearer: bool,
+    user_id: &str,
+    api: &ApiHandle,
+    state: &OperationState,
+    queue: &mut BulkDeleteQueue<'_>,
+    mut targets: Vec<String>,
+    pins: &HashSet<String>,
+) -> Result<usize, AppError> {
+    targets.retain(|id| options.pinned.allows(pins.contains(id)));
+    Logger::info(
+        app,
+        &format!(
+            "[OP] Targeted purge for {}: {} precomputed targets",
+            channel_id,
+            targets.len()
+        ),
+        None,
+    );
+
+    if options.simulation {
+        return Ok(targets.len());
+    }
+
+    let total = targets.len();
+    let progress = |deleted: usize| {
+        let _ = window.emit(
+            "deletion_progress",
+            serde_json::json!({
+                "current": index + 1,
+                "total": total,
+                "id": channel_id,
+                "deleted_count": deleted,
+                "status": "purging_cached"
+            }),
+        );
+    };
+    let mut removed: Vec<String> = Vec::new();
+    for msg_id in targets {
+        state.wait_if_paused().await;
+        if state.should_abort.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let pinned = pins.contains(&msg_id);
+        match remove_message(
+            api, options, queue, channel_id, &msg_id, None, pinned, token, is_bearer,
+        )
+        .await
+        {
+            Ok(ids) => {
+                if !ids.is_empty() {
+                    removed.extend(ids);
+                    progress(removed.len());
+                }
+            }
+            Err(e) if e.discord_code == Some(UNKNOWN_MESSAGE_CODE) => {
+                // Already gone on Discord's side; keep the index consistent.
+                let _ = CacheManager::mark_messages_deleted(app, user_id, &[msg_id]);
+            }
+            Err(e) => {
+                Logger::warn(
+                    app,
+                    &format!("[OP] Failed to delete {} in {}: {}", msg_id, channel_id, e),
+                    None,
+                );
+            }
+        }
+    }
+
+    let flushed = queue.flush(api, channel_id, token, is_bearer).await;
+    if !flushed.is_empty() {
+        removed.extend(flushed);
+        progress(removed.len());
+    }
+
+    let _ = CacheManager::mark_messages_deleted(app, user_id, &removed);
+    Ok(removed.len())
+}
+
+fn matches_query(options: &PurgeOptions, msg: &serde_json::Value) -> bool {
+    options
+        .search_query
+        .as_ref()
+        .map(|q| {
+            msg["content"]
+                .as_str()
+                .unwrap_or("")
+                .to_lowercase()
+                .contains(&q.to_lowercase())
+        })
+        .unwrap_or(true)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn process_channel_task(
-    _app: &AppHandle,
+    app: &AppHandle,
     window: &Window,
     options: &PurgeOptions,
     channel_id: &str,
@@ -118,6 +588,108 @@ async fn process_channel_task(
         return Ok(0);
     }
 
+    // Pins are precomputed so cached IDs and search hits can be checked too. Without
+    // them a pin-aware purge could delete what it was told to keep, so failures skip.
+    let fetched = match options.pinned {
+        PinnedMode::Include => None,
+        _ => match fetch_pins(api, channel_id, token, is_bearer).await {
+            Ok(pins) => Some(pins),
+            Err(e) => {
+                Logger::warn(
+                    app,
+                    &format!(
+                        "[OP] Could not fetch pins of {}, skipping the channel: {}",
+                        channel_id, e
+                    ),
+                    None,
+                );
+                None
+            }
+        },
+    };
+    let Some(pinned_messages) = options.pinned.usable_pins(fetched) else {
+        return Ok(0);
+    };
+    let pins: HashSet<String> = pinned_messages
+        .iter()
+        .filter_map(|m| m["id"].as_str().map(|id| id.to_string()))
+        .collect();
+    let is_pinned = |msg: &serde_json::Value| {
+        msg["pinned"].as_bool() == Some(true)
+            || msg["id"].as_str().is_some_and(|id| pins.contains(id))
+    };
+
+    let bulk = !options.simulation
+        && can_manage_messages(api, channel_id, user_id, token, is_bearer).await;
+    if bulk {
+        Logger::debug(
+            app,
+            &format!(
+                "[OP] Using bulk-delete for recent messages in {}",
+                channel_id
+            ),
+            None,
+        );
+    }
+    let mut queue = BulkDeleteQueue::new(app, state, bulk);
+
+    if let Some(message_ids) = &options.message_ids {
+        let wanted: HashSet<&String> = message_ids.get(channel_id).into_iter().flatten().collect();
+        let filter = PurgeTargetFilter {
+            channel_id: channel_id.to_string(),
+            author_id: user_id.to_string(),
+            start_time: None,
+            end_time: None,
+            search_query: None,
+            only_attachments: false,
+        };
+        let targets = CacheManager::query_purge_targets(app, user_id, &filter)?
+            .into_iter()
+            .filter(|id| wanted.contains(id))
+            .collect();
+        return purge_cached_targets(
+            app, window, options, channel_id, index, token, is_bearer, user_id, api, state,
+            &mut queue, targets, &pins,
+        )
+        .await;
+    }
+
+    if options.pinned == PinnedMode::Only {
+        let targets = pinned_messages
+            .iter()
+            .filter(|m| m["author"]["id"].as_str() == Some(user_id))
+            .filter(|m| matches_query(options, m))
+            .filter_map(|m| m["id"].as_str().map(|id| id.to_string()))
+            .collect();
+        return purge_cached_targets(
+            app, window, options, channel_id, index, token, is_bearer, user_id, api, state,
+            &mut queue, targets, &pins,
+        )
+        .await;
+    }
+
+    if options.use_cache
+        && let Some(targets) = resolve_cached_targets(app, options, channel_id, user_id)
+    {
+        return purge_cached_targets(
+            app, window, options, channel_id, index, token, is_bearer, user_id, api, state,
+            &mut queue, targets, &pins,
+        )
+        .await;
+    }
+
+    let progress = |deleted: usize, status: &str| {
+        let _ = window.emit(
+            "deletion_progress",
+            serde_json::json!({
+                "current": index + 1,
+                "total": 0,
+                "id": channel_id,
+                "deleted_count": deleted,
+                "status": status
+            }),
+        );
+    };
     let mut deleted = 0;
 
     // 1. Search API Pass (Optimized)
@@ -150,40 +722,36 @@ async fn process_channel_task(
                             break;
                         }
 
-                        if let Some(id) = msg["id"].as_str() {
-                            let del_url = format!(
-                                "https://discord.com/api/v9/channels/{}/messages/{}",
-                                channel_id, id
-                            );
-                            if api
-                                .send_request_json(
-                                    reqwest::Method::DELETE,
-                                    &del_url,
-                                    None,
-                                    token,
-                                    is_bearer,
-                                    None,
-                                )
-                                .await
-                                .is_ok()
-                            {
-                                deleted += 1;
-                                let _ = window.emit(
-                                    "deletion_progress",
-                                    serde_json::json!({
-                                        "current": index + 1,
-                                        "total": 0,
-                                        "id": channel_id,
-                                        "deleted_count": deleted,
-                                        "status": "purging_optimized"
-                                    }),
-                                );
-                            }
+                        if let Some(id) = msg["id"].as_str()
+                            && options.pinned.allows(is_pinned(msg))
+                            && let Ok(ids) = remove_message(
+                                api,
+                                options,
+                                &mut queue,
+                                channel_id,
+                                id,
+                                Some(msg),
+                                is_pinned(msg),
+                                token,
+                                is_bearer,
+                            )
+                            .await
+                            && !ids.is_empty()
+                        {
+                            deleted += ids.len();
+                            progress(deleted, "purging_optimized");
                         }
                     }
                 }
             }
         }
+
+        // Flush before scanning so queued messages are not picked up again.
+        let flushed = queue.flush(api, channel_id, token, is_bearer).await;
+        if !flushed.is_empty() {
+            deleted += flushed.len();
+            progress(deleted, "purging_optimized");
+        }
     }
 
     // 2. Linear Scan Pass (Safety Net)
@@ -228,46 +796,25 @@ async fn process_channel_task(
             }
 
             let msg_id = msg["id"].as_str().unwrap_or_default();
-            let matches = options
-                .search_query
-                .as_ref()
-                .map(|q| {
-                    msg["content"]
-                        .as_str()
-                        .unwrap_or("")
-                        .to_lowercase()
-                        .contains(&q.to_lowercase())
-                })
-                .unwrap_or(true);
+            let matches = matches_query(options, &msg) && options.pinned.allows(is_pinned(&msg));
 
             if !options.simulation && matches {
-                let del_url = format!(
-                    "https://discord.com/api/v9/channels/{}/messages/{}",
-                    channel_id, msg_id
-                );
-                if api
-                    .send_request_json(
-                        reqwest::Method::DELETE,
-                        &del_url,
-                        None,
-                        token,
-                        is_bearer,
-                        None,
-                    )
-                    .await
-                    .is_ok()
+                if let Ok(ids) = remove_message(
+                    api,
+                    options,
+                    &mut queue,
+                    channel_id,
+                    msg_id,
+                    Some(&msg),
+                    is_pinned(&msg),
+                    token,
+                    is_bearer,
+                )
+                .await
+                    && !ids.is_empty()
                 {
-                    deleted += 1;
-                    let _ = window.emit(
-                        "deletion_progress",
-                        serde_json::json!({
-                            "current": index + 1,
-                            "total": 0,
-                            "id": channel_id,
-                            "deleted_count": deleted,
-                            "status": "purging_scan"
-                        }),
-                    );
+                    deleted += ids.len();
+                    progress(deleted, "purging_scan");
                 }
             } else if matches {
                 deleted += 1;
@@ -275,5 +822,11 @@ async fn process_channel_task(
         }
     }
 
+    let flushed = queue.flush(api, channel_id, token, is_bearer).await;
+    if !flushed.is_empty() {
+        deleted += flushed.len();
+        progress(deleted, "purging_scan");
+    }
+
     Ok(deleted)
 }
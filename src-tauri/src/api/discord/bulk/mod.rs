@@ -2,8 +2,13 @@
 
 pub mod guilds;
 pub mod messages;
+pub mod package;
+pub mod permissions;
+pub mod reactions;
 pub mod relationships;
 
 pub use guilds::*;
 pub use messages::*;
+pub use package::*;
+pub use reactions::*;
 pub use relationships::*;
@@ -0,0 +1,433 @@
+// src-tauri/src/api/discord/bulk/package.rs
+
+use super::messages::UNKNOWN_MESSAGE_CODE;
+use crate::api::discord::gdpr::{DiscoveredMessage, GdprDiscovery};
+use crate::api::rate_limiter::ApiHandle;
+use crate::auth::types::DiscordError;
+use crate::core::cache::CacheManager;
+use crate::core::error::AppError;
+use crate::core::logger::Logger;
+use crate::core::op_manager::{OperationManager, OperationState};
+use crate::core::vault::Vault;
+use std::collections::HashSet;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+#[derive(serde::Deserialize, Clone, Default)]
+pub struct PackagePurgeOptions {
+    /// Restrict the purge to these channels; all discovered channels when empty.
+    #[serde(default, alias = "channelIds")]
+    pub channel_ids: Vec<String>,
+    #[serde(alias = "startTime")]
+    pub start_time: Option<u64>,
+    #[serde(alias = "endTime")]
+    pub end_time: Option<u64>,
+    #[serde(default)]
+    pub simulation: bool,
+    /// Closed DMs the user agreed to reopen (see `plan_dm_reopen`).
+    #[serde(default, alias = "reopenDms")]
+    pub reopen_dms: Vec<String>,
+    /// Close reopened DMs again once their messages are purged.
+    #[serde(default, alias = "closeReopened")]
+    pub close_reopened: bool,
+}
+
+/// A DM from the package that is no longer open and would be reopened for the purge.
+#[derive(serde::Serialize, Debug)]
+pub struct DmReopenCandidate {
+    pub channel_id: String,
+    pub recipient_id: String,
+    pub name: Option<String>,
+    pub message_count: usize,
+}
+
+/// Outcome of a purge for a single channel listed in the data package.
+#[derive(serde::Serialize, Default, Debug)]
+pub struct ChannelPurgeReport {
+    pub channel_id: String,
+    pub deleted: Vec<String>,
+    /// Targets a simulation would have deleted; nothing was sent to Discord.
+    pub simulated: Vec<String>,
+    /// Discord no longer knows the message (deleted earlier or by a moderator).
+    pub already_gone: Vec<String>,
+    /// The channel cannot be reached any more (left guild, closed DM, lost access).
+    pub inaccessible: Vec<String>,
+    pub failed: Vec<String>,
+    pub reopened: bool,
+    pub closed_again: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct PackagePurgeReport {
+    pub channels: Vec<ChannelPurgeReport>,
+    pub total_deleted: usize,
+    pub total_simulated: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeleteOutcome {
+    Deleted,
+    AlreadyGone,
+    Inaccessible,
+    Failed,
+}
+
+impl DeleteOutcome {
+    pub fn from_result<T>(result: &Result<T, AppError>) -> Self {
+        match result {
+            Ok(_) => DeleteOutcome::Deleted,
+            Err(e) if e.discord_code == Some(UNKNOWN_MESSAGE_CODE) => DeleteOutcome::AlreadyGone,
+            Err(e)
+                if matches!(
+                    e.semantic_error,
+                    Some(
+                        DiscordError::UnknownChannel
+                            | DiscordError::UnknownGuild
+                            | DiscordError::MissingAccess
+                    )
+                ) =>
+            {
+                DeleteOutcome::Inaccessible
+            }
+            Err(_) => DeleteOutcome::Failed,
+        }
+    }
+}
+
+/// Selects the package messages that fall inside the requested time window.
+pub fn select_targets(
+    messages: &[DiscoveredMessage],
+    options: &PackagePurgeOptions,
+) -> Vec<String> {
+    messages
+        .iter()
+        .filter(|m| options.start_time.is_none_or(|t| m.timestamp >= t as i64))
+        .filter(|m| options.end_time.is_none_or(|t| m.timestamp <= t as i64))
+        .map(|m| m.id.clone())
+        .collect()
+}
+
+/// The other participant of a one-to-one DM.
+pub fn dm_recipient(recipients: &[String], own_id: &str) -> Option<String> {
+    recipients.iter().find(|r| *r != own_id).cloned()
+}
+
+/// Lists package DMs that are no longer open, so the user can consent to reopening them.
+#[tauri::command]
+pub async fn plan_dm_reopen(
+    app_handle: AppHandle,
+    discovery: GdprDiscovery,
+) -> Result<Vec<DmReopenCandidate>, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    if identity.is_oauth {
+        return Err(AppError::new("DMs restricted", "dm_restricted"));
+    }
+    let api_handle = app_handle.state::<ApiHandle>();
+    let json = api_handle
+        .send_request_json(
+            reqwest::Method::GET,
+            "https://discord.com/api/v9/users/@me/channels",
+            None,
+            &identity.token,
+            false,
+            None,
+        )
+        .await?;
+    let open: HashSet<&str> = json
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|c| c["id"].as_str()).collect())
+        .unwrap_or_default();
+
+    let mut candidates: Vec<DmReopenCandidate> = discovery
+        .dm_recipients
+        .iter()
+        .filter(|(channel_id, _)| !open.contains(channel_id.as_str()))
+        .filter_map(|(channel_id, recipients)| {
+            Some(DmReopenCandidate {
+                channel_id: channel_id.clone(),
+                recipient_id: dm_recipient(recipients, &identity.id)?,
+                name: discovery.channel_names.get(channel_id).cloned(),
+                message_count: discovery.messages.get(channel_id).map_or(0, |m| m.len()),
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.message_count.cmp(&a.message_count));
+
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[OP] {} closed DMs from the package can be reopened",
+            candidates.len()
+        ),
+        None,
+    );
+    Ok(candidates)
+}
+
+/// Deletes the message IDs listed in a data package directly, without scanning
+/// channel history, and reports what happened to each one.
+#[tauri::command]
+pub async fn purge_package_messages(
+    app_handle: AppHandle,
+    window: Window,
+    discovery: GdprDiscovery,
+    options: PackagePurgeOptions,
+) -> Result<PackagePurgeReport, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    if discovery.owner_id.as_deref() != Some(identity.id.as_str()) {
+        return Err(AppError::new(
+            "This data package belongs to a different account than the active identity.",
+            "package_owner_mismatch",
+        ));
+    }
+    // DMs cannot be opened with OAuth tokens, matching `plan_dm_reopen`.
+    if identity.is_oauth && !options.reopen_dms.is_empty() {
+        return Err(AppError::new("DMs restricted", "dm_restricted"));
+    }
+    let api_handle = app_handle.state::<ApiHandle>().inner();
+    let op_manager = app_handle.state::<OperationManager>().inner();
+    op_manager.state.prepare();
+    op_manager.state.is_running.store(true, Ordering::SeqCst);
+
+    let channels: Vec<(String, Vec<String>)> = discovery
+        .messages
+        .iter()
+        .filter(|(id, _)| options.channel_ids.is_empty() || options.channel_ids.contains(id))
+        .map(|(id, messages)| (id.clone(), select_targets(messages, &options)))
+        .filter(|(_, targets)| !targets.is_empty())
+        .collect();
+
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[OP] Package-driven purge started: {} messages across {} channels",
+            channels.iter().map(|(_, t)| t.len()).sum::<usize>(),
+            channels.len()
+        ),
+        None,
+    );
+
+    // Channels are purged one after another; a package can list thousands of them.
+    let mut report = PackagePurgeReport::default();
+    for (i, (channel_id, targets)) in channels.into_iter().enumerate() {
+        if op_manager.state.should_abort.load(Ordering::SeqCst) {
+            break;
+        }
+        let channel = if options.simulation {
+            ChannelPurgeReport {
+                channel_id,
+                simulated: targets,
+                ..Default::default()
+            }
+        } else {
+            let reopen_recipient = if options.reopen_dms.contains(&channel_id) {
+                discovery
+                    .dm_recipients
+                    .get(&channel_id)
+                    .and_then(|r| dm_recipient(r, &identity.id))
+            } else {
+                None
+            };
+            let reopened = match &reopen_recipient {
+                Some(recipient_id) => {
+                    reopen_dm(
+                        &app_handle,
+                        api_handle,
+                        &identity.token,
+                        identity.is_oauth,
+                        &channel_id,
+                        recipient_id,
+                    )
+                    .await
+                }
+                None => false,
+            };
+            let mut channel = purge_channel(
+                &app_handle,
+                &window,
+                &channel_id,
+                i,
+                targets,
+                &identity.token,
+                identity.is_oauth,
+                &identity.id,
+                api_handle,
+                &op_manager.state,
+            )
+            .await;
+            channel.reopened = reopened;
+            if reopened && options.close_reopened {
+                channel.closed_again = api_handle
+                    .send_request_json(
+                        reqwest::Method::DELETE,
+                        &format!("https://discord.com/api/v9/channels/{}", channel_id),
+                        None,
+                        &identity.token,
+                        identity.is_oauth,
+                        None,
+                    )
+                    .await
+                    .is_ok();
+            }
+            channel
+        };
+        report.total_deleted += channel.deleted.len();
+        report.total_simulated += channel.simulated.len();
+        report.channels.push(channel);
+    }
+    report
+        .channels
+        .sort_by(|a, b| a.channel_id.cmp(&b.channel_id));
+
+    op_manager.state.reset();
+    let _ = window.emit("deletion_complete", report.total_deleted);
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[OP] Package-driven purge finished. Total nullified: {}, simulated: {}",
+            report.total_deleted, report.total_simulated
+        ),
+        None,
+    );
+    Ok(report)
+}
+
+/// Reopens a closed one-to-one DM so its messages become reachable again.
+async fn reopen_dm(
+    app: &AppHandle,
+    api: &ApiHandle,
+    token: &str,
+    is_bearer: bool,
+    channel_id: &str,
+    recipient_id: &str,
+) -> bool {
+    match api
+        .send_request_json(
+            reqwest::Method::POST,
+            "https://discord.com/api/v9/users/@me/channels",
+            Some(serde_json::json!({ "recipient_id": recipient_id })),
+            token,
+            is_bearer,
+            None,
+        )
+        .await
+    {
+        Ok(channel) => {
+            // One-to-one DM channels keep their ID for the lifetime of the pair.
+            if channel["id"].as_str() != Some(channel_id) {
+                Logger::warn(
+                    app,
+                    &format!(
+                        "[OP] Reopened DM with {} has unexpected ID {:?}",
+                        recipient_id,
+                        channel["id"].as_str()
+                    ),
+                    None,
+                );
+            }
+            Logger::info(app, &format!("[OP] Reopened DM {}", channel_id), None);
+            true
+        }
+        Err(e) => {
+            Logger::warn(
+                app,
+                &format!("[OP] Could not reopen DM {}: {}", channel_id, e),
+                None,
+            );
+            false
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn purge_channel(
+    app: &AppHandle,
+    window: &Window,
+    channel_id: &str,
+    index: usize,
+    targets: Vec<String>,
+    token: &str,
+    is_bearer: bool,
+    user_id: &str,
+    api: &ApiHandle,
+    state: &OperationState,
+) -> ChannelPurgeReport {
+    let mut report = ChannelPurgeReport {
+        channel_id: channel_id.to_string(),
+        ..Default::default()
+    };
+    let total = targets.len();
+    let mut remaining = targets.into_iter();
+
+    while let Some(msg_id) = remaining.next() {
+        state.wait_if_paused().await;
+        if state.should_abort.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let del_url = format!(
+            "https://discord.com/api/v9/channels/{}/messages/{}",
+            channel_id, msg_id
+        );
+        let result = api
+            .send_request_json(
+                reqwest::Method::DELETE,
+                &del_url,
+                None,
+                token,
+                is_bearer,
+                None,
+            )
+            .await;
+
+        match DeleteOutcome::from_result(&result) {
+            DeleteOutcome::Deleted => report.deleted.push(msg_id),
+            DeleteOutcome::AlreadyGone => report.already_gone.push(msg_id),
+            DeleteOutcome::Inaccessible => {
+                // Every other message in this channel is equally unreachable.
+                report.inaccessible.push(msg_id);
+                report.inaccessible.extend(remaining.by_ref());
+                Logger::warn(
+                    app,
+                    &format!(
+                        "[OP] Channel {} is no longer accessible, skipping {} messages",
+                        channel_id,
+                        report.inaccessible.len()
+                    ),
+                    None,
+                );
+            }
+            DeleteOutcome::Failed => {
+                if let Err(e) = &result {
+                    Logger::warn(
+                        app,
+                        &format!("[OP] Failed to delete {} in {}: {}", msg_id, channel_id, e),
+                        None,
+                    );
+                }
+                report.failed.push(msg_id);
+            }
+        }
+
+        let _ = window.emit(
+            "deletion_progress",
+            serde_json::json!({
+                "current": index + 1,
+                "total": total,
+                "id": channel_id,
+                "deleted_count": report.deleted.len(),
+                "status": "purging_package"
+            }),
+        );
+    }
+
+    // Keep the local index consistent with what is now gone on Discord's side.
+    let gone: Vec<String> = report
+        .deleted
+        .iter()
+        .chain(&report.already_gone)
+        .cloned()
+        .collect();
+    let _ = CacheManager::mark_messages_deleted(app, user_id, &gone);
+    report
+}
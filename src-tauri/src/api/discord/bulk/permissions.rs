@@ -0,0 +1,201 @@
+// src-tauri/src/api/discord/bulk/permissions.rs
+
+use crate::api::rate_limiter::ApiHandle;
+use crate::core::error::AppError;
+use serde_json::Value;
+
+pub const ADMINISTRATOR: u64 = 1 << 3;
+pub const MANAGE_MESSAGES: u64 = 1 << 13;
+
+/// Milliseconds between the Unix epoch and the first Discord snowflake.
+const DISCORD_EPOCH_MS: i64 = 1_420_070_400_000;
+
+/// Discord rejects bulk deletes of messages older than two weeks. An hour of margin
+/// covers the time a batch waits in the rate limiter.
+pub const BULK_DELETE_MAX_AGE_MS: i64 = 14 * 24 * 3600 * 1000 - 3600 * 1000;
+
+/// Most messages per bulk-delete request.
+pub const BULK_DELETE_LIMIT: usize = 100;
+
+const THREAD_TYPES: [u64; 3] = [10, 11, 12];
+
+/// Creation time of a snowflake in Unix milliseconds.
+pub fn snowflake_millis(id: &str) -> Option<i64> {
+    let id: u64 = id.parse().ok()?;
+    Some((id >> 22) as i64 + DISCORD_EPOCH_MS)
+}
+
+/// Whether a message is young enough for the bulk-delete endpoint at `now_ms`.
+pub fn bulk_deletable(id: &str, now_ms: i64) -> bool {
+    snowflake_millis(id).is_some_and(|created| now_ms - created < BULK_DELETE_MAX_AGE_MS)
+}
+
+fn permission_bits(value: &Value) -> u64 {
+    value
+        .as_str()
+        .and_then(|p| p.parse().ok())
+        .or_else(|| value.as_u64())
+        .unwrap_or(0)
+}
+
+/// Guild-wide permissions: `@everyone` (whose role ID is the guild ID) plus every
+/// role the member holds. Owners and administrators get everything.
+pub fn base_permissions(
+    guild_id: &str,
+    is_owner: bool,
+    roles: &[Value],
+    member_roles: &[String],
+) -> u64 {
+    if is_owner {
+        return u64::MAX;
+    }
+    let perms = roles
+        .iter()
+        .filter(|r| {
+            r["id"]
+                .as_str()
+                .is_some_and(|id| id == guild_id || member_roles.iter().any(|m| m == id))
+        })
+        .fold(0, |acc, r| acc | permission_bits(&r["permissions"]));
+    if perms & ADMINISTRATOR != 0 {
+        return u64::MAX;
+    }
+    perms
+}
+
+/// Applies channel overwrites in Discord's order: `@everyone`, then the member's roles
+/// together, then the member.
+pub fn channel_permissions(
+    base: u64,
+    guild_id: &str,
+    user_id: &str,
+    member_roles: &[String],
+    overwrites: &[Value],
+) -> u64 {
+    if base & ADMINISTRATOR != 0 {
+        return u64::MAX;
+    }
+    let apply = |perms: u64, allow: u64, deny: u64| (perms & !deny) | allow;
+    let find = |id: &str| overwrites.iter().find(|o| o["id"].as_str() == Some(id));
+
+    let mut perms = base;
+    if let Some(everyone) = find(guild_id) {
+        perms = apply(
+            perms,
+            permission_bits(&everyone["allow"]),
+            permission_bits(&everyone["deny"]),
+        );
+    }
+    let (allow, deny) = overwrites
+        .iter()
+        .filter(|o| {
+            o["id"]
+                .as_str()
+                .is_some_and(|id| id != guild_id && member_roles.iter().any(|m| m == id))
+        })
+        .fold((0, 0), |(allow, deny), o| {
+            (
+                allow | permission_bits(&o["allow"]),
+                deny | permission_bits(&o["deny"]),
+            )
+        });
+    perms = apply(perms, allow, deny);
+    if let Some(member) = find(user_id) {
+        perms = apply(
+            perms,
+            permission_bits(&member["allow"]),
+            permission_bits(&member["deny"]),
+        );
+    }
+    perms
+}
+
+async fn get_json(
+    api: &ApiHandle,
+    url: &str,
+    token: &str,
+    is_bearer: bool,
+) -> Result<Value, AppError> {
+    api.send_request_json(reqwest::Method::GET, url, None, token, is_bearer, None)
+        .await
+}
+
+/// Whether we hold Manage Messages in a guild channel. Threads use their parent's
+/// overwrites. DMs, and any lookup failure, count as no.
+pub async fn can_manage_messages(
+    api: &ApiHandle,
+    channel_id: &str,
+    user_id: &str,
+    token: &str,
+    is_bearer: bool,
+) -> bool {
+    let lookup = async {
+        let base = "https://discord.com/api/v9";
+        let mut channel = get_json(
+            api,
+            &format!("{}/channels/{}", base, channel_id),
+            token,
+            is_bearer,
+        )
+        .await?;
+        let Some(guild_id) = channel["guild_id"].as_str().map(|s| s.to_string()) else {
+            return Ok(false);
+        };
+        if channel["type"]
+            .as_u64()
+            .is_some_and(|t| THREAD_TYPES.contains(&t))
+            && let Some(parent_id) = channel["parent_id"].as_str()
+        {
+            channel = get_json(
+                api,
+                &format!("{}/channels/{}", base, parent_id),
+                token,
+                is_bearer,
+            )
+            .await?;
+        }
+
+        let guild = get_json(
+            api,
+            &format!("{}/guilds/{}", base, guild_id),
+            token,
+            is_bearer,
+        )
+        .await?;
+        let member = get_json(
+            api,
+            &format!("{}/users/@me/guilds/{}/member", base, guild_id),
+            token,
+            is_bearer,
+        )
+        .await?;
+        let member_roles: Vec<String> = member["roles"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.as_str().map(|s| s.to_string()))
+            .collect();
+
+        let base_perms = base_permissions(
+            &guild_id,
+            guild["owner_id"].as_str() == Some(user_id),
+            guild["roles"]
+                .as_array()
+                .map(|r| r.as_slice())
+                .unwrap_or_default(),
+            &member_roles,
+        );
+        let perms = channel_permissions(
+            base_perms,
+            &guild_id,
+            user_id,
+            &member_roles,
+            channel["permission_overwrites"]
+                .as_array()
+                .map(|o| o.as_slice())
+                .unwrap_or_default(),
+        );
+        Ok::<bool, AppError>(perms & MANAGE_MESSAGES != 0)
+    };
+    lookup.await.unwrap_or(false)
+}
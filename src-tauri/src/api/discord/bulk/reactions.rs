@@ -0,0 +1,290 @@
+// src-tauri/src/api/discord/bulk/reactions.rs
+
+use super::messages::{DEFAULT_CACHE_MAX_AGE_SECS, UNKNOWN_MESSAGE_CODE};
+use crate::api::discord::message_fetcher::MessagePager;
+use crate::api::rate_limiter::ApiHandle;
+use crate::core::cache::{CacheManager, reaction_key};
+use crate::core::error::AppError;
+use crate::core::logger::Logger;
+use crate::core::op_manager::{OperationManager, OperationState};
+use crate::core::vault::Vault;
+use serde_json::Value;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+/// Discord's "Unknown Emoji" code; the reaction can no longer exist.
+const UNKNOWN_EMOJI_CODE: u32 = 10014;
+
+#[derive(serde::Deserialize, Clone, Default)]
+pub struct ReactionPurgeOptions {
+    #[serde(alias = "channelIds")]
+    pub channel_ids: Vec<String>,
+    /// Emoji to remove: unicode characters, custom emoji names or IDs, `name:id` or
+    /// `<:name:id>`. Empty removes all of our reactions.
+    #[serde(default)]
+    pub emojis: Vec<String>,
+    #[serde(default)]
+    pub simulation: bool,
+    /// Take targets from the local index for channels with a fresh sync cursor.
+    #[serde(default, alias = "useCache")]
+    pub use_cache: bool,
+    /// Maximum age in seconds of a sync cursor before the channel is rescanned.
+    #[serde(default, alias = "cacheMaxAge")]
+    pub cache_max_age: Option<u64>,
+}
+
+/// Whether the reaction `key` is selected by `filter`; an empty filter selects everything.
+pub fn emoji_matches(key: &str, filter: &[String]) -> bool {
+    filter.is_empty()
+        || filter.iter().any(|f| {
+            let f = f.trim().trim_start_matches('<').trim_end_matches('>');
+            let f = f.strip_prefix("a:").unwrap_or(f).trim_matches(':');
+            key == f
+                || key
+                    .split_once(':')
+                    .is_some_and(|(name, id)| name == f || id == f)
+        })
+}
+
+/// Keys of the reactions on `msg` that we placed and the filter selects.
+pub fn own_reactions(msg: &Value, filter: &[String]) -> Vec<String> {
+    msg["reactions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|r| r["me"].as_bool() == Some(true))
+        .filter_map(|r| reaction_key(&r["emoji"]))
+        .filter(|key| emoji_matches(key, filter))
+        .collect()
+}
+
+/// Removes our reactions from messages without touching the messages themselves.
+#[tauri::command]
+pub async fn bulk_remove_reactions(
+    app_handle: AppHandle,
+    window: Window,
+    options: ReactionPurgeOptions,
+) -> Result<(), AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    let token = identity.token;
+    let is_bearer = identity.is_oauth;
+    let user_id = identity.id;
+
+    let api_handle = app_handle.state::<ApiHandle>().inner();
+    let op_manager = app_handle.state::<OperationManager>().inner();
+    op_manager.state.prepare();
+    op_manager.state.is_running.store(true, Ordering::SeqCst);
+
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[OP] Reaction removal started for {} channels",
+            options.channel_ids.len()
+        ),
+        None,
+    );
+
+    let mut total_removed = 0;
+    for (i, channel_id) in options.channel_ids.iter().enumerate() {
+        if op_manager.state.should_abort.load(Ordering::SeqCst) {
+            break;
+        }
+        match process_reaction_channel(
+            &app_handle,
+            &window,
+            &options,
+            channel_id,
+            i,
+            &token,
+            is_bearer,
+            &user_id,
+            api_handle,
+            &op_manager.state,
+        )
+        .await
+        {
+            Ok(count) => total_removed += count,
+            Err(e) => Logger::warn(
+                &app_handle,
+                &format!("[OP] Reaction removal failed in {}: {}", channel_id, e),
+                None,
+            ),
+        }
+    }
+
+    op_manager.state.reset();
+    let _ = window.emit("reaction_complete", total_removed);
+    Logger::info(
+        &app_handle,
+        &format!("[OP] Reaction removal finished. Removed: {}", total_removed),
+        None,
+    );
+    Ok(())
+}
+
+/// `(message_id, emoji)` pairs from the local index, or `None` when the channel has
+/// not been synced recently enough to trust it.
+fn cached_reactions(
+    app: &AppHandle,
+    options: &ReactionPurgeOptions,
+    channel_id: &str,
+    user_id: &str,
+) -> Option<Vec<(String, String)>> {
+    let cursor = CacheManager::get_sync_cursor(app, user_id, channel_id)
+        .ok()
+        .flatten()?;
+    let max_age = options.cache_max_age.unwrap_or(DEFAULT_CACHE_MAX_AGE_SECS) as i64;
+    if chrono::Utc::now().timestamp() - cursor.last_scanned > max_age {
+        return None;
+    }
+    let reactions = CacheManager::query_own_reactions(app, user_id, channel_id).ok()?;
+    Some(
+        reactions
+            .into_iter()
+            .filter(|(_, emoji)| emoji_matches(emoji, &options.emojis))
+            .collect(),
+    )
+}
+
+/// Removes one reaction. A reaction whose message or emoji no longer exists counts as
+/// removed, so the cache forgets it either way.
+async fn remove_reaction(
+    api: &ApiHandle,
+    channel_id: &str,
+    message_id: &str,
+    emoji: &str,
+    token: &str,
+    is_bearer: bool,
+) -> Result<(), AppError> {
+    let url = format!(
+        "https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me",
+        channel_id,
+        message_id,
+        urlencoding::encode(emoji)
+    );
+    match api
+        .send_request_json(reqwest::Method::DELETE, &url, None, token, is_bearer, None)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e)
+            if e.discord_code == Some(UNKNOWN_MESSAGE_CODE)
+                || e.discord_code == Some(UNKNOWN_EMOJI_CODE) =>
+        {
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_reaction_channel(
+    app: &AppHandle,
+    window: &Window,
+    options: &ReactionPurgeOptions,
+    channel_id: &str,
+    index: usize,
+    token: &str,
+    is_bearer: bool,
+    user_id: &str,
+    api: &ApiHandle,
+    state: &OperationState,
+) -> Result<usize, AppError> {
+    let cached = if options.use_cache {
+        cached_reactions(app, options, channel_id, user_id)
+    } else {
+        None
+    };
+    let scanning = cached.is_none();
+    let status = if scanning {
+        "removing_scan"
+    } else {
+        "removing_cached"
+    };
+
+    let mut removed: Vec<(String, String)> = Vec::new();
+    let mut found = 0;
+    let mut pending = cached.unwrap_or_default();
+    let mut pager = MessagePager::new(channel_id);
+
+    'pages: loop {
+        if scanning {
+            let page = match pager.next_page(api, token, is_bearer).await {
+                Ok(Some(page)) => page,
+                Ok(None) => break,
+                Err(e) => {
+                    Logger::warn(
+                        app,
+                        &format!("[OP] Reaction scan of {} stopped: {}", channel_id, e),
+                        None,
+                    );
+                    break;
+                }
+            };
+            pending = page
+                .iter()
+                .flat_map(|msg| {
+                    let id = msg["id"].as_str().unwrap_or_default().to_string();
+                    own_reactions(msg, &options.emojis)
+                        .into_iter()
+                        .map(move |emoji| (id.clone(), emoji))
+                })
+                .collect();
+        }
+
+        for (message_id, emoji) in pending.drain(..) {
+            state.wait_if_paused().await;
+            if state.should_abort.load(Ordering::SeqCst) {
+                break 'pages;
+            }
+            found += 1;
+            if options.simulation {
+                continue;
+            }
+            match remove_reaction(api, channel_id, &message_id, &emoji, token, is_bearer).await {
+                Ok(()) => {
+                    removed.push((message_id, emoji));
+                    let _ = window.emit(
+                        "reaction_progress",
+                        serde_json::json!({
+                            "current": index + 1,
+                            "total": options.channel_ids.len(),
+                            "id": channel_id,
+                            "removed_count": removed.len(),
+                            "status": status
+                        }),
+                    );
+                }
+                Err(e) => Logger::warn(
+                    app,
+                    &format!(
+                        "[OP] Failed to remove {} from {} in {}: {}",
+                        emoji, message_id, channel_id, e
+                    ),
+                    None,
+                ),
+            }
+        }
+
+        if !scanning {
+            break;
+        }
+    }
+
+    let _ = CacheManager::forget_own_reactions(app, user_id, &removed);
+    Logger::info(
+        app,
+        &format!(
+            "[OP] {} of our reactions in {}, {} removed",
+            found,
+            channel_id,
+            removed.len()
+        ),
+        None,
+    );
+    Ok(if options.simulation {
+        found
+    } else {
+        removed.len()
+    })
+}
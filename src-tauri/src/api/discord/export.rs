@@ -1,15 +1,25 @@
 // src-tauri/src/api/discord/export.rs
 
 pub mod archive;
+pub mod attachments;
+pub mod cdn;
+pub mod chat;
+pub mod csv;
+pub mod format;
 pub mod harvest;
 pub mod html;
+pub mod jsonl;
+pub mod markdown;
+pub mod mbox;
+pub mod render;
+pub mod text;
 pub mod types;
 
 #[allow(unused_imports)]
 pub use archive::*;
 #[allow(unused_imports)]
-pub use harvest::*;
+pub use chat::*;
 #[allow(unused_imports)]
-pub use html::*;
+pub use harvest::*;
 #[allow(unused_imports)]
 pub use types::*;
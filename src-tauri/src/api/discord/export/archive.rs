@@ -1,94 +1,485 @@
+use super::format::{ChannelInfo, ChannelWriter};
+use super::html::HtmlFormat;
+use super::jsonl::JsonlFormat;
+use super::render::RenderContext;
 use super::types::ExportProgress;
-use crate::api::discord::message_fetcher::fetch_all_messages;
+use crate::api::discord::message_fetcher::MessagePager;
 use crate::api::rate_limiter::handle::ApiHandle;
 use crate::core::error::AppError;
+use crate::core::forensics::container::{StagingDir, check_passphrase, seal_file, sealed_path};
+use crate::core::logger::Logger;
 use crate::core::op_manager::OperationManager;
 use crate::core::vault::Vault;
-use std::fs::File;
-use std::io::Write;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Emitter, Manager, Window};
 use zip::write::{SimpleFileOptions, ZipWriter};
 
+/// Text, voice, announcement, thread and stage channels carry messages.
+const MESSAGE_CHANNEL_TYPES: [u64; 7] = [0, 2, 5, 10, 11, 12, 13];
+
+/// Text, announcement, forum and media channels can own threads.
+const THREAD_PARENT_TYPES: [u64; 4] = [0, 5, 15, 16];
+
+const MEMBER_PAGE_SIZE: usize = 1000;
+
+pub fn is_message_channel(kind: u64) -> bool {
+    MESSAGE_CHANNEL_TYPES.contains(&kind)
+}
+
+pub fn can_have_threads(kind: u64) -> bool {
+    THREAD_PARENT_TYPES.contains(&kind)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchivedChannel {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: u64,
+    pub parent_id: Option<String>,
+    pub messages: usize,
+    pub pins: usize,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkippedChannel {
+    pub id: String,
+    pub name: String,
+    pub reason: String,
+}
+
+/// `manifest.json` at the root of a guild archive.
+#[derive(Debug, Serialize)]
+pub struct ArchiveManifest {
+    pub guild_id: String,
+    pub guild_name: String,
+    pub created_at: String,
+    pub mine_only: bool,
+    /// `member_list` when the member endpoint was readable, else `message_authors`.
+    pub member_source: String,
+    /// Set when the archive was aborted before every channel was read.
+    pub incomplete: bool,
+    pub channels: Vec<ArchivedChannel>,
+    pub skipped: Vec<SkippedChannel>,
+}
+
+async fn get_json(
+    api_handle: &ApiHandle,
+    url: &str,
+    token: &str,
+    is_bearer: bool,
+) -> Result<Value, AppError> {
+    api_handle
+        .send_request_json(reqwest::Method::GET, url, None, token, is_bearer, None)
+        .await
+}
+
+/// Active threads plus public and joined private archived threads of every parent.
+/// Listings the account may not read are skipped.
+async fn list_threads(
+    api_handle: &ApiHandle,
+    guild_id: &str,
+    channels: &[Value],
+    token: &str,
+    is_bearer: bool,
+) -> Vec<Value> {
+    let mut threads: BTreeMap<String, Value> = BTreeMap::new();
+    let mut add = |list: &Value| {
+        for thread in list["threads"].as_array().into_iter().flatten() {
+            if let Some(id) = thread["id"].as_str() {
+                threads.insert(id.to_string(), thread.clone());
+            }
+        }
+    };
+
+    let active_url = format!(
+        "https://discord.com/api/v9/guilds/{}/threads/active",
+        guild_id
+    );
+    if let Ok(active) = get_json(api_handle, &active_url, token, is_bearer).await {
+        add(&active);
+    }
+
+    for parent in channels
+        .iter()
+        .filter(|c| can_have_threads(c["type"].as_u64().unwrap_or(0)))
+    {
+        let parent_id = parent["id"].as_str().unwrap_or_default();
+        // Public archives page by archive timestamp, joined private ones by thread ID.
+        for (listing, by_timestamp) in [
+            ("threads/archived/public", true),
+            ("users/@me/threads/archived/private", false),
+        ] {
+            let mut before: Option<String> = None;
+            loop {
+                let mut url = format!(
+                    "https://discord.com/api/v9/channels/{}/{}?limit=100",
+                    parent_id, listing
+                );
+                if let Some(before) = &before {
+                    url.push_str(&format!("&before={}", before));
+                }
+                let Ok(page) = get_json(api_handle, &url, token, is_bearer).await else {
+                    break;
+                };
+                add(&page);
+                let last = page["threads"]
+                    .as_array()
+                    .and_then(|t| t.last())
+                    .and_then(|t| match by_timestamp {
+                        true => t["thread_metadata"]["archive_timestamp"].as_str(),
+                        false => t["id"].as_str(),
+                    });
+                match (page["has_more"].as_bool(), last) {
+                    (Some(true), Some(last)) => before = Some(last.to_string()),
+                    _ => break,
+                }
+            }
+        }
+    }
+    threads.into_values().collect()
+}
+
+/// Pages through the member list. User accounts are usually refused this endpoint.
+async fn list_members(
+    api_handle: &ApiHandle,
+    guild_id: &str,
+    token: &str,
+    is_bearer: bool,
+) -> Result<Vec<Value>, AppError> {
+    let mut members = Vec::new();
+    let mut after = "0".to_string();
+    loop {
+        let url = format!(
+            "https://discord.com/api/v9/guilds/{}/members?limit={}&after={}",
+            guild_id, MEMBER_PAGE_SIZE, after
+        );
+        let page: Vec<Value> =
+            serde_json::from_value(get_json(api_handle, &url, token, is_bearer).await?)?;
+        let count = page.len();
+        if let Some(id) = page.last().and_then(|m| m["user"]["id"].as_str()) {
+            after = id.to_string();
+        }
+        members.extend(page);
+        if count < MEMBER_PAGE_SIZE {
+            return Ok(members);
+        }
+    }
+}
+
+fn zip_json<W: Write + io::Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    value: &impl Serialize,
+    options: SimpleFileOptions,
+) -> Result<(), AppError> {
+    zip.start_file(name, options)?;
+    serde_json::to_writer_pretty(&mut *zip, value)?;
+    Ok(())
+}
+
+/// Moves a staged file into the zip under `channels/`.
+fn zip_staged<W: Write + io::Seek>(
+    zip: &mut ZipWriter<W>,
+    staged: &Path,
+    options: SimpleFileOptions,
+) -> Result<String, AppError> {
+    let name = format!(
+        "channels/{}",
+        staged.file_name().unwrap_or_default().to_string_lossy()
+    );
+    zip.start_file(name.as_str(), options)?;
+    io::copy(&mut File::open(staged)?, zip)?;
+    fs::remove_file(staged)?;
+    Ok(name)
+}
+
+/// Whole-guild archive: every readable channel, thread and forum post as JSONL and
+/// HTML, plus pins, roles, emoji and a member snapshot, in one zip with a manifest.
+/// With `mine_only`, only the current user's messages are kept; with `passphrase`,
+/// the zip is written as an encrypted container next to `output_path`.
 #[tauri::command]
-pub async fn start_guild_user_archive(
+pub async fn start_guild_archive(
     app_handle: AppHandle,
     window: Window,
     guild_id: String,
     output_path: String,
+    mine_only: Option<bool>,
+    passphrase: Option<String>,
 ) -> Result<(), AppError> {
+    let mine_only = mine_only.unwrap_or(false);
+    if let Some(passphrase) = &passphrase {
+        check_passphrase(passphrase)?;
+    }
     let identity = Vault::get_active_identity(&app_handle)?;
-    let token = identity.token.clone();
-    let is_bearer = identity.is_oauth;
-    let current_user_id = identity.id.clone();
-
+    let current_user_id = identity.id;
+    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
     let api_handle = app_handle.state::<ApiHandle>();
     let op_manager = app_handle.state::<OperationManager>();
     op_manager.state.prepare();
 
-    let channels_json = api_handle
-        .send_request_json(
-            reqwest::Method::GET,
-            &format!("https://discord.com/api/v9/guilds/{}/channels", guild_id),
-            None,
+    let guild_url = format!("https://discord.com/api/v9/guilds/{}", guild_id);
+    let guild = get_json(&api_handle, &guild_url, &token, is_bearer).await?;
+    let channels: Vec<Value> = serde_json::from_value(
+        get_json(
+            &api_handle,
+            &format!("{}/channels", guild_url),
             &token,
             is_bearer,
-            None,
         )
-        .await?;
-    let channels: Vec<serde_json::Value> =
-        serde_json::from_value(channels_json).map_err(AppError::from)?;
+        .await?,
+    )?;
+    let roles = get_json(
+        &api_handle,
+        &format!("{}/roles", guild_url),
+        &token,
+        is_bearer,
+    )
+    .await
+    .unwrap_or_default();
+    let emojis = get_json(
+        &api_handle,
+        &format!("{}/emojis", guild_url),
+        &token,
+        is_bearer,
+    )
+    .await
+    .unwrap_or_default();
+    let threads = list_threads(&api_handle, &guild_id, &channels, &token, is_bearer).await;
+    let members = list_members(&api_handle, &guild_id, &token, is_bearer)
+        .await
+        .ok();
+
+    let mut ctx = RenderContext::from_cache(&app_handle, &current_user_id, None);
+    for channel in channels.iter().chain(&threads) {
+        if let (Some(id), Some(name)) = (channel["id"].as_str(), channel["name"].as_str()) {
+            ctx.channels.insert(id.to_string(), name.to_string());
+        }
+    }
+    for member in members.iter().flatten() {
+        if let Some(id) = member["user"]["id"].as_str() {
+            let name = member["nick"]
+                .as_str()
+                .or(member["user"]["global_name"].as_str())
+                .or(member["user"]["username"].as_str())
+                .unwrap_or(id);
+            ctx.users.insert(id.to_string(), name.to_string());
+        }
+    }
+    let ctx = Arc::new(ctx);
 
-    let output_file = File::create(&output_path)
-        .map_err(|e| AppError::new("Zip Creation Error", &e.to_string()))?;
-    let mut zip = ZipWriter::new(output_file);
+    // Channel files are staged in a fresh folder, so leftovers of an earlier run never
+    // end up in this archive; it is removed once dropped.
+    let staging = StagingDir::create("guild_archive")?;
+    // Encrypted archives are zipped into a private staging folder and sealed at the end.
+    let sealed_staging = match passphrase {
+        Some(_) => Some(StagingDir::create("guild_archive_sealed")?),
+        None => None,
+    };
+    let zip_path = match &sealed_staging {
+        Some(staging) => staging
+            .path()
+            .join(format!("guild_archive_{}.zip", guild_id)),
+        None => PathBuf::from(&output_path),
+    };
+    let mut zip = ZipWriter::new(File::create(&zip_path)?);
     let zip_options =
         SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
 
-    let mut log_accumulator = String::new();
-
-    for (i, channel) in channels.iter().enumerate() {
-        let channel_id = channel["id"].as_str().unwrap_or_default();
-        let channel_name = channel["name"].as_str().unwrap_or("unknown");
-        let c_type = channel["type"].as_u64().unwrap_or(0);
-
-        if c_type != 0 && c_type != 2 {
-            continue;
+    let mut manifest = ArchiveManifest {
+        guild_id: guild_id.clone(),
+        guild_name: guild["name"].as_str().unwrap_or("unknown").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        mine_only,
+        member_source: if members.is_some() {
+            "member_list"
+        } else {
+            "message_authors"
         }
+        .to_string(),
+        incomplete: false,
+        channels: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut authors: BTreeMap<String, Value> = BTreeMap::new();
+
+    let targets: Vec<&Value> = channels
+        .iter()
+        .chain(&threads)
+        .filter(|c| is_message_channel(c["type"].as_u64().unwrap_or(0)))
+        .collect();
 
+    for (i, channel) in targets.iter().enumerate() {
+        let channel_id = channel["id"].as_str().unwrap_or_default();
+        let name = channel["name"].as_str().unwrap_or("unknown").to_string();
         let _ = window.emit(
             "export_progress",
             ExportProgress {
                 current: i + 1,
-                total: channels.len(),
+                total: targets.len(),
                 channel_id: channel_id.to_string(),
                 status: "archiving_channel".to_string(),
                 processed_count: 0,
             },
         );
 
-        let messages = fetch_all_messages(channel_id, &api_handle, &token, is_bearer).await?;
-
-        for msg in messages {
-            if msg["author"]["id"].as_str() == Some(&current_user_id) {
-                let ts = msg["timestamp"].as_str().unwrap_or("");
-                let content = msg["content"].as_str().unwrap_or("");
-                log_accumulator.push_str(&format!(
-                    "[{}] [#{}]: {}
-",
-                    ts, channel_name, content
-                ));
+        let info = ChannelInfo {
+            id: channel_id.to_string(),
+            name: format!("#{}", name),
+            guild_id: Some(guild_id.clone()),
+        };
+        let mut jsonl = ChannelWriter::create(staging.path(), info.clone(), Box::new(JsonlFormat))?;
+        let mut html = ChannelWriter::create(
+            staging.path(),
+            info,
+            Box::new(HtmlFormat::new(true, ctx.clone())),
+        )?;
+
+        let mut pager = MessagePager::new(channel_id);
+        let mut failure = None;
+        loop {
+            op_manager.state.wait_if_paused().await;
+            if op_manager.state.should_abort.load(Ordering::SeqCst) {
+                manifest.incomplete = true;
+                break;
+            }
+            let page = match pager.next_page(&api_handle, &token, is_bearer).await {
+                Ok(Some(page)) => page,
+                Ok(None) => break,
+                Err(e) => {
+                    failure = Some(e.user_message);
+                    break;
+                }
+            };
+            for msg in page {
+                let author_id = msg["author"]["id"].as_str().unwrap_or_default();
+                if mine_only && author_id != current_user_id {
+                    continue;
+                }
+                authors
+                    .entry(author_id.to_string())
+                    .or_insert_with(|| msg["author"].clone());
+                jsonl.write(&msg)?;
+                html.write(&msg)?;
             }
+            let _ = window.emit(
+                "export_progress",
+                ExportProgress {
+                    current: i + 1,
+                    total: targets.len(),
+                    channel_id: channel_id.to_string(),
+                    status: "archiving_channel".to_string(),
+                    processed_count: jsonl.written,
+                },
+            );
+        }
+        let messages = jsonl.finish()?;
+        html.finish()?;
+
+        let mut staged: Vec<_> = fs::read_dir(staging.path())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        staged.sort();
+
+        if let Some(reason) = failure
+            && messages == 0
+        {
+            // Nothing readable; typically a channel hidden from this account.
+            for path in staged {
+                fs::remove_file(path)?;
+            }
+            manifest.skipped.push(SkippedChannel {
+                id: channel_id.to_string(),
+                name,
+                reason,
+            });
+        } else {
+            let mut files = Vec::new();
+            for path in &staged {
+                files.push(zip_staged(&mut zip, path, zip_options)?);
+            }
+
+            let pins_url = format!("https://discord.com/api/v9/channels/{}/pins", channel_id);
+            let mut pins = get_json(&api_handle, &pins_url, &token, is_bearer)
+                .await
+                .unwrap_or_default();
+            if let Some(list) = pins.as_array_mut() {
+                if mine_only {
+                    list.retain(|m| m["author"]["id"].as_str() == Some(&current_user_id));
+                }
+                if !list.is_empty() {
+                    let pins_name = format!("channels/{}_pins.json", channel_id);
+                    zip_json(&mut zip, &pins_name, &pins, zip_options)?;
+                    files.push(pins_name);
+                }
+            }
+
+            manifest.channels.push(ArchivedChannel {
+                id: channel_id.to_string(),
+                name,
+                kind: channel["type"].as_u64().unwrap_or(0),
+                parent_id: channel["parent_id"].as_str().map(|s| s.to_string()),
+                messages,
+                pins: pins.as_array().map_or(0, |p| p.len()),
+                files,
+            });
+        }
+
+        if manifest.incomplete {
+            break;
         }
     }
 
-    zip.start_file("my_messages.txt", zip_options)
-        .map_err(|e| AppError::new("Zip Error", &e.to_string()))?;
-    zip.write_all(log_accumulator.as_bytes())
-        .map_err(|e| AppError::new("Zip Write Error", &e.to_string()))?;
-    zip.finish()
-        .map_err(|e| AppError::new("Zip Finish Error", &e.to_string()))?;
+    zip_json(&mut zip, "guild.json", &guild, zip_options)?;
+    zip_json(&mut zip, "channels.json", &channels, zip_options)?;
+    zip_json(&mut zip, "threads.json", &threads, zip_options)?;
+    zip_json(&mut zip, "roles.json", &roles, zip_options)?;
+    zip_json(&mut zip, "emojis.json", &emojis, zip_options)?;
+    match &members {
+        Some(members) => zip_json(&mut zip, "members.json", members, zip_options)?,
+        None => {
+            let authors: Vec<&Value> = authors.values().collect();
+            zip_json(&mut zip, "members.json", &authors, zip_options)?
+        }
+    }
+    zip_json(&mut zip, "manifest.json", &manifest, zip_options)?;
+    zip.finish()?;
+    drop(staging);
+    if let Some(passphrase) = &passphrase {
+        seal_file(&zip_path, &sealed_path(Path::new(&output_path)), passphrase)?;
+    }
 
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[EXPORT] Archived {} channels of guild {} ({} skipped{})",
+            manifest.channels.len(),
+            guild_id,
+            manifest.skipped.len(),
+            if manifest.incomplete { ", aborted" } else { "" }
+        ),
+        None,
+    );
     op_manager.state.reset();
     let _ = window.emit("export_complete", ());
     Ok(())
 }
+
+/// Archive of only the current user's messages; kept for existing callers.
+#[tauri::command]
+pub async fn start_guild_user_archive(
+    app_handle: AppHandle,
+    window: Window,
+    guild_id: String,
+    output_path: String,
+) -> Result<(), AppError> {
+    start_guild_archive(app_handle, window, guild_id, output_path, Some(true), None).await
+}
@@ -0,0 +1,338 @@
+use super::markdown::escape_html;
+use crate::core::error::AppError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Thumbnails per attachment index page.
+pub const THUMBNAILS_PER_PAGE: usize = 200;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PARTIAL_DIR: &str = ".partial";
+
+/// A downloaded attachment. `path` is relative to the store root and `/`-separated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredAttachment {
+    pub channel_id: String,
+    pub message_id: String,
+    pub attachment_id: String,
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub size: u64,
+    pub sha256: String,
+    pub path: String,
+}
+
+impl StoredAttachment {
+    pub fn is_image(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_some_and(|t| t.starts_with("image/"))
+    }
+}
+
+/// Keeps attachments under `channel/message_id/attachment_id-filename`. Files with
+/// identical content are written once; later copies point at the first file.
+/// Downloads land in `.partial/` and are renamed into place once verified.
+/// The manifest is reloaded on open so re-runs skip what is already there.
+pub struct AttachmentStore {
+    root: PathBuf,
+    entries: Vec<StoredAttachment>,
+    by_id: HashMap<String, usize>,
+    by_hash: HashMap<String, String>,
+}
+
+/// Replaces characters that are unsafe in file names on any platform.
+pub fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .take(100)
+        .collect();
+    match cleaned.trim_matches('.') {
+        "" => "file".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+impl AttachmentStore {
+    pub fn open(root: &Path) -> Result<Self, AppError> {
+        fs::create_dir_all(root)?;
+        let entries: Vec<StoredAttachment> = fs::read_to_string(root.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        let mut store = Self {
+            root: root.to_path_buf(),
+            entries: Vec::new(),
+            by_id: HashMap::new(),
+            by_hash: HashMap::new(),
+        };
+        for entry in entries {
+            // Entries whose file went missing or was truncated are downloaded again.
+            if fs::metadata(store.root.join(&entry.path)).is_ok_and(|m| m.len() == entry.size) {
+                store.insert(entry);
+            }
+        }
+        Ok(store)
+    }
+
+    fn insert(&mut self, entry: StoredAttachment) {
+        self.by_hash
+            .entry(entry.sha256.clone())
+            .or_insert_with(|| entry.path.clone());
+        self.by_id
+            .insert(entry.attachment_id.clone(), self.entries.len());
+        self.entries.push(entry);
+    }
+
+    pub fn root_path(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn get(&self, attachment_id: &str) -> Option<&StoredAttachment> {
+        self.by_id.get(attachment_id).map(|&i| &self.entries[i])
+    }
+
+    pub fn relative_path(
+        channel_id: &str,
+        message_id: &str,
+        attachment_id: &str,
+        filename: &str,
+    ) -> String {
+        format!(
+            "{}/{}/{}-{}",
+            channel_id,
+            message_id,
+            attachment_id,
+            sanitize_filename(filename)
+        )
+    }
+
+    /// Where an in-progress download of `attachment_id` is kept until it is verified.
+    pub fn partial_path(&self, attachment_id: &str) -> PathBuf {
+        self.root
+            .join(PARTIAL_DIR)
+            .join(format!("{}.part", sanitize_filename(attachment_id)))
+    }
+
+    /// Returns the stored copy of `attachment`, adopting a file an interrupted run left
+    /// at its final path if it has the expected size.
+    pub fn find_existing(
+        &mut self,
+        channel_id: &str,
+        message_id: &str,
+        attachment: &serde_json::Value,
+    ) -> Result<Option<StoredAttachment>, AppError> {
+        let attachment_id = attachment["id"].as_str().unwrap_or_default();
+        if let Some(existing) = self.get(attachment_id) {
+            return Ok(Some(existing.clone()));
+        }
+
+        let filename = attachment["filename"].as_str().unwrap_or("file");
+        let path = Self::relative_path(channel_id, message_id, attachment_id, filename);
+        let full = self.root.join(&path);
+        let Some(size) = attachment["size"].as_u64() else {
+            return Ok(None);
+        };
+        if !fs::metadata(&full).is_ok_and(|m| m.is_file() && m.len() == size) {
+            return Ok(None);
+        }
+
+        let mut hasher = Sha256::new();
+        io::copy(&mut fs::File::open(&full)?, &mut hasher)?;
+        let entry = Self::entry(
+            channel_id,
+            message_id,
+            attachment,
+            size,
+            hex::encode(hasher.finalize()),
+            path,
+        );
+        self.insert(entry.clone());
+        Ok(Some(entry))
+    }
+
+    /// Moves a verified download from its partial path into place. If the same content
+    /// is already stored, the download is dropped and the entry points at that file.
+    pub fn commit(
+        &mut self,
+        channel_id: &str,
+        message_id: &str,
+        attachment: &serde_json::Value,
+        size: u64,
+        sha256: &str,
+    ) -> Result<StoredAttachment, AppError> {
+        let attachment_id = attachment["id"].as_str().unwrap_or_default();
+        let part = self.partial_path(attachment_id);
+        let path = match self.by_hash.get(sha256) {
+            Some(path) => {
+                fs::remove_file(&part)?;
+                path.clone()
+            }
+            None => {
+                let filename = attachment["filename"].as_str().unwrap_or("file");
+                let path = Self::relative_path(channel_id, message_id, attachment_id, filename);
+                let full = self.root.join(&path);
+                if let Some(parent) = full.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::rename(&part, &full)?;
+                path
+            }
+        };
+
+        let entry = Self::entry(
+            channel_id,
+            message_id,
+            attachment,
+            size,
+            sha256.to_string(),
+            path,
+        );
+        self.insert(entry.clone());
+        Ok(entry)
+    }
+
+    fn entry(
+        channel_id: &str,
+        message_id: &str,
+        attachment: &serde_json::Value,
+        size: u64,
+        sha256: String,
+        path: String,
+    ) -> StoredAttachment {
+        StoredAttachment {
+            channel_id: channel_id.to_string(),
+            message_id: message_id.to_string(),
+            attachment_id: attachment["id"].as_str().unwrap_or_default().to_string(),
+            filename: attachment["filename"]
+                .as_str()
+                .unwrap_or("file")
+                .to_string(),
+            content_type: attachment["content_type"].as_str().map(|s| s.to_string()),
+            size,
+            sha256,
+            path,
+        }
+    }
+
+    pub fn save_manifest(&self) -> Result<(), AppError> {
+        fs::write(
+            self.root.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&self.entries)?,
+        )?;
+        Ok(())
+    }
+
+    /// Writes `index.html` listing every channel, and per-channel thumbnail pages
+    /// (`<channel>/index.html`, `<channel>/index_2.html`, ...).
+    pub fn write_index_pages(&self) -> Result<(), AppError> {
+        let mut by_channel: BTreeMap<&str, Vec<&StoredAttachment>> = BTreeMap::new();
+        for entry in &self.entries {
+            by_channel.entry(&entry.channel_id).or_default().push(entry);
+        }
+
+        let mut root_index = page_header("Attachments");
+        root_index.push_str("<ul>");
+        for (channel_id, entries) in &by_channel {
+            root_index.push_str(&format!(
+                "<li><a href=\"{}/index.html\">{}</a> ({} files)</li>",
+                escape_html(channel_id),
+                escape_html(channel_id),
+                entries.len()
+            ));
+
+            // Deduplicated files may all live under other channels' folders.
+            let channel_dir = self.root.join(channel_id);
+            fs::create_dir_all(&channel_dir)?;
+            let pages: Vec<&[&StoredAttachment]> = entries.chunks(THUMBNAILS_PER_PAGE).collect();
+            for (i, chunk) in pages.iter().enumerate() {
+                let mut html = page_header(&format!("Attachments of {}", channel_id));
+                html.push_str(&page_nav(i, pages.len()));
+                html.push_str("<div class='grid'>");
+                for entry in chunk.iter() {
+                    // Paths are relative to the store root, one level up from this page.
+                    let href = escape_html(&format!("../{}", entry.path));
+                    let name = escape_html(&entry.filename);
+                    if entry.is_image() {
+                        html.push_str(&format!(
+                            "<a class='tile' href=\"{}\"><img loading='lazy' src=\"{}\" alt=\"{}\"><span>{}</span></a>",
+                            href, href, name, name
+                        ));
+                    } else {
+                        html.push_str(&format!(
+                            "<a class='tile file' href=\"{}\">📎<span>{}</span></a>",
+                            href, name
+                        ));
+                    }
+                }
+                html.push_str("</div>");
+                html.push_str(&page_nav(i, pages.len()));
+                html.push_str("</body></html>");
+                fs::write(channel_dir.join(index_page_name(i)), html)?;
+            }
+        }
+        root_index.push_str("</ul></body></html>");
+        fs::write(self.root.join("index.html"), root_index)?;
+        Ok(())
+    }
+}
+
+fn index_page_name(page: usize) -> String {
+    if page == 0 {
+        "index.html".to_string()
+    } else {
+        format!("index_{}.html", page + 1)
+    }
+}
+
+fn page_nav(page: usize, total: usize) -> String {
+    let mut nav = String::from("<nav><a href=\"../index.html\">All channels</a>");
+    if page > 0 {
+        nav.push_str(&format!(
+            "<a href=\"{}\">&larr; Previous</a>",
+            index_page_name(page - 1)
+        ));
+    }
+    if page + 1 < total {
+        nav.push_str(&format!(
+            "<a href=\"{}\">Next &rarr;</a>",
+            index_page_name(page + 1)
+        ));
+    }
+    nav.push_str("</nav>");
+    nav
+}
+
+fn page_header(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title><style>
+        body {{ font-family: sans-serif; background: #313338; color: #dbdee1; padding: 20px; }}
+        a {{ color: #00a8fc; }}
+        nav a {{ margin-right: 15px; }}
+        .grid {{ display: flex; flex-wrap: wrap; gap: 10px; margin: 15px 0; }}
+        .tile {{ width: 160px; background: #2b2d31; border-radius: 6px; padding: 6px; text-decoration: none; color: #dbdee1; font-size: 0.75em; word-break: break-all; }}
+        .tile img {{ width: 160px; height: 120px; object-fit: cover; display: block; border-radius: 4px; }}
+        .tile.file {{ font-size: 2em; text-align: center; }}
+        .tile.file span {{ display: block; font-size: 0.4em; }}
+        </style></head><body><h1>{}</h1>",
+        escape_html(title),
+        escape_html(title)
+    )
+}
+
+/// Store root used by chat exports, relative to the export directory.
+pub const EXPORT_ATTACHMENTS_DIR: &str = "attachments";
+
+/// Path of a stored attachment as seen from an export file in the export directory.
+pub fn export_link(entry: &StoredAttachment) -> String {
+    format!("{}/{}", EXPORT_ATTACHMENTS_DIR, entry.path)
+}
@@ -0,0 +1,378 @@
+use super::attachments::{AttachmentStore, StoredAttachment};
+use crate::api::rate_limiter::handle::ApiHandle;
+use crate::core::error::AppError;
+use crate::core::logger::Logger;
+use crate::core::vault::Vault;
+use futures::stream::{self, StreamExt};
+use reqwest::{Client, StatusCode, header};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Simultaneous CDN downloads. These do not go through the API buckets.
+pub const CDN_CONCURRENCY: usize = 4;
+
+/// `POST /attachments/refresh-urls` accepts at most this many URLs per call.
+const REFRESH_BATCH: usize = 50;
+
+/// One attachment waiting to be downloaded.
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub channel_id: String,
+    pub message_id: String,
+    pub attachment: Value,
+}
+
+impl DownloadJob {
+    /// One job per attachment of `msg`.
+    pub fn from_message(channel_id: &str, msg: &Value) -> Vec<Self> {
+        let message_id = msg["id"].as_str().unwrap_or_default();
+        msg["attachments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|a| a["url"].is_string())
+            .map(|attachment| Self {
+                channel_id: channel_id.to_string(),
+                message_id: message_id.to_string(),
+                attachment: attachment.clone(),
+            })
+            .collect()
+    }
+
+    fn attachment_id(&self) -> &str {
+        self.attachment["id"].as_str().unwrap_or_default()
+    }
+
+    fn url(&self) -> &str {
+        self.attachment["url"].as_str().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadFailure {
+    pub channel_id: String,
+    pub message_id: String,
+    pub attachment_id: String,
+    pub filename: String,
+    pub url: String,
+    pub error: String,
+}
+
+/// Outcome of a download run. Failures are collected instead of aborting the job.
+#[derive(Debug, Default, Serialize)]
+pub struct DownloadReport {
+    pub downloaded: usize,
+    pub skipped: usize,
+    pub failed: Vec<DownloadFailure>,
+}
+
+impl DownloadReport {
+    fn fail(&mut self, job: &DownloadJob, error: &AppError) {
+        self.failed.push(DownloadFailure {
+            channel_id: job.channel_id.clone(),
+            message_id: job.message_id.clone(),
+            attachment_id: job.attachment_id().to_string(),
+            filename: job.attachment["filename"]
+                .as_str()
+                .unwrap_or("file")
+                .to_string(),
+            url: job.url().to_string(),
+            error: error.user_message.clone(),
+        });
+    }
+
+    /// Writes `failures.json` next to the manifest, or removes a stale one.
+    pub fn save(&self, root: &Path) -> Result<(), AppError> {
+        let path = root.join("failures.json");
+        if self.failed.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.failed)?)?;
+        Ok(())
+    }
+}
+
+/// Signed CDN links carry their expiry as hex unix seconds in `ex`.
+pub fn signed_url_expired(url: &str, now: i64) -> bool {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    parsed
+        .query_pairs()
+        .find(|(key, _)| key == "ex")
+        .and_then(|(_, value)| i64::from_str_radix(&value, 16).ok())
+        .is_some_and(|expires| expires <= now)
+}
+
+/// Exchanges expired attachment links for fresh ones. Unknown links are left out.
+pub async fn refresh_urls(
+    api_handle: &ApiHandle,
+    urls: &[String],
+    token: &str,
+    is_bearer: bool,
+) -> Result<HashMap<String, String>, AppError> {
+    let mut refreshed = HashMap::new();
+    for batch in urls.chunks(REFRESH_BATCH) {
+        let response = api_handle
+            .send_request_json(
+                reqwest::Method::POST,
+                "https://discord.com/api/v9/attachments/refresh-urls",
+                Some(serde_json::json!({ "attachment_urls": batch })),
+                token,
+                is_bearer,
+                None,
+            )
+            .await?;
+        for entry in response["refreshed_urls"].as_array().into_iter().flatten() {
+            if let (Some(original), Some(fresh)) =
+                (entry["original"].as_str(), entry["refreshed"].as_str())
+            {
+                refreshed.insert(original.to_string(), fresh.to_string());
+            }
+        }
+    }
+    Ok(refreshed)
+}
+
+/// Streams `url` into `part`, resuming from whatever an earlier run left there.
+/// Returns the final size and SHA-256 of the file.
+pub async fn download_to(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    expected_size: Option<u64>,
+) -> Result<(u64, String), AppError> {
+    let mut hasher = Sha256::new();
+    let mut offset = match File::open(part) {
+        Ok(mut existing) => io::copy(&mut existing, &mut hasher)?,
+        Err(_) => 0,
+    };
+    if offset > 0 && expected_size == Some(offset) {
+        return Ok((offset, hex::encode(hasher.finalize())));
+    }
+
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request.send().await?;
+    let status = response.status();
+    match status {
+        StatusCode::FORBIDDEN | StatusCode::NOT_FOUND | StatusCode::GONE => {
+            return Err(AppError::new(
+                "Attachment link expired or removed.",
+                "cdn_expired",
+            ));
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            let _ = fs::remove_file(part);
+            return Err(AppError::new(
+                "Partial download no longer matches the file.",
+                "cdn_range",
+            ));
+        }
+        _ if !status.is_success() => {
+            return Err(AppError::new(
+                &format!("CDN responded with {}.", status),
+                "cdn_error",
+            ));
+        }
+        _ => {}
+    }
+
+    let mut file = if status == StatusCode::PARTIAL_CONTENT {
+        OpenOptions::new().append(true).open(part)?
+    } else {
+        // The server ignored the range, so start over.
+        offset = 0;
+        hasher = Sha256::new();
+        if let Some(parent) = part.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        File::create(part)?
+    };
+
+    let mut written = offset;
+    while let Some(chunk) = response.chunk().await? {
+        hasher.update(&chunk);
+        file.write_all(&chunk)?;
+        written += chunk.len() as u64;
+    }
+    file.sync_all()?;
+
+    if let Some(expected) = expected_size
+        && written != expected
+    {
+        let _ = fs::remove_file(part);
+        return Err(AppError::new(
+            &format!("Expected {} bytes but received {}.", expected, written),
+            "size_mismatch",
+        ));
+    }
+    Ok((written, hex::encode(hasher.finalize())))
+}
+
+/// Downloads attachments straight from the CDN, in parallel and without the user's token.
+pub struct CdnDownloader {
+    app_handle: AppHandle,
+    client: Client,
+    concurrency: usize,
+}
+
+impl CdnDownloader {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let mut builder = Client::builder()
+            .connect_timeout(Duration::from_secs(15))
+            .read_timeout(Duration::from_secs(60));
+        if let Some(proxy) = Vault::get_credential(app_handle, "proxy_url")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .and_then(|url| reqwest::Proxy::all(&url).ok())
+        {
+            builder = builder.proxy(proxy);
+        }
+        Self {
+            app_handle: app_handle.clone(),
+            client: builder.build().unwrap_or_default(),
+            concurrency: CDN_CONCURRENCY,
+        }
+    }
+
+    /// Stores every job's attachment. Files already in the store (or left on disk by an
+    /// earlier run) are skipped; expired links are refreshed through the API once.
+    pub async fn run(
+        &self,
+        api_handle: &ApiHandle,
+        store: &mut AttachmentStore,
+        jobs: Vec<DownloadJob>,
+        token: &str,
+        is_bearer: bool,
+        report: &mut DownloadReport,
+    ) -> Vec<StoredAttachment> {
+        let mut stored = Vec::new();
+        let mut pending = Vec::new();
+        for job in jobs {
+            match store.find_existing(&job.channel_id, &job.message_id, &job.attachment) {
+                Ok(Some(existing)) => {
+                    report.skipped += 1;
+                    stored.push(existing);
+                }
+                Ok(None) => pending.push(job),
+                Err(e) => report.fail(&job, &e),
+            }
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let expired: Vec<usize> = (0..pending.len())
+            .filter(|&i| signed_url_expired(pending[i].url(), now))
+            .collect();
+        self.refresh(api_handle, &mut pending, &expired, token, is_bearer)
+            .await;
+
+        let mut retry = Vec::new();
+        for (job, result) in self.fetch_all(store, pending).await {
+            match result {
+                Err(e) if e.error_code == "cdn_expired" => retry.push(job),
+                result => self.commit(store, job, result, &mut stored, report),
+            }
+        }
+
+        if !retry.is_empty() {
+            let all: Vec<usize> = (0..retry.len()).collect();
+            self.refresh(api_handle, &mut retry, &all, token, is_bearer)
+                .await;
+            for (job, result) in self.fetch_all(store, retry).await {
+                self.commit(store, job, result, &mut stored, report);
+            }
+        }
+        stored
+    }
+
+    async fn refresh(
+        &self,
+        api_handle: &ApiHandle,
+        jobs: &mut [DownloadJob],
+        indices: &[usize],
+        token: &str,
+        is_bearer: bool,
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+        let urls: Vec<String> = indices.iter().map(|&i| jobs[i].url().to_string()).collect();
+        match refresh_urls(api_handle, &urls, token, is_bearer).await {
+            Ok(fresh) => {
+                for &i in indices {
+                    if let Some(url) = fresh.get(jobs[i].url()) {
+                        jobs[i].attachment["url"] = Value::String(url.clone());
+                    }
+                }
+            }
+            Err(e) => Logger::warn(
+                &self.app_handle,
+                &format!("[EXPORT] Could not refresh attachment links: {}", e),
+                None,
+            ),
+        }
+    }
+
+    async fn fetch_all(
+        &self,
+        store: &AttachmentStore,
+        jobs: Vec<DownloadJob>,
+    ) -> Vec<(DownloadJob, Result<(u64, String), AppError>)> {
+        let prepared: Vec<(DownloadJob, PathBuf)> = jobs
+            .into_iter()
+            .map(|job| {
+                let part = store.partial_path(job.attachment_id());
+                (job, part)
+            })
+            .collect();
+
+        stream::iter(prepared)
+            .map(|(job, part)| async move {
+                let expected = job.attachment["size"].as_u64();
+                let result = download_to(&self.client, job.url(), &part, expected).await;
+                (job, result)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await
+    }
+
+    fn commit(
+        &self,
+        store: &mut AttachmentStore,
+        job: DownloadJob,
+        result: Result<(u64, String), AppError>,
+        stored: &mut Vec<StoredAttachment>,
+        report: &mut DownloadReport,
+    ) {
+        let committed = result.and_then(|(size, sha256)| {
+            store.commit(
+                &job.channel_id,
+                &job.message_id,
+                &job.attachment,
+                size,
+                &sha256,
+            )
+        });
+        match committed {
+            Ok(entry) => {
+                report.downloaded += 1;
+                stored.push(entry);
+            }
+            Err(e) => report.fail(&job, &e),
+        }
+    }
+}
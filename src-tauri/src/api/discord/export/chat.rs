@@ -0,0 +1,328 @@
+use super::attachments::{AttachmentStore, EXPORT_ATTACHMENTS_DIR, export_link};
+use super::cdn::{CdnDownloader, DownloadJob, DownloadReport};
+use super::format::{ChannelInfo, ChannelWriter, format_for, page_file_name};
+use super::render::RenderContext;
+use super::types::{ExportOptions, ExportProgress};
+use crate::api::discord::message_fetcher::MessagePager;
+use crate::api::rate_limiter::handle::ApiHandle;
+use crate::core::error::AppError;
+use crate::core::forensics::container::{
+    StagingDir, check_passphrase, seal_file, sealed_path, zip_directory,
+};
+use crate::core::logger::Logger;
+use crate::core::op_manager::OperationManager;
+use crate::core::vault::Vault;
+use base64::Engine;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+/// Attachments larger than this are linked instead of embedded in HTML exports.
+const INLINE_ATTACHMENT_LIMIT: u64 = 8 * 1024 * 1024;
+
+/// Display name for a channel header: the channel name, or the DM recipients.
+async fn channel_info(
+    api_handle: &ApiHandle,
+    channel_id: &str,
+    token: &str,
+    is_bearer: bool,
+) -> ChannelInfo {
+    let channel = api_handle
+        .send_request_json(
+            reqwest::Method::GET,
+            &format!("https://discord.com/api/v9/channels/{}", channel_id),
+            None,
+            token,
+            is_bearer,
+            None,
+        )
+        .await
+        .unwrap_or_default();
+
+    let name = channel["name"]
+        .as_str()
+        .map(|n| format!("#{}", n))
+        .or_else(|| {
+            let recipients: Vec<&str> = channel["recipients"]
+                .as_array()?
+                .iter()
+                .filter_map(|r| r["global_name"].as_str().or(r["username"].as_str()))
+                .collect();
+            (!recipients.is_empty()).then(|| recipients.join(", "))
+        })
+        .unwrap_or_else(|| channel_id.to_string());
+
+    ChannelInfo {
+        id: channel_id.to_string(),
+        name,
+        guild_id: channel["guild_id"].as_str().map(|s| s.to_string()),
+    }
+}
+
+/// Points the attachments of `msg` at their stored copies. With `embed`, small files
+/// are also inlined as `data_uri`.
+fn attach_local_copies(store: &AttachmentStore, msg: &mut serde_json::Value, embed: bool) {
+    for attachment in msg["attachments"].as_array_mut().into_iter().flatten() {
+        let Some(entry) = attachment["id"].as_str().and_then(|id| store.get(id)) else {
+            continue;
+        };
+        attachment["local_path"] = serde_json::Value::String(export_link(entry));
+        if !embed || entry.size > INLINE_ATTACHMENT_LIMIT {
+            continue;
+        }
+        if let Ok(bytes) = fs::read(store.root_path().join(&entry.path)) {
+            let content_type = entry
+                .content_type
+                .as_deref()
+                .unwrap_or("application/octet-stream");
+            attachment["data_uri"] = serde_json::Value::String(format!(
+                "data:{};base64,{}",
+                content_type,
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            ));
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn start_chat_export(
+    app_handle: AppHandle,
+    window: Window,
+    options: ExportOptions,
+) -> Result<(), AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    let user_id = identity.id;
+    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let op_manager = app_handle.state::<OperationManager>();
+
+    let ctx = Arc::new(RenderContext::from_cache(
+        &app_handle,
+        &user_id,
+        options.utc_offset_minutes,
+    ));
+    // Reject unknown formats before anything is created on disk.
+    let dm_only = format_for(&options.format, options.include_attachments, ctx.clone())?.dm_only();
+    if let Some(passphrase) = &options.passphrase {
+        check_passphrase(passphrase)?;
+    }
+    op_manager.state.prepare();
+    let result = export_channels(
+        &app_handle,
+        &window,
+        &options,
+        &user_id,
+        &token,
+        is_bearer,
+        ctx,
+        dm_only,
+    )
+    .await;
+    // Reset on failures too, so a broken export never leaves the operation running.
+    op_manager.state.reset();
+    result?;
+    let _ = window.emit("export_complete", ());
+    Ok(())
+}
+
+/// Writes every requested channel and seals the result when a passphrase is set.
+#[allow(clippy::too_many_arguments)]
+async fn export_channels(
+    app: &AppHandle,
+    window: &Window,
+    options: &ExportOptions,
+    user_id: &str,
+    token: &str,
+    is_bearer: bool,
+    ctx: Arc<RenderContext>,
+    dm_only: bool,
+) -> Result<(), AppError> {
+    let api_handle = app.state::<ApiHandle>();
+    let op_manager = app.state::<OperationManager>();
+
+    // Encrypted exports are assembled in a staging folder and sealed at the end.
+    let sealed_name = format!("chat_export_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    let staging = match &options.passphrase {
+        Some(_) => Some(StagingDir::create("chat_export")?),
+        None => None,
+    };
+    let output_dir = match &staging {
+        Some(staging) => staging.path().join(&sealed_name),
+        None => PathBuf::from(&options.output_path),
+    };
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| AppError::new(&e.to_string(), "directory_creation_failed"))?;
+    let mut store = if options.include_attachments {
+        Some(AttachmentStore::open(
+            &output_dir.join(EXPORT_ATTACHMENTS_DIR),
+        )?)
+    } else {
+        None
+    };
+    let embed = options.embed_attachments.unwrap_or(true);
+    let downloader = CdnDownloader::new(app);
+    let mut report = DownloadReport::default();
+    let mut skipped: Vec<String> = Vec::new();
+
+    'channels: for (i, channel_id) in options.channel_ids.iter().enumerate() {
+        let _ = window.emit(
+            "export_progress",
+            ExportProgress {
+                current: i + 1,
+                total: options.channel_ids.len(),
+                channel_id: channel_id.to_string(),
+                status: format!("generating_{}", options.format),
+                processed_count: 0,
+            },
+        );
+
+        let channel = channel_info(&api_handle, channel_id, token, is_bearer).await;
+        if dm_only && channel.guild_id.is_some() {
+            Logger::warn(
+                app,
+                &format!(
+                    "[EXPORT] Skipping {}: {} exports only cover DMs",
+                    channel_id, options.format
+                ),
+                None,
+            );
+            continue;
+        }
+        let mut writer = ChannelWriter::create(
+            &output_dir,
+            channel,
+            format_for(&options.format, options.include_attachments, ctx.clone())?,
+        )?;
+        let inline = embed && writer.format().inlines_attachments();
+        let mut pager = MessagePager::new(channel_id);
+
+        let mut failure = None;
+        loop {
+            let page = match pager.next_page(&api_handle, token, is_bearer).await {
+                Ok(Some(page)) => page,
+                Ok(None) => break,
+                Err(e) => {
+                    failure = Some(e.user_message);
+                    break;
+                }
+            };
+            op_manager.state.wait_if_paused().await;
+            if op_manager.state.should_abort.load(Ordering::SeqCst) {
+                writer.finish()?;
+                break 'channels;
+            }
+
+            let mut page: Vec<serde_json::Value> = page
+                .into_iter()
+                .filter(|msg| {
+                    let msg_author_id = msg["author"]["id"].as_str().unwrap_or("");
+                    match options.direction.as_str() {
+                        "sent" => msg_author_id == user_id,
+                        "received" => msg_author_id != user_id,
+                        _ => true, // "both" or default
+                    }
+                })
+                .collect();
+
+            if let Some(store) = store.as_mut() {
+                let jobs = page
+                    .iter()
+                    .flat_map(|msg| DownloadJob::from_message(channel_id, msg))
+                    .collect();
+                downloader
+                    .run(&api_handle, store, jobs, token, is_bearer, &mut report)
+                    .await;
+                for msg in page.iter_mut() {
+                    attach_local_copies(store, msg, inline);
+                }
+            }
+            for msg in &page {
+                writer.write(msg)?;
+            }
+
+            let _ = window.emit(
+                "export_progress",
+                ExportProgress {
+                    current: i + 1,
+                    total: options.channel_ids.len(),
+                    channel_id: channel_id.to_string(),
+                    status: format!("generating_{}", options.format),
+                    processed_count: writer.written,
+                },
+            );
+        }
+
+        let extension = writer.format().extension();
+        let written = writer.finish()?;
+        if let Some(reason) = failure {
+            // Typically a channel hidden from this account or a DM that no longer exists.
+            if written == 0 {
+                let _ = fs::remove_file(output_dir.join(page_file_name(channel_id, 1, extension)));
+            }
+            Logger::warn(
+                app,
+                &format!(
+                    "[EXPORT] Could not read {} after {} messages: {}",
+                    channel_id, written, reason
+                ),
+                None,
+            );
+            skipped.push(channel_id.clone());
+            continue;
+        }
+        Logger::info(
+            app,
+            &format!(
+                "[EXPORT] Wrote {} messages from {} as {}",
+                written, channel_id, options.format
+            ),
+            None,
+        );
+    }
+
+    if let Some(store) = &store {
+        store.save_manifest()?;
+        store.write_index_pages()?;
+        report.save(store.root_path())?;
+        if !report.failed.is_empty() {
+            Logger::warn(
+                app,
+                &format!(
+                    "[EXPORT] {} attachments could not be downloaded, see failures.json",
+                    report.failed.len()
+                ),
+                None,
+            );
+        }
+    }
+
+    if let Some(passphrase) = &options.passphrase {
+        let zip_path = output_dir.with_extension("zip");
+        zip_directory(&output_dir, &zip_path)?;
+        fs::remove_dir_all(&output_dir)?;
+        let dest_dir = PathBuf::from(&options.output_path);
+        fs::create_dir_all(&dest_dir)?;
+        let dest = sealed_path(&dest_dir.join(format!("{}.zip", sealed_name)));
+        seal_file(&zip_path, &dest, passphrase)?;
+        Logger::info(
+            app,
+            &format!("[EXPORT] Sealed chat export into {}", dest.display()),
+            None,
+        );
+    }
+
+    if !skipped.is_empty() {
+        Logger::warn(
+            app,
+            &format!(
+                "[EXPORT] {} of {} channels could not be read: {}",
+                skipped.len(),
+                options.channel_ids.len(),
+                skipped.join(", ")
+            ),
+            None,
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use super::format::{ChannelInfo, ExportFormat, attachments, author_name, reactions};
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// Spreadsheet-friendly export, one RFC 4180 row per message.
+pub struct CsvFormat;
+
+/// Quotes a field when it contains a separator, quote or line break. Fields that a
+/// spreadsheet would evaluate as a formula are prefixed with `'` and quoted.
+pub fn csv_field(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("\"'{}\"", value.replace('"', "\"\""))
+    } else if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_row(out: &mut dyn Write, fields: &[&str]) -> io::Result<()> {
+    let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    write!(out, "{}\r\n", row.join(","))
+}
+
+impl ExportFormat for CsvFormat {
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn begin(
+        &mut self,
+        out: &mut dyn Write,
+        _channel: &ChannelInfo,
+        _page: usize,
+        _prev: Option<&str>,
+    ) -> io::Result<()> {
+        write_row(
+            out,
+            &[
+                "ID",
+                "Timestamp",
+                "AuthorID",
+                "Author",
+                "Content",
+                "ReplyTo",
+                "Attachments",
+                "Reactions",
+            ],
+        )
+    }
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        let attachment_urls: Vec<&str> = attachments(msg).into_iter().map(|(_, url)| url).collect();
+        let reaction_list: Vec<String> = reactions(msg)
+            .into_iter()
+            .map(|(emoji, count)| format!("{} ({})", emoji, count))
+            .collect();
+        write_row(
+            out,
+            &[
+                msg["id"].as_str().unwrap_or(""),
+                msg["timestamp"].as_str().unwrap_or(""),
+                msg["author"]["id"].as_str().unwrap_or(""),
+                author_name(&msg["author"]),
+                msg["content"].as_str().unwrap_or(""),
+                msg["message_reference"]["message_id"]
+                    .as_str()
+                    .unwrap_or(""),
+                attachment_urls.join(" ").as_str(),
+                reaction_list.join(", ").as_str(),
+            ],
+        )
+    }
+
+    fn end(&mut self, _out: &mut dyn Write, _next: Option<&str>) -> io::Result<()> {
+        Ok(())
+    }
+}
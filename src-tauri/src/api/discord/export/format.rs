@@ -0,0 +1,266 @@
+use super::csv::CsvFormat;
+use super::html::HtmlFormat;
+use super::jsonl::{JsonArrayFormat, JsonlFormat};
+use super::mbox::MboxFormat;
+use super::render::RenderContext;
+use super::text::{MarkdownFormat, TextFormat};
+use crate::core::error::AppError;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The channel being written, used for file headers.
+#[derive(Debug, Clone)]
+pub struct ChannelInfo {
+    pub id: String,
+    pub name: String,
+    /// `None` for DMs and group DMs.
+    pub guild_id: Option<String>,
+}
+
+/// A chat export format. Messages are fed one at a time in chronological order,
+/// so writers never need the whole channel in memory.
+pub trait ExportFormat: Send {
+    fn extension(&self) -> &'static str;
+
+    /// Messages per output file. `None` writes each channel to a single file.
+    fn page_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether downloaded attachments should also be passed in as `data_uri` fields.
+    fn inlines_attachments(&self) -> bool {
+        false
+    }
+
+    /// Whether the format only applies to DMs and group DMs.
+    fn dm_only(&self) -> bool {
+        false
+    }
+
+    /// Starts a file. `prev` is the file name of the previous page, if any.
+    fn begin(
+        &mut self,
+        out: &mut dyn Write,
+        channel: &ChannelInfo,
+        page: usize,
+        prev: Option<&str>,
+    ) -> io::Result<()>;
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()>;
+
+    /// Ends a file. `next` is the file name of the following page, if any.
+    fn end(&mut self, out: &mut dyn Write, next: Option<&str>) -> io::Result<()>;
+}
+
+/// Resolves the `format` option of an export request.
+pub fn format_for(
+    name: &str,
+    include_attachments: bool,
+    ctx: Arc<RenderContext>,
+) -> Result<Box<dyn ExportFormat>, AppError> {
+    let format: Box<dyn ExportFormat> = match name.to_ascii_lowercase().as_str() {
+        "html" => Box::new(HtmlFormat::new(include_attachments, ctx)),
+        "markdown" | "md" => Box::new(MarkdownFormat::new(include_attachments, ctx)),
+        "txt" | "text" => Box::new(TextFormat::new(include_attachments, ctx)),
+        "csv" => Box::new(CsvFormat),
+        "jsonl" => Box::new(JsonlFormat),
+        "mbox" | "eml" => Box::new(MboxFormat::new(include_attachments, ctx)),
+        "raw" | "json" => Box::new(JsonArrayFormat::default()),
+        other => {
+            return Err(AppError::new(
+                &format!("Unsupported export format: {}", other),
+                "invalid_format",
+            ));
+        }
+    };
+    Ok(format)
+}
+
+/// Writes one channel through an [`ExportFormat`], rolling over to a new file
+/// (`<id>_page2.<ext>`, ...) whenever the format's page size is reached.
+pub struct ChannelWriter {
+    format: Box<dyn ExportFormat>,
+    dir: PathBuf,
+    channel: ChannelInfo,
+    out: BufWriter<File>,
+    page: usize,
+    in_page: usize,
+    pub written: usize,
+}
+
+impl ChannelWriter {
+    pub fn create(
+        dir: &Path,
+        channel: ChannelInfo,
+        mut format: Box<dyn ExportFormat>,
+    ) -> Result<Self, AppError> {
+        let mut out = BufWriter::new(File::create(dir.join(page_file_name(
+            &channel.id,
+            1,
+            format.extension(),
+        )))?);
+        format.begin(&mut out, &channel, 1, None)?;
+        Ok(Self {
+            format,
+            dir: dir.to_path_buf(),
+            channel,
+            out,
+            page: 1,
+            in_page: 0,
+            written: 0,
+        })
+    }
+
+    pub fn format(&self) -> &dyn ExportFormat {
+        self.format.as_ref()
+    }
+
+    pub fn write(&mut self, msg: &Value) -> Result<(), AppError> {
+        if self
+            .format
+            .page_size()
+            .is_some_and(|size| self.in_page >= size)
+        {
+            self.next_page()?;
+        }
+        self.format.write_message(&mut self.out, msg)?;
+        self.in_page += 1;
+        self.written += 1;
+        Ok(())
+    }
+
+    fn next_page(&mut self) -> Result<(), AppError> {
+        let ext = self.format.extension();
+        let current = page_file_name(&self.channel.id, self.page, ext);
+        let next = page_file_name(&self.channel.id, self.page + 1, ext);
+
+        self.format.end(&mut self.out, Some(&next))?;
+        self.out.flush()?;
+
+        self.page += 1;
+        self.in_page = 0;
+        self.out = BufWriter::new(File::create(self.dir.join(&next))?);
+        self.format
+            .begin(&mut self.out, &self.channel, self.page, Some(&current))?;
+        Ok(())
+    }
+
+    /// Closes the last file and returns the number of messages written.
+    pub fn finish(mut self) -> Result<usize, AppError> {
+        self.format.end(&mut self.out, None)?;
+        self.out.flush()?;
+        Ok(self.written)
+    }
+}
+
+pub fn page_file_name(channel_id: &str, page: usize, ext: &str) -> String {
+    if page <= 1 {
+        format!("{}.{}", channel_id, ext)
+    } else {
+        format!("{}_page{}.{}", channel_id, page, ext)
+    }
+}
+
+/// Emits a label whenever consecutive messages fall on different days in the export's time zone.
+#[derive(Default)]
+pub struct DaySeparator {
+    last: Option<NaiveDate>,
+}
+
+impl DaySeparator {
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    pub fn next(&mut self, msg: &Value, ctx: &RenderContext) -> Option<String> {
+        let day = ctx.day_of(msg)?;
+        if self.last == Some(day) {
+            return None;
+        }
+        self.last = Some(day);
+        Some(day.format("%A, %B %-d, %Y").to_string())
+    }
+}
+
+pub fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Display name of a message author: global name, falling back to the username.
+pub fn author_name(author: &Value) -> &str {
+    author["global_name"]
+        .as_str()
+        .or_else(|| author["username"].as_str())
+        .unwrap_or("Unknown")
+}
+
+/// `(author, first line)` of the message this one replies to.
+pub fn reply_preview(msg: &Value) -> Option<(String, String)> {
+    let referenced = msg.get("referenced_message").filter(|r| r.is_object())?;
+    let content = referenced["content"].as_str().unwrap_or("");
+    let mut preview: String = content
+        .lines()
+        .next()
+        .unwrap_or("")
+        .chars()
+        .take(80)
+        .collect();
+    if preview.len() < content.len() {
+        preview.push('…');
+    }
+    Some((author_name(&referenced["author"]).to_string(), preview))
+}
+
+/// `(emoji, count)` for every reaction; custom emoji are shown as `:name:`.
+pub fn reactions(msg: &Value) -> Vec<(String, u64)> {
+    msg["reactions"]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .map(|r| {
+                    let emoji = &r["emoji"];
+                    let name = emoji["name"].as_str().unwrap_or("?");
+                    let label = if emoji["id"].is_string() {
+                        format!(":{}:", name)
+                    } else {
+                        name.to_string()
+                    };
+                    (label, r["count"].as_u64().unwrap_or(0))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn sticker_names(msg: &Value) -> Vec<&str> {
+    msg["sticker_items"]
+        .as_array()
+        .map(|list| list.iter().filter_map(|s| s["name"].as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// `(filename, link)` of every attachment, preferring the locally stored copy.
+pub fn attachments(msg: &Value) -> Vec<(&str, &str)> {
+    msg["attachments"]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .map(|a| {
+                    (
+                        a["filename"].as_str().unwrap_or("file"),
+                        a["local_path"]
+                            .as_str()
+                            .or_else(|| a["url"].as_str())
+                            .unwrap_or(""),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,51 +1,16 @@
+use super::attachments::AttachmentStore;
+use super::cdn::{CdnDownloader, DownloadJob, DownloadReport};
 use super::types::{ExportOptions, ExportProgress};
 use crate::api::discord::message_fetcher::fetch_all_messages;
-use crate::api::rate_limiter::handle::{ApiHandle, RequestConfig};
-use crate::api::rate_limiter::types::ApiResponseContent;
+use crate::api::rate_limiter::handle::ApiHandle;
 use crate::core::error::AppError;
+use crate::core::logger::Logger;
 use crate::core::op_manager::OperationManager;
 use crate::core::vault::Vault;
-use std::fs::{self, File};
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Emitter, Manager, Window};
 
-async fn download_file(
-    api_handle: &ApiHandle,
-    url: &str,
-    save_path: &Path,
-    token: &str,
-    is_bearer: bool,
-) -> Result<(), AppError> {
-    let response_content = api_handle
-        .send_request(
-            reqwest::Method::GET,
-            url,
-            None,
-            token,
-            is_bearer,
-            RequestConfig {
-                return_raw_bytes: true,
-                ..Default::default()
-            },
-        )
-        .await?;
-
-    match response_content {
-        ApiResponseContent::Bytes(bytes) => {
-            let mut file = File::create(save_path)
-                .map_err(|e| AppError::new("File Creation Error", &e.to_string()))?;
-            file.write_all(&bytes)
-                .map_err(|e| AppError::new("File Write Error", &e.to_string()))?;
-            Ok(())
-        }
-        _ => Err(AppError::new(
-            "Type Mismatch",
-            "Expected raw bytes, received JSON",
-        )),
-    }
-}
-
 #[tauri::command]
 pub async fn start_attachment_harvest(
     app_handle: AppHandle,
@@ -59,11 +24,15 @@ pub async fn start_attachment_harvest(
     let op_manager = app_handle.state::<OperationManager>();
     op_manager.state.prepare();
 
-    let output_dir = PathBuf::from(&options.output_path);
-    fs::create_dir_all(&output_dir)
-        .map_err(|e| AppError::new("Directory Creation Error", &e.to_string()))?;
+    let mut store = AttachmentStore::open(&PathBuf::from(&options.output_path))?;
+    let downloader = CdnDownloader::new(&app_handle);
+    let mut report = DownloadReport::default();
 
     for (i, channel_id) in options.channel_ids.iter().enumerate() {
+        op_manager.state.wait_if_paused().await;
+        if op_manager.state.should_abort.load(Ordering::SeqCst) {
+            break;
+        }
         let _ = window.emit(
             "export_progress",
             ExportProgress {
@@ -76,47 +45,56 @@ pub async fn start_attachment_harvest(
         );
 
         let messages = fetch_all_messages(channel_id, &api_handle, &token, is_bearer).await?;
-        let mut attachment_count = 0;
-
-        for msg in messages {
-            // Filter by direction
-            let msg_author_id = msg["author"]["id"].as_str().unwrap_or("");
-            let should_process = match options.direction.as_str() {
-                "sent" => msg_author_id == user_id,
-                "received" => msg_author_id != user_id,
-                _ => true,
-            };
-
-            if !should_process {
-                continue;
-            }
-
-            if let Some(attachments) = msg.get("attachments").and_then(|a| a.as_array()) {
-                for attachment in attachments {
-                    if let (Some(url), Some(filename)) = (
-                        attachment.get("url").and_then(|u| u.as_str()),
-                        attachment.get("filename").and_then(|f| f.as_str()),
-                    ) {
-                        let save_path = output_dir.join(filename);
-                        let _ = window.emit(
-                            "export_progress",
-                            ExportProgress {
-                                current: i + 1,
-                                total: options.channel_ids.len(),
-                                channel_id: channel_id.to_string(),
-                                status: format!("downloading_{}", filename),
-                                processed_count: attachment_count,
-                            },
-                        );
-                        download_file(&api_handle, url, &save_path, &token, is_bearer).await?;
-                        attachment_count += 1;
-                    }
+        let jobs: Vec<DownloadJob> = messages
+            .iter()
+            .filter(|msg| {
+                // Filter by direction
+                let msg_author_id = msg["author"]["id"].as_str().unwrap_or("");
+                match options.direction.as_str() {
+                    "sent" => msg_author_id == user_id,
+                    "received" => msg_author_id != user_id,
+                    _ => true,
                 }
-            }
-        }
+            })
+            .flat_map(|msg| DownloadJob::from_message(channel_id, msg))
+            .collect();
+
+        let _ = window.emit(
+            "export_progress",
+            ExportProgress {
+                current: i + 1,
+                total: options.channel_ids.len(),
+                channel_id: channel_id.to_string(),
+                status: format!("downloading_{}_attachments", jobs.len()),
+                processed_count: report.downloaded + report.skipped,
+            },
+        );
+        downloader
+            .run(
+                &api_handle,
+                &mut store,
+                jobs,
+                &token,
+                is_bearer,
+                &mut report,
+            )
+            .await;
+        store.save_manifest()?;
     }
 
+    store.write_index_pages()?;
+    report.save(store.root_path())?;
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[EXPORT] Attachment harvest finished: {} downloaded, {} already present, {} failed",
+            report.downloaded,
+            report.skipped,
+            report.failed.len()
+        ),
+        None,
+    );
     op_manager.state.reset();
-    let _ = window.emit("export_complete", ());
+    let _ = window.emit("export_complete", &report);
     Ok(())
 }
@@ -1,131 +1,206 @@
-use super::types::{ExportOptions, ExportProgress};
-use crate::api::discord::message_fetcher::fetch_all_messages;
-use crate::api::rate_limiter::handle::ApiHandle;
-use crate::core::error::AppError;
-use crate::core::op_manager::OperationManager;
-use crate::core::vault::Vault;
-use std::fs::{self, File};
-use std::io::Write;
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter, Manager, Window};
+use super::format::{
+    ChannelInfo, DaySeparator, ExportFormat, author_name, reactions, reply_preview, sticker_names,
+};
+use super::markdown::{MarkdownRenderer, escape_html, is_safe_url};
+use super::render::RenderContext;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::Arc;
 
-pub fn generate_html_from_messages(
-    messages: &[serde_json::Value],
-    include_attachments: bool,
-) -> String {
-    let mut html_content = String::from(
-        "<html><head><style>
-        body { font-family: sans-serif; background: #313338; color: #dbdee1; padding: 20px; }
-        .message { margin-bottom: 15px; padding: 10px; border-radius: 8px; background: #2b2d31; }
-        .author { font-weight: bold; color: #f2f3f5; margin-right: 10px; }
-        .timestamp { font-size: 0.8em; color: #949ba4; }
-        .content { margin-top: 5px; white-space: pre-wrap; }
-        .attachment { margin-top: 10px; padding: 5px; border: 1px solid #4e5058; border-radius: 4px; display: inline-block; }
-    </style></head><body>",
-    );
+/// Messages per HTML page; larger channels are split across linked files.
+pub const HTML_PAGE_SIZE: usize = 5000;
 
-    for msg in messages {
-        let author = msg["author"]["username"].as_str().unwrap_or("Unknown");
-        let content = msg["content"].as_str().unwrap_or("");
-        let ts = msg["timestamp"].as_str().unwrap_or("");
+const STYLE: &str = "
+    body { font-family: sans-serif; background: #313338; color: #dbdee1; padding: 20px; }
+    h1 { color: #f2f3f5; }
+    nav { margin: 10px 0; }
+    nav a { color: #00a8fc; margin-right: 15px; }
+    .day { text-align: center; color: #949ba4; font-size: 0.8em; margin: 25px 0 10px; border-top: 1px solid #3f4147; padding-top: 5px; }
+    .message { margin-bottom: 15px; padding: 10px; border-radius: 8px; background: #2b2d31; }
+    .author { font-weight: bold; color: #f2f3f5; margin-right: 10px; }
+    .timestamp { font-size: 0.8em; color: #949ba4; }
+    .reply { font-size: 0.85em; color: #b5bac1; margin-bottom: 4px; }
+    .content { margin-top: 5px; white-space: pre-wrap; word-wrap: break-word; }
+    .content pre { background: #1e1f22; padding: 8px; border-radius: 4px; }
+    .content code { background: #1e1f22; padding: 1px 4px; border-radius: 3px; }
+    .content blockquote { margin: 4px 0; padding-left: 10px; border-left: 4px solid #4e5058; }
+    .content a { color: #00a8fc; }
+    .spoiler { background: #1e1f22; color: transparent; border-radius: 3px; cursor: pointer; }
+    .spoiler:hover, .spoiler:active { background: #3f4147; color: inherit; }
+    .emoji { width: 1.375em; height: 1.375em; vertical-align: bottom; }
+    .mention { background: rgba(88,101,242,0.3); color: #c9cdfb; border-radius: 3px; padding: 0 2px; }
+    .embed { margin-top: 8px; padding: 8px 12px; border-left: 4px solid #1e1f22; background: #232428; border-radius: 4px; max-width: 520px; }
+    .embed-title { font-weight: bold; color: #00a8fc; }
+    .sticker { margin-top: 5px; font-style: italic; color: #b5bac1; }
+    .attachment { margin-top: 10px; padding: 5px; border: 1px solid #4e5058; border-radius: 4px; display: inline-block; }
+    .attachment img { max-width: 400px; max-height: 300px; display: block; }
+    .reactions span { display: inline-block; margin: 6px 4px 0 0; padding: 2px 6px; border-radius: 8px; background: #3f4147; font-size: 0.85em; }
+";
 
-        html_content.push_str(&format!(
-            "<div class='message'><span class='author'>{}</span><span class='timestamp'>{}</span><div class='content'>{}</div>",
-            author, ts, content
-        ));
+/// Self-contained HTML transcript. Attachments carrying a `data_uri` are embedded,
+/// others link to their `local_path` copy or the CDN.
+pub struct HtmlFormat {
+    include_attachments: bool,
+    ctx: Arc<RenderContext>,
+    days: DaySeparator,
+}
 
-        if include_attachments {
-            if let Some(atts) = msg["attachments"].as_array() {
-                for att in atts {
-                    let att_name = att["filename"].as_str().unwrap_or("file");
-                    html_content.push_str(&format!(
-                        "<div class='attachment'>Attachment: {}</div>",
-                        att_name
-                    ));
-                }
-            }
+impl HtmlFormat {
+    pub fn new(include_attachments: bool, ctx: Arc<RenderContext>) -> Self {
+        Self {
+            include_attachments,
+            ctx,
+            days: DaySeparator::default(),
         }
-        html_content.push_str("</div>");
     }
-    html_content.push_str("</body></html>");
-    html_content
-}
 
-#[tauri::command]
-pub async fn start_chat_export(
-    app_handle: AppHandle,
-    window: Window,
-    options: ExportOptions,
-) -> Result<(), AppError> {
-    let identity = Vault::get_active_identity(&app_handle)?;
-    let user_id = identity.id;
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
-    let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.prepare();
+    fn write_nav(out: &mut dyn Write, prev: Option<&str>, next: Option<&str>) -> io::Result<()> {
+        if prev.is_none() && next.is_none() {
+            return Ok(());
+        }
+        write!(out, "<nav>")?;
+        if let Some(prev) = prev {
+            write!(out, "<a href=\"{}\">&larr; Older</a>", escape_html(prev))?;
+        }
+        if let Some(next) = next {
+            write!(out, "<a href=\"{}\">Newer &rarr;</a>", escape_html(next))?;
+        }
+        writeln!(out, "</nav>")
+    }
 
-    let output_dir = PathBuf::from(&options.output_path);
-    fs::create_dir_all(&output_dir)
-        .map_err(|e| AppError::new("Directory Creation Error", &e.to_string()))?;
+    fn write_attachment(out: &mut dyn Write, attachment: &Value) -> io::Result<()> {
+        let name = escape_html(attachment["filename"].as_str().unwrap_or("file"));
+        let is_image = attachment["content_type"]
+            .as_str()
+            .is_some_and(|t| t.starts_with("image/"));
+        let (href, download) = match attachment["data_uri"].as_str() {
+            Some(uri) => (uri, format!(" download=\"{}\"", name)),
+            None => (
+                attachment["local_path"]
+                    .as_str()
+                    .or_else(|| attachment["url"].as_str())
+                    .unwrap_or(""),
+                String::new(),
+            ),
+        };
+        let href = escape_html(href);
 
-    for (i, channel_id) in options.channel_ids.iter().enumerate() {
-        let _ = window.emit(
-            "export_progress",
-            ExportProgress {
-                current: i + 1,
-                total: options.channel_ids.len(),
-                channel_id: channel_id.to_string(),
-                status: "fetching_history".to_string(),
-                processed_count: 0,
-            },
-        );
+        write!(out, "<div class='attachment'>")?;
+        if is_image {
+            write!(
+                out,
+                "<a href=\"{}\"{}><img src=\"{}\" alt=\"{}\"></a>",
+                href, download, href, name
+            )?;
+        } else {
+            write!(out, "📎 <a href=\"{}\"{}>{}</a>", href, download, name)?;
+        }
+        write!(out, "</div>")
+    }
+}
 
-        let all_messages = fetch_all_messages(channel_id, &api_handle, &token, is_bearer).await?;
+impl ExportFormat for HtmlFormat {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
 
-        // Filter by direction
-        let filtered_messages: Vec<serde_json::Value> = all_messages
-            .into_iter()
-            .filter(|msg| {
-                let msg_author_id = msg["author"]["id"].as_str().unwrap_or("");
-                match options.direction.as_str() {
-                    "sent" => msg_author_id == user_id,
-                    "received" => msg_author_id != user_id,
-                    _ => true, // "both" or default
-                }
-            })
-            .collect();
+    fn page_size(&self) -> Option<usize> {
+        Some(HTML_PAGE_SIZE)
+    }
 
-        let _ = window.emit(
-            "export_progress",
-            ExportProgress {
-                current: i + 1,
-                total: options.channel_ids.len(),
-                channel_id: channel_id.to_string(),
-                status: format!("generating_{}", options.format),
-                processed_count: filtered_messages.len(),
-            },
-        );
+    fn inlines_attachments(&self) -> bool {
+        self.include_attachments
+    }
 
-        let (ext, content) = if options.format == "raw" {
-            (
-                "json",
-                serde_json::to_string_pretty(&filtered_messages).unwrap(),
-            )
-        } else {
-            (
-                "html",
-                generate_html_from_messages(&filtered_messages, options.include_attachments),
-            )
-        };
+    fn begin(
+        &mut self,
+        out: &mut dyn Write,
+        channel: &ChannelInfo,
+        page: usize,
+        prev: Option<&str>,
+    ) -> io::Result<()> {
+        self.days.reset();
+        let title = escape_html(&channel.name);
+        write!(
+            out,
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head><body>",
+            title, STYLE
+        )?;
+        writeln!(out, "<h1>{} <small>(page {})</small></h1>", title, page)?;
+        Self::write_nav(out, prev, None)
+    }
 
-        let file_path = output_dir.join(format!("{}.{}", channel_id, ext));
-        let mut file = File::create(file_path)
-            .map_err(|e| AppError::new("File Creation Error", &e.to_string()))?;
-        file.write_all(content.as_bytes())
-            .map_err(|e| AppError::new("File Write Error", &e.to_string()))?;
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        if let Some(day) = self.days.next(msg, &self.ctx) {
+            writeln!(out, "<div class='day'>{}</div>", day)?;
+        }
+        write!(
+            out,
+            "<div class='message' id='m{}'>",
+            escape_html(msg["id"].as_str().unwrap_or(""))
+        )?;
+        if let Some((author, preview)) = reply_preview(msg) {
+            write!(
+                out,
+                "<div class='reply'>↪ <b>{}</b> {}</div>",
+                escape_html(&author),
+                escape_html(&preview)
+            )?;
+        }
+        let timestamp = msg["timestamp"].as_str().unwrap_or("");
+        let renderer = MarkdownRenderer::new(&self.ctx, msg);
+        write!(
+            out,
+            "<span class='author'>{}</span><time class='timestamp' datetime=\"{}\">{}</time><div class='content'>{}</div>",
+            escape_html(author_name(&msg["author"])),
+            escape_html(timestamp),
+            self.ctx.format_timestamp(timestamp),
+            renderer.render(msg["content"].as_str().unwrap_or(""))
+        )?;
+
+        for embed in msg["embeds"].as_array().into_iter().flatten() {
+            write!(out, "<div class='embed'>")?;
+            if let Some(title) = embed["title"].as_str() {
+                match embed["url"].as_str().filter(|url| is_safe_url(url)) {
+                    Some(url) => write!(
+                        out,
+                        "<a class='embed-title' href=\"{}\">{}</a>",
+                        escape_html(url),
+                        escape_html(title)
+                    )?,
+                    None => write!(out, "<div class='embed-title'>{}</div>", escape_html(title))?,
+                }
+            }
+            if let Some(description) = embed["description"].as_str() {
+                write!(out, "<div>{}</div>", renderer.render(description))?;
+            }
+            write!(out, "</div>")?;
+        }
+        for sticker in sticker_names(msg) {
+            write!(
+                out,
+                "<div class='sticker'>Sticker: {}</div>",
+                escape_html(sticker)
+            )?;
+        }
+        if self.include_attachments {
+            for attachment in msg["attachments"].as_array().into_iter().flatten() {
+                Self::write_attachment(out, attachment)?;
+            }
+        }
+        let reactions = reactions(msg);
+        if !reactions.is_empty() {
+            write!(out, "<div class='reactions'>")?;
+            for (emoji, count) in reactions {
+                write!(out, "<span>{} {}</span>", escape_html(&emoji), count)?;
+            }
+            write!(out, "</div>")?;
+        }
+        writeln!(out, "</div>")
     }
 
-    op_manager.state.reset();
-    let _ = window.emit("export_complete", ());
-    Ok(())
+    fn end(&mut self, out: &mut dyn Write, next: Option<&str>) -> io::Result<()> {
+        Self::write_nav(out, None, next)?;
+        writeln!(out, "</body></html>")
+    }
 }
@@ -0,0 +1,68 @@
+use super::format::{ChannelInfo, ExportFormat};
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// One raw message object per line.
+pub struct JsonlFormat;
+
+impl ExportFormat for JsonlFormat {
+    fn extension(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn begin(
+        &mut self,
+        _out: &mut dyn Write,
+        _channel: &ChannelInfo,
+        _page: usize,
+        _prev: Option<&str>,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        serde_json::to_writer(&mut *out, msg)?;
+        writeln!(out)
+    }
+
+    fn end(&mut self, _out: &mut dyn Write, _next: Option<&str>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The legacy "raw" export: a single JSON array of message objects, written incrementally.
+#[derive(Default)]
+pub struct JsonArrayFormat {
+    first: bool,
+}
+
+impl ExportFormat for JsonArrayFormat {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn begin(
+        &mut self,
+        out: &mut dyn Write,
+        _channel: &ChannelInfo,
+        _page: usize,
+        _prev: Option<&str>,
+    ) -> io::Result<()> {
+        self.first = true;
+        write!(out, "[")
+    }
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        if !self.first {
+            write!(out, ",")?;
+        }
+        self.first = false;
+        writeln!(out)?;
+        serde_json::to_writer_pretty(&mut *out, msg)?;
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write, _next: Option<&str>) -> io::Result<()> {
+        writeln!(out, "\n]")
+    }
+}
@@ -0,0 +1,227 @@
+use super::render::{RenderContext, Token, parse_token, token_regex};
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+static CODE_BLOCK_REGEX: OnceLock<Regex> = OnceLock::new();
+static INLINE_CODE_REGEX: OnceLock<Regex> = OnceLock::new();
+static LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+static PLACEHOLDER_REGEX: OnceLock<Regex> = OnceLock::new();
+static INLINE_REGEXES: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+
+pub fn escape_html(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Only http(s) links are turned into anchors; anything else stays text.
+pub fn is_safe_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+/// Converts Discord-flavoured markdown to HTML. All user text is escaped; mentions,
+/// channels, custom emoji and timestamps are resolved through `ctx`.
+pub struct MarkdownRenderer<'a> {
+    ctx: &'a RenderContext,
+    msg: &'a Value,
+}
+
+impl<'a> MarkdownRenderer<'a> {
+    pub fn new(ctx: &'a RenderContext, msg: &'a Value) -> Self {
+        Self { ctx, msg }
+    }
+
+    pub fn render(&self, content: &str) -> String {
+        let code_block = CODE_BLOCK_REGEX
+            .get_or_init(|| Regex::new(r"(?s)```(?:([a-zA-Z0-9_+-]+)\n)?\n?(.*?)```").unwrap());
+
+        let mut html = String::new();
+        let mut last = 0;
+        for caps in code_block.captures_iter(content) {
+            let whole = caps.get(0).unwrap();
+            html.push_str(&self.render_blocks(&content[last..whole.start()]));
+            let class = caps
+                .get(1)
+                .map(|lang| format!(" class=\"language-{}\"", escape_html(lang.as_str())))
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<pre><code{}>{}</code></pre>",
+                class,
+                escape_html(caps.get(2).map_or("", |c| c.as_str()))
+            ));
+            last = whole.end();
+        }
+        html.push_str(&self.render_blocks(&content[last..]));
+        html
+    }
+
+    /// Groups `> ` quoted lines (and everything after `>>> `) into blockquotes.
+    fn render_blocks(&self, text: &str) -> String {
+        let mut html = String::new();
+        let mut quoted: Vec<&str> = Vec::new();
+        let mut plain: Vec<&str> = Vec::new();
+        let mut lines = text.split('\n');
+
+        while let Some(line) = lines.next() {
+            if let Some(rest) = line.strip_prefix(">>> ") {
+                self.flush(&mut html, &mut plain, false);
+                quoted.push(rest);
+                quoted.extend(lines.by_ref());
+                break;
+            }
+            match line.strip_prefix("> ").or((line == ">").then_some("")) {
+                Some(rest) => {
+                    self.flush(&mut html, &mut plain, false);
+                    quoted.push(rest);
+                }
+                None => {
+                    self.flush(&mut html, &mut quoted, true);
+                    plain.push(line);
+                }
+            }
+        }
+        self.flush(&mut html, &mut plain, false);
+        self.flush(&mut html, &mut quoted, true);
+        html
+    }
+
+    fn flush(&self, html: &mut String, lines: &mut Vec<&str>, quote: bool) {
+        if lines.is_empty() {
+            return;
+        }
+        let inner = self.render_inline(&lines.join("\n"));
+        if quote {
+            html.push_str(&format!("<blockquote>{}</blockquote>", inner));
+        } else {
+            if !html.is_empty() && !html.ends_with("</blockquote>") {
+                html.push('\n');
+            }
+            html.push_str(&inner);
+        }
+        lines.clear();
+    }
+
+    /// Inline code spans are kept verbatim; everything else gets text formatting.
+    fn render_inline(&self, text: &str) -> String {
+        let inline_code = INLINE_CODE_REGEX.get_or_init(|| Regex::new(r"`([^`]+)`").unwrap());
+        let mut html = String::new();
+        let mut last = 0;
+        for caps in inline_code.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            html.push_str(&self.render_text(&text[last..whole.start()]));
+            html.push_str(&format!("<code>{}</code>", escape_html(&caps[1])));
+            last = whole.end();
+        }
+        html.push_str(&self.render_text(&text[last..]));
+        html
+    }
+
+    /// Tokens and links are swapped for placeholders first so their generated HTML
+    /// is not touched by escaping or the emphasis rules.
+    fn render_text(&self, text: &str) -> String {
+        let link = LINK_REGEX.get_or_init(|| {
+            Regex::new(r"\[([^\]\n]+)\]\((https?://[^)\s]+)\)|https?://[^\s<]+[^\s<.,:;!?)\]'\x22]")
+                .unwrap()
+        });
+        let placeholder =
+            PLACEHOLDER_REGEX.get_or_init(|| Regex::new(r"\x{E000}(\d+)\x{E001}").unwrap());
+
+        let mut fragments: Vec<String> = Vec::new();
+        let mut stash = |html: String| {
+            fragments.push(html);
+            format!("\u{E000}{}\u{E001}", fragments.len() - 1)
+        };
+
+        let text = token_regex().replace_all(text, |caps: &Captures| match parse_token(caps) {
+            Some(token) => stash(self.token_html(&token)),
+            None => caps[0].to_string(),
+        });
+        let text = link.replace_all(&text, |caps: &Captures| {
+            let (label, url) = match (caps.get(1), caps.get(2)) {
+                (Some(label), Some(url)) => (label.as_str(), url.as_str()),
+                _ => (&caps[0], &caps[0]),
+            };
+            if !is_safe_url(url) {
+                return caps[0].to_string();
+            }
+            stash(format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(url),
+                escape_html(label)
+            ))
+        });
+
+        let mut html = escape_html(&text);
+        let rules = INLINE_REGEXES.get_or_init(|| {
+            vec![
+                (
+                    Regex::new(r"\|\|(.+?)\|\|").unwrap(),
+                    "<span class=\"spoiler\">$1</span>",
+                ),
+                (Regex::new(r"\*\*(.+?)\*\*").unwrap(), "<strong>$1</strong>"),
+                (Regex::new(r"__(.+?)__").unwrap(), "<u>$1</u>"),
+                (Regex::new(r"\*([^*\s][^*]*?)\*").unwrap(), "<em>$1</em>"),
+                (Regex::new(r"\b_([^_\s][^_]*?)_\b").unwrap(), "<em>$1</em>"),
+                (Regex::new(r"~~(.+?)~~").unwrap(), "<s>$1</s>"),
+            ]
+        });
+        for (re, replacement) in rules {
+            html = re.replace_all(&html, *replacement).to_string();
+        }
+
+        placeholder
+            .replace_all(&html, |caps: &Captures| {
+                caps[1]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| fragments.get(i).cloned())
+                    .unwrap_or_default()
+            })
+            .to_string()
+    }
+
+    fn token_html(&self, token: &Token) -> String {
+        match token {
+            Token::User(id) => format!(
+                "<span class=\"mention\" title=\"{}\">@{}</span>",
+                id,
+                escape_html(
+                    &self
+                        .ctx
+                        .user_name(self.msg, id)
+                        .unwrap_or_else(|| id.to_string())
+                )
+            ),
+            Token::Role(id) => format!("<span class=\"mention\">@role:{}</span>", id),
+            Token::Channel(id) => format!(
+                "<span class=\"mention\">#{}</span>",
+                escape_html(self.ctx.channel_name(id).unwrap_or(id))
+            ),
+            Token::Emoji { name, id, animated } => format!(
+                "<img class=\"emoji\" src=\"https://cdn.discordapp.com/emojis/{}.{}\" alt=\":{}:\" title=\":{}:\">",
+                id,
+                if *animated { "gif" } else { "png" },
+                name,
+                name
+            ),
+            Token::Timestamp { secs, style } => format!(
+                "<time datetime=\"{}\">{}</time>",
+                chrono::DateTime::from_timestamp(*secs, 0)
+                    .map(|dt| dt.to_rfc3339())
+                    .unwrap_or_default(),
+                escape_html(&self.ctx.format_unix(*secs, *style))
+            ),
+        }
+    }
+}
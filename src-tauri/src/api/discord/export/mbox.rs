@@ -0,0 +1,325 @@
+use super::format::{ChannelInfo, ExportFormat, author_name, parse_timestamp, sticker_names};
+use super::render::RenderContext;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// Most ancestors kept in a `References` header; older ones are dropped from the front.
+const MAX_REFERENCES: usize = 20;
+
+/// Longest encoded line, per RFC 2045.
+const LINE_LIMIT: usize = 76;
+
+/// One RFC 5322 message per Discord message in an mboxrd file. Replies carry
+/// `In-Reply-To`/`References`, so mail clients thread them. Attachments carrying a
+/// `data_uri` become MIME parts; the rest are listed with their links.
+pub struct MboxFormat {
+    include_attachments: bool,
+    ctx: Arc<RenderContext>,
+    channel: Option<ChannelInfo>,
+    /// `References` chain of every reply written so far in this channel.
+    threads: HashMap<String, Vec<String>>,
+}
+
+impl MboxFormat {
+    pub fn new(include_attachments: bool, ctx: Arc<RenderContext>) -> Self {
+        Self {
+            include_attachments,
+            ctx,
+            channel: None,
+            threads: HashMap::new(),
+        }
+    }
+
+    fn message_id(&self, id: &str) -> String {
+        let channel = self.channel.as_ref().map(|c| c.id.as_str()).unwrap_or("0");
+        format!("<{}.{}@discord.com>", id, channel)
+    }
+
+    fn jump_link(&self, id: &str) -> String {
+        let (guild, channel) = match &self.channel {
+            Some(c) => (c.guild_id.as_deref().unwrap_or("@me"), c.id.as_str()),
+            None => ("@me", "0"),
+        };
+        format!("https://discord.com/channels/{}/{}/{}", guild, channel, id)
+    }
+
+    /// Plain-text body: resolved content, embeds, stickers and links to attachments
+    /// that are not carried as MIME parts.
+    fn body_text(&self, msg: &Value) -> String {
+        let mut lines = vec![
+            self.ctx
+                .resolve_plain(msg, msg["content"].as_str().unwrap_or("")),
+        ];
+        for embed in msg["embeds"].as_array().into_iter().flatten() {
+            if let Some(title) = embed["title"].as_str() {
+                lines.push(format!("[embed] {}", title));
+            }
+            if let Some(url) = embed["url"].as_str() {
+                lines.push(format!("  {}", url));
+            }
+            if let Some(description) = embed["description"].as_str() {
+                lines.push(format!("  {}", description));
+            }
+        }
+        for sticker in sticker_names(msg) {
+            lines.push(format!("[sticker] {}", sticker));
+        }
+        for attachment in msg["attachments"].as_array().into_iter().flatten() {
+            if self.include_attachments && attachment["data_uri"].is_string() {
+                continue;
+            }
+            let link = attachment["local_path"]
+                .as_str()
+                .filter(|_| self.include_attachments)
+                .or_else(|| attachment["url"].as_str())
+                .unwrap_or("");
+            lines.push(format!(
+                "[attachment] {} {}",
+                attachment["filename"].as_str().unwrap_or("file"),
+                link
+            ));
+        }
+        lines.push(String::new());
+        lines.push(self.jump_link(msg["id"].as_str().unwrap_or("")));
+        lines.join("\n")
+    }
+}
+
+/// RFC 2047 encoded-word for header text that is not plain ASCII.
+pub fn encode_header(text: &str) -> String {
+    let clean: String = text.chars().filter(|c| !c.is_control()).collect();
+    if clean.is_ascii() {
+        return clean;
+    }
+    // Encoded words are limited to 75 characters, so long text is split on char boundaries.
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in clean.chars() {
+        if chunk.len() + c.len_utf8() > 45 {
+            words.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    words.push(chunk);
+    words
+        .iter()
+        .map(|w| format!("=?UTF-8?B?{}?=", base64_encode(w.as_bytes())))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Display name of an address: a quoted string, or encoded words when not ASCII.
+fn mailbox_name(name: &str) -> String {
+    let clean: String = name.chars().filter(|c| !c.is_control()).collect();
+    if !clean.is_ascii() {
+        return encode_header(&clean);
+    }
+    format!("\"{}\"", clean.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `name="value"`, or the RFC 2231 `name*=UTF-8''...` form for non-ASCII values.
+fn mime_param(name: &str, value: &str) -> String {
+    if value.is_ascii() && !value.contains(['"', '\\']) && !value.chars().any(char::is_control) {
+        return format!("{}=\"{}\"", name, value);
+    }
+    let encoded: String = value
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+    format!("{}*=UTF-8''{}", name, encoded)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Quoted-printable body (RFC 2045) with LF line breaks.
+pub fn quoted_printable(text: &str) -> String {
+    let mut out = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let bytes = line.as_bytes();
+        let mut width = 0;
+        for (j, &b) in bytes.iter().enumerate() {
+            let trailing_space = (b == b' ' || b == b'\t') && j + 1 == bytes.len();
+            let literal = (b == b'\t' || (b' '..=b'~').contains(&b)) && b != b'=';
+            let encoded = if literal && !trailing_space {
+                (b as char).to_string()
+            } else {
+                format!("={:02X}", b)
+            };
+            if width + encoded.len() > LINE_LIMIT - 1 {
+                out.push_str("=\n");
+                width = 0;
+            }
+            width += encoded.len();
+            out.push_str(&encoded);
+        }
+    }
+    out
+}
+
+/// Escapes `From ` lines the mboxrd way, so a reader never mistakes them for separators.
+pub fn escape_from_lines(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            if line.trim_start_matches('>').starts_with("From ") {
+                format!(">{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ExportFormat for MboxFormat {
+    fn extension(&self) -> &'static str {
+        "mbox"
+    }
+
+    fn inlines_attachments(&self) -> bool {
+        self.include_attachments
+    }
+
+    fn dm_only(&self) -> bool {
+        true
+    }
+
+    fn begin(
+        &mut self,
+        _out: &mut dyn Write,
+        channel: &ChannelInfo,
+        _page: usize,
+        _prev: Option<&str>,
+    ) -> io::Result<()> {
+        self.channel = Some(channel.clone());
+        self.threads.clear();
+        Ok(())
+    }
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        let id = msg["id"].as_str().unwrap_or("0");
+        let author = &msg["author"];
+        let author_id = author["id"].as_str().unwrap_or("0");
+        let sent = parse_timestamp(msg["timestamp"].as_str().unwrap_or(""))
+            .unwrap_or_default()
+            .with_timezone(&self.ctx.offset);
+        let channel_name = self
+            .channel
+            .as_ref()
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+
+        let mut headers = vec![
+            format!(
+                "From: {} <{}@users.discord.com>",
+                mailbox_name(author_name(author)),
+                author_id
+            ),
+            format!(
+                "To: {} <{}@channels.discord.com>",
+                mailbox_name(&channel_name),
+                self.channel.as_ref().map(|c| c.id.as_str()).unwrap_or("0")
+            ),
+            format!("Subject: {}", encode_header(&channel_name)),
+            format!("Date: {}", sent.to_rfc2822()),
+            format!("Message-ID: {}", self.message_id(id)),
+        ];
+
+        let parent = msg["message_reference"]["message_id"]
+            .as_str()
+            .or_else(|| msg["referenced_message"]["id"].as_str());
+        if let Some(parent) = parent {
+            let mut references = self.threads.get(parent).cloned().unwrap_or_default();
+            references.push(self.message_id(parent));
+            if references.len() > MAX_REFERENCES {
+                references.drain(..references.len() - MAX_REFERENCES);
+            }
+            headers.push(format!("In-Reply-To: {}", self.message_id(parent)));
+            // Folded, one ID per line, to stay under the header line limit.
+            headers.push(format!("References: {}", references.join("\n ")));
+            self.threads.insert(id.to_string(), references);
+        }
+
+        headers.push(format!("X-Discord-Message-Id: {}", id));
+        headers.push(format!("X-Discord-Author-Id: {}", author_id));
+        if let Some(edited) = msg["edited_timestamp"].as_str() {
+            headers.push(format!("X-Discord-Edited: {}", edited));
+        }
+        headers.push(format!("Archived-At: <{}>", self.jump_link(id)));
+        headers.push("MIME-Version: 1.0".to_string());
+
+        let text = escape_from_lines(&quoted_printable(&self.body_text(msg)));
+        let text_part =
+            "Content-Type: text/plain; charset=utf-8\nContent-Transfer-Encoding: quoted-printable";
+        let parts: Vec<&Value> = msg["attachments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|a| self.include_attachments && a["data_uri"].is_string())
+            .collect();
+
+        writeln!(
+            out,
+            "From {}@users.discord.com {}",
+            author_id,
+            sent.format("%a %b %e %H:%M:%S %Y")
+        )?;
+        for header in &headers {
+            writeln!(out, "{}", header)?;
+        }
+        if parts.is_empty() {
+            writeln!(out, "{}\n\n{}\n", text_part, text)?;
+            return Ok(());
+        }
+
+        let boundary = format!("discord-{}", id);
+        writeln!(
+            out,
+            "Content-Type: multipart/mixed; boundary=\"{}\"\n\n--{}\n{}\n\n{}",
+            boundary, boundary, text_part, text
+        )?;
+        for attachment in parts {
+            let data_uri = attachment["data_uri"].as_str().unwrap_or("");
+            let (meta, data) = data_uri.split_once(',').unwrap_or(("", ""));
+            let content_type = meta
+                .strip_prefix("data:")
+                .and_then(|m| m.strip_suffix(";base64"))
+                .filter(|t| !t.is_empty())
+                .unwrap_or("application/octet-stream");
+            let name = attachment["filename"].as_str().unwrap_or("file");
+            writeln!(
+                out,
+                "--{}\nContent-Type: {}; {}\nContent-Disposition: attachment; {}\nContent-Transfer-Encoding: base64\n",
+                boundary,
+                content_type,
+                mime_param("name", name),
+                mime_param("filename", name)
+            )?;
+            for line in data.as_bytes().chunks(LINE_LIMIT) {
+                out.write_all(line)?;
+                writeln!(out)?;
+            }
+        }
+        writeln!(out, "--{}--\n", boundary)
+    }
+
+    fn end(&mut self, _out: &mut dyn Write, _next: Option<&str>) -> io::Result<()> {
+        self.channel = None;
+        self.threads.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use super::format::{author_name, parse_timestamp};
+use crate::core::cache::CacheManager;
+use chrono::{DateTime, FixedOffset, NaiveDate, Offset, Utc};
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+static TOKEN_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// A Discord inline token such as a mention, custom emoji or timestamp.
+pub enum Token<'a> {
+    User(&'a str),
+    Role(&'a str),
+    Channel(&'a str),
+    Emoji {
+        name: &'a str,
+        id: &'a str,
+        animated: bool,
+    },
+    Timestamp {
+        secs: i64,
+        style: char,
+    },
+}
+
+/// Matches `<@id>`, `<@!id>`, `<@&id>`, `<#id>`, `<:name:id>`, `<a:name:id>` and `<t:secs:style>`.
+pub fn token_regex() -> &'static Regex {
+    TOKEN_REGEX.get_or_init(|| {
+        Regex::new(r"<(@!?|@&|#)(\d+)>|<(a?):(\w+):(\d+)>|<t:(-?\d+)(?::([tTdDfFR]))?>").unwrap()
+    })
+}
+
+pub fn parse_token<'a>(caps: &Captures<'a>) -> Option<Token<'a>> {
+    if let (Some(kind), Some(id)) = (caps.get(1), caps.get(2)) {
+        return Some(match kind.as_str() {
+            "#" => Token::Channel(id.as_str()),
+            "@&" => Token::Role(id.as_str()),
+            _ => Token::User(id.as_str()),
+        });
+    }
+    if let (Some(name), Some(id)) = (caps.get(4), caps.get(5)) {
+        return Some(Token::Emoji {
+            name: name.as_str(),
+            id: id.as_str(),
+            animated: caps.get(3).is_some_and(|a| a.as_str() == "a"),
+        });
+    }
+    Some(Token::Timestamp {
+        secs: caps.get(6)?.as_str().parse().ok()?,
+        style: caps
+            .get(7)
+            .and_then(|s| s.as_str().chars().next())
+            .unwrap_or('f'),
+    })
+}
+
+/// Names and time zone used when turning raw messages into readable exports.
+pub struct RenderContext {
+    pub users: HashMap<String, String>,
+    pub channels: HashMap<String, String>,
+    pub offset: FixedOffset,
+}
+
+impl Default for RenderContext {
+    fn default() -> Self {
+        Self {
+            users: HashMap::new(),
+            channels: HashMap::new(),
+            offset: Utc.fix(),
+        }
+    }
+}
+
+impl RenderContext {
+    /// Loads cached user and channel names. `utc_offset_minutes` defaults to the
+    /// machine's local offset.
+    pub fn from_cache(app: &AppHandle, identity_id: &str, utc_offset_minutes: Option<i32>) -> Self {
+        let offset = utc_offset_minutes
+            .and_then(|m| FixedOffset::east_opt(m * 60))
+            .unwrap_or_else(|| chrono::Local::now().offset().fix());
+        Self {
+            users: CacheManager::user_names(app, identity_id).unwrap_or_default(),
+            channels: CacheManager::channel_names(app, identity_id).unwrap_or_default(),
+            offset,
+        }
+    }
+
+    pub fn local(&self, dt: DateTime<Utc>) -> DateTime<FixedOffset> {
+        dt.with_timezone(&self.offset)
+    }
+
+    pub fn day_of(&self, msg: &Value) -> Option<NaiveDate> {
+        Some(
+            self.local(parse_timestamp(msg["timestamp"].as_str()?)?)
+                .date_naive(),
+        )
+    }
+
+    /// Formats an ISO message timestamp in the export's time zone.
+    pub fn format_timestamp(&self, ts: &str) -> String {
+        parse_timestamp(ts)
+            .map(|dt| self.local(dt).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| ts.to_string())
+    }
+
+    /// Formats a `<t:secs:style>` token the way the Discord client displays it.
+    pub fn format_unix(&self, secs: i64, style: char) -> String {
+        let Some(dt) = DateTime::from_timestamp(secs, 0) else {
+            return secs.to_string();
+        };
+        let pattern = match style {
+            't' => "%H:%M",
+            'T' => "%H:%M:%S",
+            'd' => "%d/%m/%Y",
+            'D' => "%B %-d, %Y",
+            'F' => "%A, %B %-d, %Y %H:%M",
+            _ => "%B %-d, %Y %H:%M",
+        };
+        self.local(dt).format(pattern).to_string()
+    }
+
+    /// Resolves a user ID from the message's own mention list, then the cache.
+    pub fn user_name(&self, msg: &Value, id: &str) -> Option<String> {
+        msg["mentions"]
+            .as_array()
+            .and_then(|list| list.iter().find(|u| u["id"].as_str() == Some(id)))
+            .map(|u| author_name(u).to_string())
+            .or_else(|| self.users.get(id).cloned())
+    }
+
+    pub fn channel_name(&self, id: &str) -> Option<&str> {
+        self.channels.get(id).map(|s| s.as_str())
+    }
+
+    /// Plain-text rendering of a token, used by the non-HTML formats.
+    pub fn token_text(&self, msg: &Value, token: &Token) -> String {
+        match token {
+            Token::User(id) => format!(
+                "@{}",
+                self.user_name(msg, id).unwrap_or_else(|| id.to_string())
+            ),
+            Token::Role(id) => format!("@role:{}", id),
+            Token::Channel(id) => format!("#{}", self.channel_name(id).unwrap_or(id)),
+            Token::Emoji { name, .. } => format!(":{}:", name),
+            Token::Timestamp { secs, style } => self.format_unix(*secs, *style),
+        }
+    }
+
+    /// Replaces every Discord token in `content` with readable text.
+    pub fn resolve_plain(&self, msg: &Value, content: &str) -> String {
+        token_regex()
+            .replace_all(content, |caps: &Captures| match parse_token(caps) {
+                Some(token) => self.token_text(msg, &token),
+                None => caps[0].to_string(),
+            })
+            .to_string()
+    }
+}
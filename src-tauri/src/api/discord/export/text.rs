@@ -0,0 +1,179 @@
+use super::format::{
+    ChannelInfo, DaySeparator, ExportFormat, attachments, author_name, reactions, reply_preview,
+    sticker_names,
+};
+use super::render::RenderContext;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// Markdown transcript; message content is kept as written since Discord already uses markdown.
+pub struct MarkdownFormat {
+    include_attachments: bool,
+    ctx: Arc<RenderContext>,
+    days: DaySeparator,
+}
+
+impl MarkdownFormat {
+    pub fn new(include_attachments: bool, ctx: Arc<RenderContext>) -> Self {
+        Self {
+            include_attachments,
+            ctx,
+            days: DaySeparator::default(),
+        }
+    }
+}
+
+impl ExportFormat for MarkdownFormat {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn begin(
+        &mut self,
+        out: &mut dyn Write,
+        channel: &ChannelInfo,
+        _page: usize,
+        _prev: Option<&str>,
+    ) -> io::Result<()> {
+        self.days.reset();
+        writeln!(out, "# {}\n", channel.name)?;
+        writeln!(out, "Channel ID: `{}`\n", channel.id)
+    }
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        if let Some(day) = self.days.next(msg, &self.ctx) {
+            writeln!(out, "## {}\n", day)?;
+        }
+        writeln!(
+            out,
+            "**{}** — {}",
+            author_name(&msg["author"]),
+            self.ctx
+                .format_timestamp(msg["timestamp"].as_str().unwrap_or(""))
+        )?;
+        if let Some((author, preview)) = reply_preview(msg) {
+            writeln!(out, "> ↪ {}: {}", author, preview)?;
+        }
+
+        let content = self
+            .ctx
+            .resolve_plain(msg, msg["content"].as_str().unwrap_or(""));
+        if !content.is_empty() {
+            writeln!(out, "{}", content)?;
+        }
+        for embed in msg["embeds"].as_array().into_iter().flatten() {
+            let title = embed["title"].as_str().unwrap_or("Embed");
+            match embed["url"].as_str() {
+                Some(url) => writeln!(out, "> **[{}]({})**", title, url)?,
+                None => writeln!(out, "> **{}**", title)?,
+            }
+            if let Some(description) = embed["description"].as_str() {
+                for line in description.lines() {
+                    writeln!(out, "> {}", line)?;
+                }
+            }
+        }
+        for sticker in sticker_names(msg) {
+            writeln!(out, "*Sticker: {}*", sticker)?;
+        }
+        if self.include_attachments {
+            for (name, url) in attachments(msg) {
+                writeln!(out, "- 📎 [{}]({})", name, url)?;
+            }
+        }
+        let reactions = reactions(msg);
+        if !reactions.is_empty() {
+            let joined: Vec<String> = reactions
+                .iter()
+                .map(|(emoji, count)| format!("{} {}", emoji, count))
+                .collect();
+            writeln!(out, "_{}_", joined.join(" · "))?;
+        }
+        writeln!(out)
+    }
+
+    fn end(&mut self, _out: &mut dyn Write, _next: Option<&str>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Plain text transcript, one `[time] author: content` line per message.
+pub struct TextFormat {
+    include_attachments: bool,
+    ctx: Arc<RenderContext>,
+    days: DaySeparator,
+}
+
+impl TextFormat {
+    pub fn new(include_attachments: bool, ctx: Arc<RenderContext>) -> Self {
+        Self {
+            include_attachments,
+            ctx,
+            days: DaySeparator::default(),
+        }
+    }
+}
+
+impl ExportFormat for TextFormat {
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn begin(
+        &mut self,
+        out: &mut dyn Write,
+        channel: &ChannelInfo,
+        _page: usize,
+        _prev: Option<&str>,
+    ) -> io::Result<()> {
+        self.days.reset();
+        writeln!(out, "{} ({})\n", channel.name, channel.id)
+    }
+
+    fn write_message(&mut self, out: &mut dyn Write, msg: &Value) -> io::Result<()> {
+        if let Some(day) = self.days.next(msg, &self.ctx) {
+            writeln!(out, "\n----- {} -----\n", day)?;
+        }
+        if let Some((author, preview)) = reply_preview(msg) {
+            writeln!(out, "  (reply to {}: {})", author, preview)?;
+        }
+        let content = self
+            .ctx
+            .resolve_plain(msg, msg["content"].as_str().unwrap_or(""));
+        writeln!(
+            out,
+            "[{}] {}: {}",
+            self.ctx
+                .format_timestamp(msg["timestamp"].as_str().unwrap_or("")),
+            author_name(&msg["author"]),
+            content
+        )?;
+        for embed in msg["embeds"].as_array().into_iter().flatten() {
+            if let Some(title) = embed["title"].as_str() {
+                writeln!(out, "  [embed] {}", title)?;
+            }
+        }
+        for sticker in sticker_names(msg) {
+            writeln!(out, "  [sticker] {}", sticker)?;
+        }
+        if self.include_attachments {
+            for (name, url) in attachments(msg) {
+                writeln!(out, "  [attachment] {} {}", name, url)?;
+            }
+        }
+        let reactions = reactions(msg);
+        if !reactions.is_empty() {
+            let joined: Vec<String> = reactions
+                .iter()
+                .map(|(emoji, count)| format!("{} x{}", emoji, count))
+                .collect();
+            writeln!(out, "  [reactions] {}", joined.join(", "))?;
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, _out: &mut dyn Write, _next: Option<&str>) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -9,6 +9,16 @@ pub struct ExportOptions {
     pub format: String,
     #[serde(alias = "outputPath")]
     pub output_path: String,
+    /// HTML exports embed downloaded attachments unless this is false, in which
+    /// case they link to the copies under `attachments/`.
+    #[serde(default, alias = "embedAttachments")]
+    pub embed_attachments: Option<bool>,
+    /// Time zone for rendered timestamps; defaults to the machine's local offset.
+    #[serde(default, alias = "utcOffsetMinutes")]
+    pub utc_offset_minutes: Option<i32>,
+    /// When set, the export is written as one passphrase-encrypted container.
+    #[serde(default)]
+    pub passphrase: Option<String>,
 }
 
 #[derive(serde::Serialize, Clone)]
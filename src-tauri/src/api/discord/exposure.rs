@@ -0,0 +1,121 @@
+// src-tauri/src/api/discord/exposure.rs
+
+use crate::api::rate_limiter::ApiHandle;
+use crate::core::cache::CacheManager;
+use crate::core::error::AppError;
+use crate::core::forensics::exposure::{ExposureReport, ExposureScorer};
+use crate::core::forensics::pii::PIIClassifier;
+use crate::core::logger::Logger;
+use crate::core::vault::Vault;
+use reqwest::Method;
+use tauri::{AppHandle, Manager};
+
+#[tauri::command]
+pub async fn compute_exposure_report(app_handle: AppHandle) -> Result<ExposureReport, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    let api_handle = app_handle.state::<ApiHandle>();
+    let mut findings = Vec::new();
+    let mut skipped = Vec::new();
+
+    Logger::info(
+        &app_handle,
+        "[EXPOSURE] Computing privacy exposure report",
+        None,
+    );
+
+    // 1. Local footprint (own, not yet deleted messages)
+    let conn = CacheManager::get_connection(&app_handle)?;
+    let authored: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM messages WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive'",
+            [&identity.id],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+    let with_attachments: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM messages WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive' AND has_attachments = 1",
+            [&identity.id],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT channel_id FROM messages WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive'",
+    )?;
+    let channel_ids: Vec<String> = stmt
+        .query_map([&identity.id], |r| r.get(0))?
+        .flatten()
+        .collect();
+    findings.extend(ExposureScorer::footprint_findings(
+        authored,
+        with_attachments,
+        &channel_ids,
+    ));
+
+    // 2. Personal data in our own messages
+    let own_pii = PIIClassifier::scan_own_messages(&app_handle, &identity.id)?;
+    let channel_guilds = CacheManager::channel_guild_map(&app_handle, &identity.id)?;
+    findings.extend(ExposureScorer::pii_findings(&own_pii, &channel_guilds));
+
+    // 3. Live account audits
+    match api_handle
+        .send_request_json(
+            Method::GET,
+            "https://discord.com/api/v9/oauth2/tokens",
+            None,
+            &identity.token,
+            identity.is_oauth,
+            None,
+        )
+        .await
+    {
+        Ok(json) => findings.extend(ExposureScorer::integration_findings(
+            json.as_array().map(|a| a.as_slice()).unwrap_or_default(),
+        )),
+        Err(_) => skipped.push("integrations".to_string()),
+    }
+
+    match api_handle
+        .send_request_json(
+            Method::GET,
+            "https://discord.com/api/v9/users/@me/connections",
+            None,
+            &identity.token,
+            identity.is_oauth,
+            None,
+        )
+        .await
+    {
+        Ok(json) => findings.extend(ExposureScorer::connection_findings(
+            json.as_array().map(|a| a.as_slice()).unwrap_or_default(),
+        )),
+        Err(_) => skipped.push("connections".to_string()),
+    }
+
+    match api_handle
+        .send_request_json(
+            Method::GET,
+            "https://discord.com/api/v9/users/@me/sessions",
+            None,
+            &identity.token,
+            identity.is_oauth,
+            None,
+        )
+        .await
+    {
+        Ok(json) => findings.extend(ExposureScorer::session_findings(&json)),
+        Err(_) => skipped.push("sessions".to_string()),
+    }
+
+    let report = ExposureScorer::build_report(findings, skipped);
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[EXPOSURE] Score {} from {} findings",
+            report.score,
+            report.findings.len()
+        ),
+        None,
+    );
+    Ok(report)
+}
@@ -2,6 +2,8 @@
 
 use crate::core::cache::CacheManager;
 use crate::core::error::AppError;
+use crate::core::forensics::analytics::{ActivityAnalytics, FootprintAnalytics};
+use crate::core::vault::Vault;
 use serde::Serialize;
 use tauri::AppHandle;
 
@@ -10,6 +12,7 @@ pub struct FootprintStats {
     pub total_messages: i64,
     pub total_attachments: i64,
     pub server_densities: Vec<ServerDensity>,
+    pub analytics: ActivityAnalytics,
 }
 
 #[derive(Serialize)]
@@ -20,17 +23,25 @@ pub struct ServerDensity {
 }
 
 #[tauri::command]
-pub async fn get_digital_footprint(app_handle: AppHandle) -> Result<FootprintStats, AppError> {
+pub async fn get_digital_footprint(
+    app_handle: AppHandle,
+    utc_offset_minutes: Option<i32>,
+) -> Result<FootprintStats, AppError> {
+    let identity_id = Vault::get_active_identity(&app_handle)?.id;
     let conn = CacheManager::get_connection(&app_handle)?;
 
-    // 1. Total counts
+    // 1. Total counts of our own live messages, as in the analytics below
     let total_messages: i64 = conn
-        .query_row("SELECT COUNT(*) FROM messages", [], |r| r.get(0))
+        .query_row(
+            "SELECT COUNT(*) FROM messages WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive'",
+            [&identity_id],
+            |r| r.get(0),
+        )
         .unwrap_or(0);
     let total_attachments: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM messages WHERE has_attachments = 1",
-            [],
+            "SELECT COUNT(*) FROM messages WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive' AND has_attachments = 1",
+            [&identity_id],
             |r| r.get(0),
         )
         .unwrap_or(0);
@@ -39,10 +50,11 @@ pub async fn get_digital_footprint(app_handle: AppHandle) -> Result<FootprintSta
     let mut stmt = conn
         .prepare(
             "
-        SELECT g.id, g.name, COUNT(m.id) 
+        SELECT g.id, g.name, COUNT(m.id)
         FROM guilds g
-        JOIN channels c ON g.id = c.guild_id
-        JOIN messages m ON c.id = m.channel_id
+        JOIN channels c ON g.id = c.guild_id AND c.identity_id = g.identity_id
+        JOIN messages m ON c.id = m.channel_id AND m.identity_id = c.identity_id
+        WHERE g.identity_id = ?1 AND m.author_id = ?1 AND m.is_deleted = 0 AND m.source != 'archive'
         GROUP BY g.id
         ORDER BY COUNT(m.id) DESC
     ",
@@ -50,7 +62,7 @@ pub async fn get_digital_footprint(app_handle: AppHandle) -> Result<FootprintSta
         .map_err(AppError::from)?;
 
     let densities = stmt
-        .query_map([], |row| {
+        .query_map([&identity_id], |row| {
             Ok(ServerDensity {
                 guild_id: row.get(0)?,
                 guild_name: row.get(1)?,
@@ -64,9 +76,14 @@ pub async fn get_digital_footprint(app_handle: AppHandle) -> Result<FootprintSta
         server_densities.push(d?);
     }
 
+    // 3. Time-based activity analytics
+    let analytics =
+        FootprintAnalytics::compute(&app_handle, &identity_id, utc_offset_minutes.unwrap_or(0))?;
+
     Ok(FootprintStats {
         total_messages,
         total_attachments,
         server_densities,
+        analytics,
     })
 }
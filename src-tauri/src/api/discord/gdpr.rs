@@ -1,17 +1,44 @@
 // src-tauri/src/api/discord/gdpr.rs
 
 use crate::core::error::AppError;
+use crate::core::gdpr::activity::{ActivityLog, ActivityRecord, ActivitySummary};
+use crate::core::gdpr::diff::{PackageDiff, PackageSnapshot};
+use crate::core::gdpr::importer::{ImportSummary, PackageImporter};
+use crate::core::gdpr::parser::{self, PackageEntry};
+use crate::core::gdpr::reader::PackageReader;
 use crate::core::logger::Logger;
-use std::fs::File;
+use crate::core::vault::Vault;
+use std::collections::{HashMap, HashSet};
 use std::io::Read;
-use tauri::AppHandle;
-use zip::ZipArchive;
+use tauri::{AppHandle, Emitter};
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct GdprDiscovery {
+    /// Account ID of the package owner, from `account/user.json`.
+    #[serde(default)]
+    pub owner_id: Option<String>,
     pub channel_ids: Vec<String>,
     pub guild_ids: Vec<String>,
     pub user_ids: Vec<String>,
+    /// Every message ID we sent, keyed by channel ID.
+    #[serde(default)]
+    pub messages: HashMap<String, Vec<DiscoveredMessage>>,
+    /// Participants of one-to-one DMs, from each `channel.json`.
+    #[serde(default)]
+    pub dm_recipients: HashMap<String, Vec<String>>,
+    /// Human readable channel descriptions from `messages/index.json`.
+    #[serde(default)]
+    pub channel_names: HashMap<String, String>,
+    /// Connected accounts from `account/user.json`, as `type:id`.
+    #[serde(default)]
+    pub connections: Vec<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct DiscoveredMessage {
+    pub id: String,
+    /// Unix milliseconds; 0 when the package timestamp could not be parsed.
+    pub timestamp: i64,
 }
 
 #[tauri::command]
@@ -19,65 +46,143 @@ pub async fn process_gdpr_data(
     app_handle: AppHandle,
     zip_path: String,
 ) -> Result<GdprDiscovery, AppError> {
+    blocking(move || discover_package(&app_handle, &zip_path)).await
+}
+
+/// Runs package work on the blocking pool, so zip decompression and cache writes never
+/// stall the async runtime.
+async fn blocking<T, F>(work: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::new(&e.to_string(), "package_task_failed"))
+        .and_then(|result| result)
+}
+
+/// Walks a package (zip or extracted folder) and collects the IDs it references.
+pub fn discover_package(app_handle: &AppHandle, zip_path: &str) -> Result<GdprDiscovery, AppError> {
     Logger::info(
-        &app_handle,
+        app_handle,
         &format!("[GDPR] Parsing data package: {}", zip_path),
         None,
     );
 
-    let file = File::open(&zip_path)
-        .map_err(|e| AppError::new(&format!("Failed to open data package: {}", e), "io_error"))?;
-
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| AppError::new(&format!("Invalid zip archive: {}", e), "zip_error"))?;
-
-    let mut channel_ids = std::collections::HashSet::new();
-    let mut guild_ids = std::collections::HashSet::new();
-    let mut user_ids = std::collections::HashSet::new();
+    let mut reader = PackageReader::open(zip_path)?;
+    let mut channel_ids = HashSet::new();
+    let mut guild_ids = HashSet::new();
+    let mut user_ids = HashSet::new();
+    let mut messages: HashMap<String, Vec<DiscoveredMessage>> = HashMap::new();
+    let mut dm_recipients = HashMap::new();
+    let mut channel_names = HashMap::new();
+    let mut connections = HashSet::new();
+    let mut owner_id = None;
+    let mut last_percent = None;
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i).unwrap();
-        let name = file.name().to_string();
-
-        if name.starts_with("messages/c") && name.ends_with("/messages.csv") {
-            // Extract channel ID from path: messages/c12345/messages.csv
-            if let Some(id) = name.split('/').nth(1).and_then(|s| s.get(1..)) {
-                channel_ids.insert(id.to_string());
-            }
-        } else if name == "servers/index.json" {
-            let mut content = String::new();
-            let _ = file.read_to_string(&mut content);
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content)
-                && let Some(arr) = json.as_array()
+    reader.for_each(
+        |file, content| {
+            if let PackageEntry::MessagesCsv { channel_id }
+            | PackageEntry::MessagesJson { channel_id } = &file.kind
             {
-                for server in arr {
-                    if let Some(id) = server["id"].as_str() {
-                        guild_ids.insert(id.to_string());
-                    }
+                let found = messages.entry(channel_id.clone()).or_default();
+                let collect = |m: parser::PackageMessage| -> Result<(), AppError> {
+                    found.push(DiscoveredMessage {
+                        id: m.id,
+                        timestamp: m.timestamp,
+                    });
+                    Ok(())
+                };
+                if matches!(file.kind, PackageEntry::MessagesCsv { .. }) {
+                    parser::read_messages_csv(&mut *content, collect)?;
+                } else {
+                    parser::read_messages_json(&mut *content, collect)?;
                 }
+                channel_ids.insert(channel_id.clone());
+                return Ok(());
             }
-        } else if name == "account/user.json" {
-            // Maybe find friends? Actually relationships/index.json is better
-        } else if name == "relationships/index.json" {
-            let mut content = String::new();
-            let _ = file.read_to_string(&mut content);
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content)
-                && let Some(arr) = json.as_array()
-            {
-                for rel in arr {
-                    if let Some(id) = rel["id"].as_str() {
-                        user_ids.insert(id.to_string());
+
+            let mut text = String::new();
+            match &file.kind {
+                PackageEntry::ChannelMeta { .. } => {
+                    content.read_to_string(&mut text)?;
+                    if let Some(channel) = parser::parse_channel_json(&text)
+                        && channel.channel_type == 1
+                    {
+                        dm_recipients.insert(channel.id, channel.recipients);
                     }
                 }
+                PackageEntry::MessagesIndex => {
+                    content.read_to_string(&mut text)?;
+                    channel_names.extend(
+                        parser::parse_messages_index(&text)
+                            .into_iter()
+                            .filter_map(|(id, name)| Some((id, name?))),
+                    );
+                }
+                PackageEntry::ServersIndex => {
+                    content.read_to_string(&mut text)?;
+                    guild_ids.extend(
+                        parser::parse_servers_index(&text)
+                            .into_iter()
+                            .map(|(id, _)| id),
+                    );
+                }
+                PackageEntry::Account | PackageEntry::RelationshipsIndex => {
+                    let json: serde_json::Value = serde_json::from_reader(content)?;
+                    let list = if file.kind == PackageEntry::Account {
+                        owner_id = parser::snowflake(&json["id"]);
+                        connections.extend(
+                            json["connections"]
+                                .as_array()
+                                .into_iter()
+                                .flatten()
+                                .filter_map(|c| {
+                                    Some(format!("{}:{}", c["type"].as_str()?, c["id"].as_str()?))
+                                }),
+                        );
+                        &json["relationships"]
+                    } else {
+                        &json
+                    };
+                    user_ids.extend(
+                        list.as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|rel| parser::snowflake(&rel["id"])),
+                    );
+                }
+                _ => {}
             }
-        }
+            Ok(())
+        },
+        |progress| {
+            let percent = progress.percent();
+            if last_percent != Some(percent) {
+                last_percent = Some(percent);
+                let _ = app_handle.emit("gdpr_progress", progress);
+            }
+        },
+    )?;
+
+    if !reader.skipped.is_empty() {
+        Logger::warn(
+            app_handle,
+            &format!(
+                "[GDPR] Skipped {} unreadable package entries",
+                reader.skipped.len()
+            ),
+            Some(serde_json::json!({ "entries": reader.skipped })),
+        );
     }
 
     Logger::info(
-        &app_handle,
+        app_handle,
         &format!(
-            "[GDPR] Discovery complete: {} channels, {} guilds, {} relationships",
+            "[GDPR] Discovery complete: {} channels, {} messages, {} guilds, {} relationships",
             channel_ids.len(),
+            messages.values().map(|m| m.len()).sum::<usize>(),
             guild_ids.len(),
             user_ids.len()
         ),
@@ -85,8 +190,78 @@ pub async fn process_gdpr_data(
     );
 
     Ok(GdprDiscovery {
+        owner_id,
         channel_ids: channel_ids.into_iter().collect(),
         guild_ids: guild_ids.into_iter().collect(),
         user_ids: user_ids.into_iter().collect(),
+        messages,
+        dm_recipients,
+        channel_names,
+        connections: connections.into_iter().collect(),
+    })
+}
+
+/// Imports message contents, channels, servers and relationships from a data package
+/// into the cache, tagged as the "package" source.
+#[tauri::command]
+pub async fn import_gdpr_package(
+    app_handle: AppHandle,
+    zip_path: String,
+) -> Result<ImportSummary, AppError> {
+    blocking(move || PackageImporter::import(&app_handle, &zip_path)).await
+}
+
+/// Summarizes the telemetry Discord retained about the active identity.
+#[tauri::command]
+pub async fn get_activity_summary(app_handle: AppHandle) -> Result<ActivitySummary, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    ActivityLog::summary(&app_handle, &identity.id)
+}
+
+#[tauri::command]
+pub async fn list_activity_events(
+    app_handle: AppHandle,
+    event_type: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<ActivityRecord>, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    ActivityLog::events(
+        &app_handle,
+        &identity.id,
+        event_type.as_deref(),
+        limit.unwrap_or(100).min(1000),
+        offset.unwrap_or(0),
+    )
+}
+
+/// Compares an older and a newer data package, e.g. to confirm a cleanup reached Discord.
+#[tauri::command]
+pub async fn diff_gdpr_packages(
+    app_handle: AppHandle,
+    old_path: String,
+    new_path: String,
+) -> Result<PackageDiff, AppError> {
+    blocking(move || {
+        let old = PackageSnapshot::from_discovery(&discover_package(&app_handle, &old_path)?);
+        let new = PackageSnapshot::from_discovery(&discover_package(&app_handle, &new_path)?);
+        Ok(PackageDiff::between(&old, &new))
+    })
+    .await
+}
+
+/// Compares a data package against the live cache of the active identity.
+/// Messages are only compared for channels that have been deep scanned.
+#[tauri::command]
+pub async fn diff_gdpr_package_with_cache(
+    app_handle: AppHandle,
+    zip_path: String,
+) -> Result<PackageDiff, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    blocking(move || {
+        let package = PackageSnapshot::from_discovery(&discover_package(&app_handle, &zip_path)?);
+        let cache = PackageSnapshot::from_cache(&app_handle, &identity.id)?;
+        Ok(PackageDiff::between(&package, &cache))
     })
+    .await
 }
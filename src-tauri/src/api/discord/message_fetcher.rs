@@ -40,3 +40,61 @@ pub async fn fetch_all_messages(
 
     Ok(all_messages)
 }
+
+/// Walks a channel from its oldest message forward, one page of up to 100 at a time,
+/// so callers can stream a channel without holding all of it in memory.
+pub struct MessagePager {
+    channel_id: String,
+    after: String,
+    done: bool,
+}
+
+impl MessagePager {
+    pub fn new(channel_id: &str) -> Self {
+        Self {
+            channel_id: channel_id.to_string(),
+            after: "0".to_string(),
+            done: false,
+        }
+    }
+
+    /// Returns the next page in chronological order, or `None` once the channel is exhausted.
+    pub async fn next_page(
+        &mut self,
+        api_handle: &ApiHandle,
+        token: &str,
+        is_bearer: bool,
+    ) -> Result<Option<Vec<serde_json::Value>>, AppError> {
+        if self.done {
+            return Ok(None);
+        }
+        let url = format!(
+            "https://discord.com/api/v9/channels/{}/messages?limit=100&after={}",
+            self.channel_id, self.after
+        );
+        let response = api_handle
+            .send_request_json(reqwest::Method::GET, &url, None, token, is_bearer, None)
+            .await?;
+        let mut messages: Vec<serde_json::Value> =
+            serde_json::from_value(response).map_err(AppError::from)?;
+
+        if messages.len() < 100 {
+            self.done = true;
+        }
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        // Pages arrive newest-first; snowflakes sort chronologically.
+        messages.sort_by_key(|m| {
+            m["id"]
+                .as_str()
+                .and_then(|id| id.parse::<u64>().ok())
+                .unwrap_or(0)
+        });
+        if let Some(id) = messages.last().and_then(|m| m["id"].as_str()) {
+            self.after = id.to_string();
+        }
+        Ok(Some(messages))
+    }
+}
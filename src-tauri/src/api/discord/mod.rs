@@ -3,6 +3,7 @@
 pub mod billing;
 pub mod bulk;
 pub mod export;
+pub mod exposure;
 pub mod footprint;
 pub mod gdpr;
 pub mod message_fetcher;
@@ -17,6 +18,7 @@ pub mod verification;
 pub use billing::*;
 pub use bulk::*;
 pub use export::*;
+pub use exposure::*;
 pub use footprint::*;
 pub use gdpr::*;
 pub use ops::*;
@@ -2,6 +2,7 @@
 
 use crate::api::rate_limiter::ApiHandle;
 use crate::core::error::AppError;
+use crate::core::gdpr::harvest::{HarvestRecord, HarvestSettings, HarvestTracker};
 use crate::core::logger::Logger;
 use crate::core::op_manager::OperationManager;
 use crate::core::vault::Vault;
@@ -151,40 +152,86 @@ pub async fn nitro_stealth_wipe(app_handle: AppHandle) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Requests a data package and records it so the janitor can poll it until ready.
 #[tauri::command]
 pub async fn trigger_data_harvest(app_handle: AppHandle) -> Result<serde_json::Value, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let identity = Vault::get_active_identity(&app_handle)?;
+    HarvestTracker::ensure_request_allowed(&app_handle, &identity.id)?;
     let api_handle = app_handle.state::<ApiHandle>();
 
-    api_handle
+    let response = api_handle
         .send_request_json(
             reqwest::Method::POST,
             "https://discord.com/api/v9/users/@me/harvest",
             Some(serde_json::json!({
                 "backends": ["Account", "Analytics", "Activities", "Ads", "Messages", "Servers", "Zendesk"]
             })),
-            &token,
-            is_bearer,
+            &identity.token,
+            identity.is_oauth,
             None,
         )
-        .await
+        .await?;
+
+    HarvestTracker::record_request(&app_handle, &identity.id, &response)?;
+    Logger::info(
+        &app_handle,
+        &format!("[GDPR] Data package requested for {}", identity.id),
+        None,
+    );
+    Ok(response)
 }
 
 #[tauri::command]
 pub async fn get_harvest_status(app_handle: AppHandle) -> Result<serde_json::Value, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let identity = Vault::get_active_identity(&app_handle)?;
     let api_handle = app_handle.state::<ApiHandle>();
 
-    api_handle
+    let status = api_handle
         .send_request_json(
             reqwest::Method::GET,
             "https://discord.com/api/v9/users/@me/harvest",
             None,
-            &token,
-            is_bearer,
+            &identity.token,
+            identity.is_oauth,
             None,
         )
-        .await
+        .await?;
+
+    HarvestTracker::update_from_status(&app_handle, &identity.id, &status)?;
+    Ok(status)
+}
+
+/// Tracked data package requests of every identity, oldest first.
+#[tauri::command]
+pub async fn list_harvest_requests(app_handle: AppHandle) -> Result<Vec<HarvestRecord>, AppError> {
+    Ok(HarvestTracker::load_records(&app_handle))
+}
+
+#[tauri::command]
+pub async fn get_harvest_settings(app_handle: AppHandle) -> Result<HarvestSettings, AppError> {
+    Ok(HarvestTracker::load_settings(&app_handle))
+}
+
+/// Sets the folder watched for downloaded packages. Passing no folder disables auto-import.
+#[tauri::command]
+pub async fn set_harvest_watch_dir(
+    app_handle: AppHandle,
+    watch_dir: Option<String>,
+    auto_import: bool,
+) -> Result<(), AppError> {
+    if let Some(dir) = &watch_dir
+        && !std::path::Path::new(dir).is_dir()
+    {
+        return Err(AppError::new(
+            &format!("Watch folder does not exist: {}", dir),
+            "invalid_path",
+        ));
+    }
+    let settings = HarvestSettings {
+        auto_import: auto_import && watch_dir.is_some(),
+        watch_dir,
+    };
+    HarvestTracker::save_settings(&app_handle, &settings)
 }
 
 #[tauri::command]
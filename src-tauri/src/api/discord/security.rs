@@ -1,6 +1,7 @@
 // src-tauri/src/api/discord/security.rs
 
 use crate::api::rate_limiter::ApiHandle;
+use crate::core::cache::CacheManager;
 use crate::core::error::AppError;
 use crate::core::forensics::auditor::IntegrationAuditor;
 use crate::core::logger::Logger;
@@ -151,7 +152,7 @@ pub async fn fetch_user_connections(app_handle: AppHandle) -> Result<serde_json:
         None,
     );
 
-    api_handle
+    let json = api_handle
         .send_request_json(
             Method::GET,
             "https://discord.com/api/v9/users/@me/connections",
@@ -160,7 +161,14 @@ pub async fn fetch_user_connections(app_handle: AppHandle) -> Result<serde_json:
             is_bearer,
             None,
         )
-        .await
+        .await?;
+
+    if let (Ok(identity), Some(connections)) =
+        (Vault::get_active_identity(&app_handle), json.as_array())
+    {
+        let _ = CacheManager::upsert_connections(&app_handle, &identity.id, connections);
+    }
+    Ok(json)
 }
 
 #[tauri::command]
@@ -3,6 +3,7 @@
 use super::types::{Channel, Guild, Relationship};
 use crate::api::rate_limiter::ApiHandle;
 use crate::core::cache::CacheManager;
+use crate::core::cache::archive::{ArchiveImportSummary, ArchiveImporter};
 use crate::core::error::AppError;
 use crate::core::logger::Logger;
 use crate::core::vault::Vault;
@@ -95,6 +96,16 @@ pub async fn fetch_channels(
                         .map(|s| s.to_string())
                         .or(Some("Unnamed Group DM".to_string()))
                 };
+                if let (Some(id), Some(recipients)) =
+                    (ch["id"].as_str(), ch["recipients"].as_array())
+                {
+                    let _ = CacheManager::upsert_channel_recipients(
+                        &app_handle,
+                        &identity_id,
+                        id,
+                        recipients,
+                    );
+                }
                 result.push(Channel {
                     id: ch["id"].as_str().unwrap_or_default().to_string(),
                     name,
@@ -112,6 +123,7 @@ pub async fn fetch_relationships(app_handle: AppHandle) -> Result<Vec<Relationsh
     let identity = Vault::get_active_identity(&app_handle)?;
     let token = identity.token;
     let is_bearer = identity.is_oauth;
+    let identity_id = identity.id;
     let api_handle = app_handle.state::<ApiHandle>();
 
     let json = api_handle
@@ -125,7 +137,9 @@ pub async fn fetch_relationships(app_handle: AppHandle) -> Result<Vec<Relationsh
         )
         .await?;
 
-    serde_json::from_value(json).map_err(AppError::from)
+    let relationships: Vec<Relationship> = serde_json::from_value(json).map_err(AppError::from)?;
+    let _ = CacheManager::upsert_relationships(&app_handle, &identity_id, &relationships);
+    Ok(relationships)
 }
 
 #[tauri::command]
@@ -173,7 +187,32 @@ pub async fn search_local_cache(
     app_handle: AppHandle,
     query: String,
 ) -> Result<Vec<serde_json::Value>, AppError> {
-    CacheManager::search_messages(&app_handle, &query)
+    let identity = Vault::get_active_identity(&app_handle)?;
+    CacheManager::search_messages(&app_handle, &identity.id, &query)
+}
+
+/// Restores the app's own exports (JSON/JSONL, guild archives, forensic exports and
+/// encrypted containers) into the cache so they stay searchable after a purge.
+#[tauri::command]
+pub async fn import_local_archive(
+    app_handle: AppHandle,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<ArchiveImportSummary, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    ArchiveImporter::import(&app_handle, &identity.id, &path, passphrase.as_deref())
+}
+
+#[tauri::command]
+pub async fn wipe_identity_cache(
+    app_handle: AppHandle,
+    identity_id: Option<String>,
+) -> Result<(), AppError> {
+    let identity_id = match identity_id {
+        Some(id) => id,
+        None => Vault::get_active_identity(&app_handle)?.id,
+    };
+    CacheManager::wipe_identity(&app_handle, &identity_id)
 }
 
 #[tauri::command]
@@ -195,6 +234,8 @@ pub async fn start_deep_scan(
             None,
         );
         let mut last_id: Option<String> = None;
+        let mut newest_id: Option<String> = None;
+        let mut completed = false;
         loop {
             let mut url = format!(
                 "https://discord.com/api/v9/channels/{}/messages?limit=100",
@@ -213,8 +254,14 @@ pub async fn start_deep_scan(
             };
 
             if messages.is_empty() {
+                completed = true;
                 break;
             }
+            if newest_id.is_none() {
+                newest_id = messages
+                    .first()
+                    .and_then(|m| m["id"].as_str().map(|s| s.to_string()));
+            }
             last_id = messages
                 .last()
                 .and_then(|m| m["id"].as_str().map(|s| s.to_string()));
@@ -227,6 +274,16 @@ pub async fn start_deep_scan(
             }
             let _ = window.emit("scan_progress", serde_json::json!({ "current": i + 1, "total": channel_ids.len(), "channel_id": channel_id }));
         }
+
+        // Only a scan that reached the start of history can drive cache-first purges.
+        if completed {
+            let _ = CacheManager::record_sync_cursor(
+                &app_handle,
+                &identity_id,
+                channel_id,
+                newest_id.as_deref(),
+            );
+        }
     }
     Ok(())
 }
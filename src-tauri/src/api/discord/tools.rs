@@ -2,6 +2,7 @@
 
 use crate::api::rate_limiter::ApiHandle;
 use crate::core::error::AppError;
+use crate::core::forensics::container;
 use crate::core::logger::Logger;
 use crate::core::op_manager::OperationManager;
 use crate::core::vault::Vault;
@@ -215,13 +216,142 @@ pub async fn start_burner_protocol(app_handle: AppHandle) -> Result<(), AppError
 pub async fn scan_for_pii(
     app_handle: AppHandle,
 ) -> Result<Vec<crate::core::forensics::pii::PIIResult>, AppError> {
-    crate::core::forensics::pii::PIIClassifier::scan_cache(&app_handle)
+    let identity = Vault::get_active_identity(&app_handle)?;
+    crate::core::forensics::pii::PIIClassifier::scan_cache(&app_handle, &identity.id)
+}
+
+#[tauri::command]
+pub async fn analyze_identity_correlation(
+    app_handle: AppHandle,
+) -> Result<crate::core::forensics::correlation::CorrelationReport, AppError> {
+    crate::core::forensics::correlation::IdentityCorrelator::analyze_all(&app_handle)
 }
 
 #[tauri::command]
 pub async fn start_forensic_export(
     app_handle: AppHandle,
     output_path: String,
+    passphrase: Option<String>,
 ) -> Result<(), AppError> {
-    crate::core::forensics::export::ExportForensics::generate_json_ld(&app_handle, &output_path)
+    let identity = Vault::get_active_identity(&app_handle)?;
+    let Some(passphrase) = passphrase else {
+        return crate::core::forensics::export::ExportForensics::generate_json_ld(
+            &app_handle,
+            &identity,
+            &output_path,
+        );
+    };
+
+    container::check_passphrase(&passphrase)?;
+    let output = std::path::Path::new(&output_path);
+    let staging = container::StagingDir::create("forensic_export")?;
+    let plain = staging.path().join(
+        output
+            .file_name()
+            .unwrap_or(std::ffi::OsStr::new("forensic_export.jsonld")),
+    );
+    crate::core::forensics::export::ExportForensics::generate_json_ld(
+        &app_handle,
+        &identity,
+        &plain.to_string_lossy(),
+    )?;
+    container::seal_file(&plain, &container::sealed_path(output), &passphrase)
+}
+
+/// Recomputes the record hashes and Merkle root of a forensic export.
+#[tauri::command]
+pub async fn verify_forensic_export(
+    path: String,
+) -> Result<crate::core::forensics::export::ForensicVerification, AppError> {
+    crate::core::forensics::export::ExportForensics::verify_json_ld(&path)
+}
+
+#[tauri::command]
+pub async fn create_encrypted_backup(
+    app_handle: AppHandle,
+    channel_id: String,
+    output_path: String,
+    passphrase: String,
+) -> Result<(), AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    crate::core::forensics::backup::ForensicBackup::create_encrypted_backup(
+        &app_handle,
+        &identity.id,
+        &channel_id,
+        &output_path,
+        &passphrase,
+    )
+}
+
+/// Decrypts a sealed export. Without `output_path` the payload is written next to the
+/// container under its original name. Nothing is left behind if verification fails.
+#[tauri::command]
+pub async fn decrypt_export_container(
+    app_handle: AppHandle,
+    input_path: String,
+    passphrase: String,
+    output_path: Option<String>,
+) -> Result<container::ContainerInfo, AppError> {
+    let input = std::path::Path::new(&input_path);
+    let part = input.with_extension("decrypting");
+    let result = container::open_container(
+        &mut std::io::BufReader::new(std::fs::File::open(input)?),
+        &passphrase,
+        &mut std::io::BufWriter::new(std::fs::File::create(&part)?),
+    );
+    let info = match result {
+        Ok(info) => info,
+        Err(e) => {
+            let _ = std::fs::remove_file(&part);
+            return Err(e);
+        }
+    };
+
+    // The content name comes from the container header, so only its last component is
+    // trusted; anything else could write outside the container's folder.
+    let dest = match output_path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => match std::path::Path::new(&info.content_name).file_name() {
+            Some(name) => input.with_file_name(name),
+            None => {
+                let _ = std::fs::remove_file(&part);
+                return Err(AppError::new(
+                    "The container does not name a valid output file",
+                    "container_invalid",
+                ));
+            }
+        },
+    };
+    if dest.exists() {
+        let _ = std::fs::remove_file(&part);
+        return Err(AppError::new(
+            "The output file already exists; choose another destination.",
+            "output_exists",
+        ));
+    }
+    std::fs::rename(&part, &dest)?;
+    Logger::info(
+        &app_handle,
+        &format!(
+            "[FORENSICS] Decrypted {} ({} bytes, sha256 {})",
+            dest.display(),
+            info.size,
+            info.sha256
+        ),
+        None,
+    );
+    Ok(info)
+}
+
+/// Checks the passphrase and every chunk of a sealed export without writing anything.
+#[tauri::command]
+pub async fn verify_export_container(
+    input_path: String,
+    passphrase: String,
+) -> Result<container::ContainerInfo, AppError> {
+    container::open_container(
+        &mut std::io::BufReader::new(std::fs::File::open(&input_path)?),
+        &passphrase,
+        &mut std::io::sink(),
+    )
 }
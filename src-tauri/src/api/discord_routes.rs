@@ -20,6 +20,10 @@ pub enum DiscordApiRoute {
     ChannelMessages(String),
     /// /channels/{channel.id}/messages/{message.id}
     ChannelMessage(String, String),
+    /// /channels/{channel.id}/messages/{message.id}/reactions/...
+    MessageReactions(String),
+    /// /channels/{channel.id}/messages/bulk-delete
+    BulkDeleteMessages(String),
     /// /guilds/{guild.id}
     Guild(String),
     // Add more specific routes as needed
@@ -39,6 +43,12 @@ impl std::fmt::Display for DiscordApiRoute {
             DiscordApiRoute::ChannelMessage(channel_id, message_id) => {
                 format!("channels/{}/messages/{}", channel_id, message_id)
             }
+            DiscordApiRoute::MessageReactions(id) => {
+                format!("channels/{}/messages/:id/reactions", id)
+            }
+            DiscordApiRoute::BulkDeleteMessages(id) => {
+                format!("channels/{}/messages/bulk-delete", id)
+            }
             DiscordApiRoute::Guild(id) => format!("guilds/{}", id),
         };
         write!(f, "{}", s)
@@ -57,25 +67,17 @@ pub fn get_discord_route(url_str: &str) -> DiscordApiRoute {
     let path = parsed_url.path();
     let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
 
-    // Check for /users/@me routes
-    if let Some(pos) = segments.iter().position(|&s| s == "@me") {
-        if segments.get(pos.saturating_add(1)) == Some(&"guilds") {
-            return DiscordApiRoute::CurrentUserGuilds;
-        }
-        if segments.get(pos.saturating_add(1)) == Some(&"channels") {
-            return DiscordApiRoute::CurrentUserChannels;
-        }
-        if segments.get(pos.saturating_add(1)) == Some(&"relationships") {
-            return DiscordApiRoute::CurrentUserRelationships;
-        }
-        return DiscordApiRoute::CurrentUser;
-    }
-
     // Check for /channels/{id} routes
     if let Some(pos) = segments.iter().position(|&s| s == "channels")
         && let Some(channel_id) = segments.get(pos.saturating_add(1))
     {
         if segments.get(pos.saturating_add(2)) == Some(&"messages") {
+            if segments.get(pos.saturating_add(3)) == Some(&"bulk-delete") {
+                return DiscordApiRoute::BulkDeleteMessages(channel_id.to_string());
+            }
+            if segments.get(pos.saturating_add(4)) == Some(&"reactions") {
+                return DiscordApiRoute::MessageReactions(channel_id.to_string());
+            }
             if let Some(message_id) = segments.get(pos.saturating_add(3)) {
                 return DiscordApiRoute::ChannelMessage(
                     channel_id.to_string(),
@@ -87,6 +89,25 @@ pub fn get_discord_route(url_str: &str) -> DiscordApiRoute {
         return DiscordApiRoute::Channel(channel_id.to_string());
     }
 
+    // Check for /users/@me routes. Channel routes are matched first, since reaction and
+    // thread URLs also end in `@me`.
+    if let Some(pos) = segments
+        .windows(2)
+        .position(|pair| pair == ["users", "@me"])
+        .map(|pos| pos + 1)
+    {
+        if segments.get(pos.saturating_add(1)) == Some(&"guilds") {
+            return DiscordApiRoute::CurrentUserGuilds;
+        }
+        if segments.get(pos.saturating_add(1)) == Some(&"channels") {
+            return DiscordApiRoute::CurrentUserChannels;
+        }
+        if segments.get(pos.saturating_add(1)) == Some(&"relationships") {
+            return DiscordApiRoute::CurrentUserRelationships;
+        }
+        return DiscordApiRoute::CurrentUser;
+    }
+
     // Check for /guilds/{id} routes
     if let Some(pos) = segments.iter().position(|&s| s == "guilds")
         && let Some(guild_id) = segments.get(pos.saturating_add(1))
@@ -97,3 +118,16 @@ pub fn get_discord_route(url_str: &str) -> DiscordApiRoute {
 
     DiscordApiRoute::Default
 }
+
+/// Key of the local rate-limit bucket for a request. Discord limits each method on a
+/// route separately, with the channel or guild as the only major parameter, so message
+/// IDs are collapsed and editing and deleting messages draw from different buckets.
+pub fn bucket_key(method: &reqwest::Method, url_str: &str) -> String {
+    let route = match get_discord_route(url_str) {
+        DiscordApiRoute::ChannelMessage(channel_id, _) => {
+            format!("channels/{}/messages/:id", channel_id)
+        }
+        route => route.to_string(),
+    };
+    format!("{} {}", method, route)
+}
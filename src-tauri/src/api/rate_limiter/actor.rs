@@ -1,6 +1,6 @@
 // src-tauri/src/api/rate_limiter/actor.rs
 
-use crate::api::discord_routes::get_discord_route;
+use crate::api::discord_routes::bucket_key;
 use crate::api::rate_limiter::client_builder::build_client;
 use crate::api::rate_limiter::fingerprint::{BrowserProfile, FingerprintManager};
 use crate::api::rate_limiter::request_handler::handle_request;
@@ -69,7 +69,7 @@ impl RateLimiterActor {
                     let buckets = self.buckets.clone();
                     let global = self.global_reset_at.clone();
                     let app_handle = self.app_handle.clone();
-                    let route = get_discord_route(&req.url).to_string();
+                    let route = bucket_key(&req.method, &req.url);
                     let global_429_count = self.global_429_count.clone();
                     let actor_profile = self.profile.clone();
 
@@ -3,6 +3,7 @@
 use super::oauth::refresh_oauth_token;
 use super::types::DiscordUser;
 use crate::api::rate_limiter::ApiHandle;
+use crate::core::cache::CacheManager;
 use crate::core::error::AppError;
 use crate::core::logger::Logger;
 use crate::core::vault::{DiscordIdentity, Vault};
@@ -205,7 +206,15 @@ pub async fn switch_identity(
 
 #[tauri::command]
 pub async fn remove_identity(app_handle: AppHandle, id: String) -> Result<(), AppError> {
-    Vault::remove_identity(&app_handle, &id)
+    Vault::remove_identity(&app_handle, &id)?;
+    if let Err(e) = CacheManager::wipe_identity(&app_handle, &id) {
+        Logger::warn(
+            &app_handle,
+            &format!("[Auth] Failed to wipe cache for removed identity: {}", e),
+            None,
+        );
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -1,6 +1,7 @@
 // src-tauri/src/core/automation/janitor.rs
 
 use crate::core::error::AppError;
+use crate::core::gdpr::harvest::HarvestTracker;
 use crate::core::logger::Logger;
 use crate::core::vault::Vault;
 use serde::{Deserialize, Serialize};
@@ -76,6 +77,14 @@ impl Janitor {
             let _ = Vault::set_credential(app, "janitor_rules", &serde_json::to_string(&rules)?);
         }
 
+        if let Err(e) = HarvestTracker::run_cycle(app).await {
+            Logger::warn(
+                app,
+                "[JANITOR] Data package tracking failed",
+                Some(serde_json::json!({ "error": e.to_string() })),
+            );
+        }
+
         Ok(())
     }
 }
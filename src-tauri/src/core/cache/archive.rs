@@ -0,0 +1,365 @@
+// src-tauri/src/core/cache/archive.rs
+
+use crate::core::cache::CacheManager;
+use crate::core::error::AppError;
+use crate::core::forensics::container;
+use crate::core::gdpr::parser::PackageChannel;
+use crate::core::logger::Logger;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Messages are written in transactions of this many rows.
+const IMPORT_BATCH_SIZE: usize = 5000;
+
+/// A message recovered from one of the app's own exports or backups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedMessage {
+    pub id: String,
+    pub channel_id: String,
+    pub author_id: String,
+    pub content: String,
+    /// Unix milliseconds; 0 when the export had no readable timestamp.
+    pub timestamp: i64,
+    pub attachments: Vec<Value>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ArchiveImportSummary {
+    pub files: usize,
+    pub channels: usize,
+    pub messages: usize,
+    /// Messages that were not in the cache yet.
+    pub new_messages: usize,
+    /// Files that could not be read, with the reason.
+    pub skipped_files: Vec<String>,
+}
+
+/// Accepts the raw Discord message shape (JSON/JSONL exports, guild archives) as well
+/// as the flattened rows of forensic exports and encrypted backups.
+/// Objects without an ID and a timestamp (channel lists, manifests, ...) are ignored.
+pub fn normalize_message(value: &Value, fallback_channel: Option<&str>) -> Option<ArchivedMessage> {
+    let id = value["id"].as_str()?;
+    let timestamp = match &value["timestamp"] {
+        Value::String(ts) => chrono::DateTime::parse_from_rfc3339(ts)
+            .map(|dt| dt.timestamp_millis())
+            .unwrap_or(0),
+        Value::Number(ms) => ms.as_i64()?,
+        _ => return None,
+    };
+    let channel_id = value["channel_id"].as_str().or(fallback_channel)?;
+    Some(ArchivedMessage {
+        id: id.to_string(),
+        channel_id: channel_id.to_string(),
+        author_id: value["author"]["id"]
+            .as_str()
+            .or(value["author_id"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        content: value["content"].as_str().unwrap_or_default().to_string(),
+        timestamp,
+        attachments: value["attachments"].as_array().cloned().unwrap_or_default(),
+    })
+}
+
+/// Trailing segment of a JSON-LD node reference such as `discord:channel:123`.
+fn graph_ref(value: &Value) -> Option<&str> {
+    value["@id"].as_str()?.rsplit(':').next()
+}
+
+/// A `Message` node from the `@graph` of a forensic JSON-LD export.
+pub fn graph_message(record: &Value) -> Option<ArchivedMessage> {
+    if record["@type"].as_str() != Some("Message") {
+        return None;
+    }
+    let timestamp = chrono::DateTime::parse_from_rfc3339(record["dateSent"].as_str()?)
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or(0);
+    let attachments = record["messageAttachment"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|a| {
+            serde_json::json!({
+                "id": a["identifier"],
+                "filename": a["name"],
+                "content_type": a["encodingFormat"],
+                "size": a["contentSize"],
+                "url": a["contentUrl"],
+            })
+        })
+        .collect();
+    Some(ArchivedMessage {
+        id: record["identifier"].as_str()?.to_string(),
+        channel_id: graph_ref(&record["isPartOf"])?.to_string(),
+        author_id: graph_ref(&record["sender"]).unwrap_or_default().to_string(),
+        content: record["text"].as_str().unwrap_or_default().to_string(),
+        timestamp,
+        attachments,
+    })
+}
+
+/// Messages from a JSON document: an array of messages, a forensic JSON-LD export
+/// (`@graph`, or `data.content.messages` in older exports) or a single message.
+pub fn parse_json_document(doc: &Value, fallback_channel: Option<&str>) -> Vec<ArchivedMessage> {
+    if let Some(graph) = doc["@graph"].as_array() {
+        return graph.iter().filter_map(graph_message).collect();
+    }
+    let list = doc
+        .as_array()
+        .or_else(|| doc["data"]["content"]["messages"].as_array());
+    match list {
+        Some(items) => items
+            .iter()
+            .filter_map(|m| normalize_message(m, fallback_channel))
+            .collect(),
+        None => normalize_message(doc, fallback_channel)
+            .into_iter()
+            .collect(),
+    }
+}
+
+pub fn parse_jsonl<R: BufRead>(
+    reader: R,
+    fallback_channel: Option<&str>,
+) -> std::io::Result<Vec<ArchivedMessage>> {
+    let mut messages = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if let Ok(value) = serde_json::from_str::<Value>(&line)
+            && let Some(msg) = normalize_message(&value, fallback_channel)
+        {
+            messages.push(msg);
+        }
+    }
+    Ok(messages)
+}
+
+/// Channel ID encoded in an export file name: `123.jsonl`, `123_page2.html`, `123_pins.json`.
+pub fn channel_from_file_name(name: &str) -> Option<String> {
+    let base = name.rsplit('/').next()?;
+    let id = base.split(['.', '_']).next()?;
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| id.to_string())
+}
+
+/// Channel and server names found alongside the messages (guild archives carry
+/// `guild.json`, `channels.json` and `threads.json`).
+#[derive(Default)]
+struct ArchiveMeta {
+    guild: Option<(String, String)>,
+    channels: HashMap<String, Value>,
+}
+
+impl ArchiveMeta {
+    fn absorb(&mut self, file_name: &str, doc: &Value) {
+        match file_name.rsplit('/').next().unwrap_or_default() {
+            "guild.json" => {
+                if let (Some(id), Some(name)) = (doc["id"].as_str(), doc["name"].as_str()) {
+                    self.guild = Some((id.to_string(), name.to_string()));
+                }
+            }
+            "channels.json" | "threads.json" => {
+                for channel in doc.as_array().into_iter().flatten() {
+                    if let Some(id) = channel["id"].as_str() {
+                        self.channels.insert(id.to_string(), channel.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn channel(&self, id: &str) -> PackageChannel {
+        let meta = self.channels.get(id);
+        let guild_id = meta
+            .and_then(|c| c["guild_id"].as_str())
+            .map(|s| s.to_string())
+            .or_else(|| meta.and(self.guild.as_ref().map(|(id, _)| id.clone())));
+        PackageChannel {
+            id: id.to_string(),
+            channel_type: meta.and_then(|c| c["type"].as_u64()).unwrap_or(0) as u8,
+            name: meta.and_then(|c| c["name"].as_str()).map(|s| s.to_string()),
+            guild_name: guild_id
+                .as_ref()
+                .and_then(|_| self.guild.as_ref().map(|(_, name)| name.clone())),
+            guild_id,
+            recipients: Vec::new(),
+        }
+    }
+}
+
+/// Restores the app's own exports into the cache under the read-only "archive" source:
+/// JSON/JSONL chat exports (files or folders), guild archive zips, forensic JSON-LD
+/// exports and passphrase-encrypted containers of any of these.
+pub struct ArchiveImporter<'a> {
+    app: &'a AppHandle,
+    identity_id: String,
+    meta: ArchiveMeta,
+    messages: BTreeMap<String, Vec<ArchivedMessage>>,
+    summary: ArchiveImportSummary,
+}
+
+impl<'a> ArchiveImporter<'a> {
+    pub fn import(
+        app: &'a AppHandle,
+        identity_id: &str,
+        path: &str,
+        passphrase: Option<&str>,
+    ) -> Result<ArchiveImportSummary, AppError> {
+        Logger::info(app, &format!("[CACHE] Importing archive: {}", path), None);
+        let mut importer = Self {
+            app,
+            identity_id: identity_id.to_string(),
+            meta: ArchiveMeta::default(),
+            messages: BTreeMap::new(),
+            summary: ArchiveImportSummary::default(),
+        };
+        importer.read_path(Path::new(path), passphrase)?;
+        importer.commit()?;
+        Ok(importer.summary)
+    }
+
+    fn read_path(&mut self, path: &Path, passphrase: Option<&str>) -> Result<(), AppError> {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .collect();
+            entries.sort();
+            for entry in entries {
+                self.read_path(&entry, passphrase)?;
+            }
+            return Ok(());
+        }
+
+        if container::is_container(path) {
+            let passphrase = passphrase.ok_or_else(|| {
+                AppError::new(
+                    "This archive is encrypted; a passphrase is required.",
+                    "passphrase_required",
+                )
+            })?;
+            let staging = std::env::temp_dir().join(format!(
+                "archive_import_{}",
+                chrono::Utc::now().timestamp_millis()
+            ));
+            fs::create_dir_all(&staging)?;
+            let part = staging.join("payload");
+            let info = container::open_container(
+                &mut BufReader::new(File::open(path)?),
+                passphrase,
+                &mut std::io::BufWriter::new(File::create(&part)?),
+            );
+            let result = info.and_then(|info| {
+                let payload = staging.join(
+                    Path::new(&info.content_name)
+                        .file_name()
+                        .unwrap_or_default(),
+                );
+                fs::rename(&part, &payload)?;
+                self.read_path(&payload, None)
+            });
+            let _ = fs::remove_dir_all(&staging);
+            return result;
+        }
+
+        let name = path.to_string_lossy().to_string();
+        let outcome = match path.extension().and_then(|e| e.to_str()) {
+            Some("zip") => self.read_zip(path),
+            Some("json" | "jsonld") => self.read_json(path, &name),
+            Some("jsonl") => self.read_jsonl(path, &name),
+            _ => return Ok(()),
+        };
+        match outcome {
+            Ok(()) => self.summary.files += 1,
+            Err(e) => self
+                .summary
+                .skipped_files
+                .push(format!("{}: {}", name, e.user_message)),
+        }
+        Ok(())
+    }
+
+    fn read_json(&mut self, path: &Path, name: &str) -> Result<(), AppError> {
+        let doc: Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        self.add_document(name, &doc);
+        Ok(())
+    }
+
+    fn read_jsonl(&mut self, path: &Path, name: &str) -> Result<(), AppError> {
+        let messages = parse_jsonl(
+            BufReader::new(File::open(path)?),
+            channel_from_file_name(name).as_deref(),
+        )?;
+        self.add_messages(messages);
+        Ok(())
+    }
+
+    fn read_zip(&mut self, path: &Path) -> Result<(), AppError> {
+        let mut zip = zip::ZipArchive::new(File::open(path)?)?;
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            let name = entry.name().to_string();
+            if name.ends_with(".jsonl") {
+                let messages = parse_jsonl(
+                    BufReader::new(&mut entry),
+                    channel_from_file_name(&name).as_deref(),
+                )?;
+                self.add_messages(messages);
+            } else if name.ends_with(".json") {
+                let mut text = String::new();
+                entry.read_to_string(&mut text)?;
+                if let Ok(doc) = serde_json::from_str::<Value>(&text) {
+                    self.add_document(&name, &doc);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn add_document(&mut self, name: &str, doc: &Value) {
+        self.meta.absorb(name, doc);
+        let messages = parse_json_document(doc, channel_from_file_name(name).as_deref());
+        self.add_messages(messages);
+    }
+
+    fn add_messages(&mut self, messages: Vec<ArchivedMessage>) {
+        for msg in messages {
+            self.messages
+                .entry(msg.channel_id.clone())
+                .or_default()
+                .push(msg);
+        }
+    }
+
+    fn commit(&mut self) -> Result<(), AppError> {
+        for (channel_id, messages) in &mut self.messages {
+            // Pins and paged files repeat messages.
+            messages.sort_by(|a, b| a.id.cmp(&b.id));
+            messages.dedup_by(|a, b| a.id == b.id);
+            CacheManager::import_package_channel(
+                self.app,
+                &self.identity_id,
+                &self.meta.channel(channel_id),
+            )?;
+            for batch in messages.chunks(IMPORT_BATCH_SIZE) {
+                self.summary.new_messages +=
+                    CacheManager::import_archive_messages(self.app, &self.identity_id, batch)?;
+            }
+            self.summary.messages += messages.len();
+            self.summary.channels += 1;
+        }
+        Logger::info(
+            self.app,
+            &format!(
+                "[CACHE] Archive import finished: {} messages ({} new) in {} channels",
+                self.summary.messages, self.summary.new_messages, self.summary.channels
+            ),
+            None,
+        );
+        Ok(())
+    }
+}
@@ -1,13 +1,48 @@
 // src-tauri/src/core/cache/manager.rs
 
-use crate::core::cache::schema::SCHEMA;
+use crate::core::cache::archive::ArchivedMessage;
+use crate::core::cache::schema::{
+    MIGRATION_V1_MESSAGE_IDENTITY_KEY, MIGRATION_V2_MESSAGE_SOURCE,
+    MIGRATION_V3_ENCRYPTED_OBSERVATIONS, MIGRATION_V4_MEMBERSHIP_SOURCE,
+    MIGRATION_V5_OBSERVATION_HMAC, SCHEMA, SCHEMA_VERSION,
+};
 use crate::core::crypto::Crypto;
 use crate::core::error::AppError;
+use crate::core::gdpr::parser::{PackageChannel, PackageMessage, attachment_from_url};
 use crate::core::logger::Logger;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
 
+/// Deep scan bookkeeping for a single channel.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SyncCursor {
+    pub channel_id: String,
+    pub newest_message_id: Option<String>,
+    /// Unix seconds of the last completed scan.
+    pub last_scanned: i64,
+}
+
+/// Criteria used to resolve purge targets from the message index.
+pub struct PurgeTargetFilter {
+    pub channel_id: String,
+    pub author_id: String,
+    /// Lower bound in unix milliseconds.
+    pub start_time: Option<i64>,
+    /// Upper bound in unix milliseconds.
+    pub end_time: Option<i64>,
+    pub search_query: Option<String>,
+    pub only_attachments: bool,
+}
+
+/// Path form of a reaction emoji: the unicode emoji itself, or `name:id` for custom emoji.
+pub fn reaction_key(emoji: &serde_json::Value) -> Option<String> {
+    match emoji["id"].as_str() {
+        Some(id) => Some(format!("{}:{}", emoji["name"].as_str().unwrap_or("_"), id)),
+        None => emoji["name"].as_str().map(|name| name.to_string()),
+    }
+}
+
 pub struct CacheManager;
 
 impl CacheManager {
@@ -34,10 +69,88 @@ impl CacheManager {
             technical_details: Some(e.to_string()),
             ..Default::default()
         })?;
+        Self::run_migrations(&conn)?;
 
         Ok(conn)
     }
 
+    /// Upgrades caches created by older releases to the current schema version.
+    pub fn run_migrations(conn: &Connection) -> Result<(), AppError> {
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version >= SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        if version < 1 {
+            let pk_columns: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('messages') WHERE pk > 0",
+                [],
+                |r| r.get(0),
+            )?;
+            if pk_columns == 1 {
+                Self::apply_migration(
+                    conn,
+                    &format!("BEGIN; {} COMMIT;", MIGRATION_V1_MESSAGE_IDENTITY_KEY),
+                )?;
+            }
+        }
+
+        if version < 2 {
+            let has_source: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('messages') WHERE name = 'source'",
+                [],
+                |r| r.get(0),
+            )?;
+            if has_source == 0 {
+                Self::apply_migration(conn, MIGRATION_V2_MESSAGE_SOURCE)?;
+            }
+        }
+
+        if version < 3 {
+            let has_hash: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('activity_observations') WHERE name = 'value_hash'",
+                [],
+                |r| r.get(0),
+            )?;
+            if has_hash == 0 {
+                Self::apply_migration(
+                    conn,
+                    &format!("BEGIN; {} COMMIT;", MIGRATION_V3_ENCRYPTED_OBSERVATIONS),
+                )?;
+            }
+        }
+
+        if version < 4 {
+            let has_source: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('guilds') WHERE name = 'source'",
+                [],
+                |r| r.get(0),
+            )?;
+            if has_source == 0 {
+                Self::apply_migration(
+                    conn,
+                    &format!("BEGIN; {} COMMIT;", MIGRATION_V4_MEMBERSHIP_SOURCE),
+                )?;
+            }
+        }
+
+        if version < 5 {
+            Self::apply_migration(conn, MIGRATION_V5_OBSERVATION_HMAC)?;
+        }
+
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(())
+    }
+
+    fn apply_migration(conn: &Connection, sql: &str) -> Result<(), AppError> {
+        conn.execute_batch(sql).map_err(|e| AppError {
+            user_message: "Cache migration failed.".into(),
+            error_code: "cache_migration_failed".into(),
+            technical_details: Some(e.to_string()),
+            ..Default::default()
+        })
+    }
+
     pub fn upsert_guilds(
         app: &AppHandle,
         identity_id: &str,
@@ -97,6 +210,82 @@ impl CacheManager {
         Ok(())
     }
 
+    pub fn upsert_relationships(
+        app: &AppHandle,
+        identity_id: &str,
+        relationships: &[crate::api::discord::types::Relationship],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        // Only the live list is replaced; package rows stay for the package diff.
+        tx.execute(
+            "DELETE FROM relationships WHERE identity_id = ?1 AND source = 'live'",
+            [identity_id],
+        )?;
+        for rel in relationships {
+            tx.execute(
+                "INSERT OR REPLACE INTO relationships (user_id, identity_id, username, type, last_synced) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![rel.id, identity_id, rel.user["username"].as_str(), rel.rel_type, now],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stores the participants of a DM or group DM so partners can be correlated later.
+    pub fn upsert_channel_recipients(
+        app: &AppHandle,
+        identity_id: &str,
+        channel_id: &str,
+        recipients: &[serde_json::Value],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+
+        for user in recipients {
+            if let Some(user_id) = user["id"].as_str() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO channel_recipients (channel_id, identity_id, user_id, username) VALUES (?1, ?2, ?3, ?4)",
+                    params![channel_id, identity_id, user_id, user["username"].as_str()],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn upsert_connections(
+        app: &AppHandle,
+        identity_id: &str,
+        connections: &[serde_json::Value],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        tx.execute(
+            "DELETE FROM connections WHERE identity_id = ?1",
+            [identity_id],
+        )?;
+        for connection in connections {
+            if let (Some(kind), Some(account_id)) =
+                (connection["type"].as_str(), connection["id"].as_str())
+            {
+                tx.execute(
+                    "INSERT OR REPLACE INTO connections (identity_id, type, account_id, name, last_synced) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![identity_id, kind, account_id, connection["name"].as_str(), now],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
     /// Optimized message insertion with forensic encryption.
     pub fn upsert_message(
         app: &AppHandle,
@@ -126,11 +315,223 @@ impl CacheManager {
             "INSERT OR REPLACE INTO messages (id, identity_id, channel_id, author_id, content, timestamp, has_attachments) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![id, identity_id, channel_id, author_id, encrypted_content, timestamp, has_atts],
         );
+
+        let _ = conn.execute(
+            "DELETE FROM own_reactions WHERE message_id = ?1 AND identity_id = ?2",
+            params![id, identity_id],
+        );
+        for reaction in msg["reactions"].as_array().into_iter().flatten() {
+            if reaction["me"].as_bool() != Some(true) {
+                continue;
+            }
+            if let Some(emoji) = reaction_key(&reaction["emoji"]) {
+                let _ = conn.execute(
+                    "INSERT OR IGNORE INTO own_reactions (message_id, identity_id, channel_id, emoji) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, identity_id, channel_id, emoji],
+                );
+            }
+        }
+
+        if let Some(attachments) = msg["attachments"].as_array() {
+            for att in attachments {
+                let _ = conn.execute(
+                    "INSERT OR REPLACE INTO attachments (id, identity_id, message_id, channel_id, filename, content_type, size, url) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        att["id"].as_str().unwrap_or_default(),
+                        identity_id,
+                        id,
+                        channel_id,
+                        att["filename"].as_str().unwrap_or("file"),
+                        att["content_type"].as_str(),
+                        att["size"].as_i64().unwrap_or(0),
+                        att["url"].as_str()
+                    ],
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts messages from a GDPR data package. Rows already indexed from the live
+    /// API are left untouched; package messages are always authored by the identity.
+    /// Returns the number of newly inserted messages.
+    pub fn import_package_messages(
+        app: &AppHandle,
+        identity_id: &str,
+        channel_id: &str,
+        messages: &[PackageMessage],
+    ) -> Result<usize, AppError> {
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+
+        for msg in messages {
+            let encrypted_content = Crypto::encrypt(&enc_key, &msg.content)?;
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO messages (id, identity_id, channel_id, author_id, content, timestamp, has_attachments, source) VALUES (?1, ?2, ?3, ?2, ?4, ?5, ?6, 'package')",
+                params![msg.id, identity_id, channel_id, encrypted_content, msg.timestamp, !msg.attachments.is_empty()],
+            )?;
+
+            for url in &msg.attachments {
+                if let Some((attachment_id, filename)) = attachment_from_url(url) {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO attachments (id, identity_id, message_id, channel_id, filename, size, url) VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6)",
+                        params![attachment_id, identity_id, msg.id, channel_id, filename, url],
+                    )?;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Restores messages from the app's own exports under the "archive" source.
+    /// Existing rows win, so live and package data are never overwritten.
+    /// Returns the number of newly inserted messages.
+    pub fn import_archive_messages(
+        app: &AppHandle,
+        identity_id: &str,
+        messages: &[ArchivedMessage],
+    ) -> Result<usize, AppError> {
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+
+        for msg in messages {
+            let encrypted_content = Crypto::encrypt(&enc_key, &msg.content)?;
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO messages (id, identity_id, channel_id, author_id, content, timestamp, has_attachments, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'archive')",
+                params![msg.id, identity_id, msg.channel_id, msg.author_id, encrypted_content, msg.timestamp, !msg.attachments.is_empty()],
+            )?;
+
+            for att in &msg.attachments {
+                tx.execute(
+                    "INSERT OR IGNORE INTO attachments (id, identity_id, message_id, channel_id, filename, content_type, size, url) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        att["id"].as_str().unwrap_or_default(),
+                        identity_id,
+                        msg.id,
+                        msg.channel_id,
+                        att["filename"].as_str().unwrap_or("file"),
+                        att["content_type"].as_str(),
+                        att["size"].as_i64().unwrap_or(0),
+                        att["url"].as_str()
+                    ],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Indexes channel metadata from a data package without overwriting live data.
+    pub fn import_package_channel(
+        app: &AppHandle,
+        identity_id: &str,
+        channel: &PackageChannel,
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        if let Some(guild_id) = &channel.guild_id {
+            tx.execute(
+                "INSERT OR IGNORE INTO guilds (id, identity_id, name, icon, owner, last_synced, source) VALUES (?1, ?2, ?3, NULL, 0, ?4, 'package')",
+                params![guild_id, identity_id, channel.guild_name.as_deref().unwrap_or(guild_id), now],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO channels (id, identity_id, guild_id, name, type, last_synced) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![channel.id, identity_id, channel.guild_id, channel.name, channel.channel_type, now],
+        )?;
+        for user_id in channel.recipients.iter().filter(|r| *r != identity_id) {
+            tx.execute(
+                "INSERT OR IGNORE INTO channel_recipients (channel_id, identity_id, user_id, username) VALUES (?1, ?2, ?3, NULL)",
+                params![channel.id, identity_id, user_id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Indexes servers listed in a data package; live guild rows take precedence.
+    pub fn import_package_guilds(
+        app: &AppHandle,
+        identity_id: &str,
+        guilds: &[(String, Option<String>)],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        for (id, name) in guilds {
+            tx.execute(
+                "INSERT OR IGNORE INTO guilds (id, identity_id, name, icon, owner, last_synced, source) VALUES (?1, ?2, ?3, NULL, 0, ?4, 'package')",
+                params![id, identity_id, name.as_deref().unwrap_or(id), now],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Adds relationships from `account/user.json` that are not already known.
+    pub fn import_package_relationships(
+        app: &AppHandle,
+        identity_id: &str,
+        relationships: &[crate::api::discord::types::Relationship],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        for rel in relationships {
+            tx.execute(
+                "INSERT OR IGNORE INTO relationships (user_id, identity_id, username, type, last_synced, source) VALUES (?1, ?2, ?3, ?4, ?5, 'package')",
+                params![rel.id, identity_id, rel.user["username"].as_str(), rel.rel_type, now],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Records a completed package import for later comparison and auditing.
+    pub fn record_package_import(
+        app: &AppHandle,
+        identity_id: &str,
+        source_path: &str,
+        channel_count: usize,
+        message_count: usize,
+        guild_count: usize,
+        relationship_count: usize,
+    ) -> Result<(), AppError> {
+        let conn = Self::get_connection(app)?;
+        conn.execute(
+            "INSERT INTO packages (identity_id, source_path, imported_at, channel_count, message_count, guild_count, relationship_count) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                identity_id,
+                source_path,
+                chrono::Utc::now().timestamp(),
+                channel_count as i64,
+                message_count as i64,
+                guild_count as i64,
+                relationship_count as i64
+            ],
+        )?;
         Ok(())
     }
 
     pub fn search_messages(
         app: &AppHandle,
+        identity_id: &str,
         query: &str,
     ) -> Result<Vec<serde_json::Value>, AppError> {
         let conn = Self::get_connection(app)?;
@@ -138,11 +539,11 @@ impl CacheManager {
             crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, channel_id, identity_id, author_id, content, timestamp, has_attachments FROM messages"
+            "SELECT id, channel_id, identity_id, author_id, content, timestamp, has_attachments, source FROM messages WHERE identity_id = ?1"
         ).map_err(AppError::from)?;
 
         let message_rows = stmt
-            .query_map([], |row| {
+            .query_map([identity_id], |row| {
                 let encrypted_content = row.get::<_, String>(4)?;
                 // Decrypt for matching (this is O(N) unfortunately, but we're in local cache)
                 let decrypted = Crypto::decrypt(&enc_key, &encrypted_content).unwrap_or_default();
@@ -156,6 +557,7 @@ impl CacheManager {
                         "content": decrypted.clone(),
                         "timestamp": row.get::<_, i64>(5)?,
                         "has_attachments": row.get::<_, bool>(6)?,
+                        "source": row.get::<_, String>(7)?,
                     }),
                     decrypted,
                 ))
@@ -176,6 +578,252 @@ impl CacheManager {
         Ok(results)
     }
 
+    /// Every live message the identity wrote, decrypted. Unlike `search_messages` this
+    /// has no row cap and skips deleted and restored archive rows.
+    pub fn own_messages(
+        app: &AppHandle,
+        identity_id: &str,
+    ) -> Result<Vec<serde_json::Value>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, channel_id, author_id, content FROM messages
+             WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive'",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            let encrypted_content = row.get::<_, String>(3)?;
+            Ok(serde_json::json!({
+                "id": row.get::<_, String>(0)?,
+                "channel_id": row.get::<_, String>(1)?,
+                "author_id": row.get::<_, String>(2)?,
+                "content": Crypto::decrypt(&enc_key, &encrypted_content).unwrap_or_default(),
+            }))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Maps every cached channel of an identity to its guild (`None` for DMs).
+    pub fn channel_guild_map(
+        app: &AppHandle,
+        identity_id: &str,
+    ) -> Result<std::collections::HashMap<String, Option<String>>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let mut stmt = conn.prepare("SELECT id, guild_id FROM channels WHERE identity_id = ?1")?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Usernames known for an identity, from friends and DM recipients.
+    pub fn user_names(
+        app: &AppHandle,
+        identity_id: &str,
+    ) -> Result<std::collections::HashMap<String, String>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let mut stmt = conn.prepare(
+            "SELECT user_id, username FROM relationships WHERE identity_id = ?1 AND username IS NOT NULL
+             UNION
+             SELECT user_id, username FROM channel_recipients WHERE identity_id = ?1 AND username IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Names of every cached channel of an identity.
+    pub fn channel_names(
+        app: &AppHandle,
+        identity_id: &str,
+    ) -> Result<std::collections::HashMap<String, String>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let mut stmt = conn
+            .prepare("SELECT id, name FROM channels WHERE identity_id = ?1 AND name IS NOT NULL")?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Records that a channel has been fully indexed by the deep scan.
+    pub fn record_sync_cursor(
+        app: &AppHandle,
+        identity_id: &str,
+        channel_id: &str,
+        newest_message_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let conn = Self::get_connection(app)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_cursors (channel_id, identity_id, newest_message_id, last_scanned) VALUES (?1, ?2, ?3, ?4)",
+            params![channel_id, identity_id, newest_message_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_sync_cursor(
+        app: &AppHandle,
+        identity_id: &str,
+        channel_id: &str,
+    ) -> Result<Option<SyncCursor>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let cursor = conn
+            .query_row(
+                "SELECT newest_message_id, last_scanned FROM sync_cursors WHERE channel_id = ?1 AND identity_id = ?2",
+                params![channel_id, identity_id],
+                |row| {
+                    Ok(SyncCursor {
+                        channel_id: channel_id.to_string(),
+                        newest_message_id: row.get(0)?,
+                        last_scanned: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(cursor)
+    }
+
+    /// Resolves the message IDs a purge should target from the local index.
+    /// Content filtering happens after decryption, so the query is only narrowed in SQL
+    /// by identity, author, channel, time range and attachment presence.
+    /// Restored archive rows are read-only and never targeted.
+    pub fn query_purge_targets(
+        app: &AppHandle,
+        identity_id: &str,
+        filter: &PurgeTargetFilter,
+    ) -> Result<Vec<String>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, content FROM messages
+             WHERE identity_id = ?1 AND author_id = ?2 AND channel_id = ?3 AND is_deleted = 0
+               AND source != 'archive'
+               AND (?4 IS NULL OR timestamp >= ?4)
+               AND (?5 IS NULL OR timestamp <= ?5)
+               AND (?6 = 0 OR has_attachments = 1)
+             ORDER BY timestamp DESC",
+        )?;
+
+        let rows = stmt.query_map(
+            params![
+                identity_id,
+                filter.author_id,
+                filter.channel_id,
+                filter.start_time,
+                filter.end_time,
+                filter.only_attachments
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?;
+
+        let query = filter.search_query.as_ref().map(|q| q.to_lowercase());
+        let mut ids = Vec::new();
+        for (id, encrypted_content) in rows.flatten() {
+            if let Some(q) = &query {
+                let content = Crypto::decrypt(&enc_key, &encrypted_content).unwrap_or_default();
+                if !content.to_lowercase().contains(q) {
+                    continue;
+                }
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Flags messages as deleted so subsequent cache-driven purges skip them.
+    /// Restored archive rows are left as they are.
+    pub fn mark_messages_deleted(
+        app: &AppHandle,
+        identity_id: &str,
+        message_ids: &[String],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        for id in message_ids {
+            tx.execute(
+                "UPDATE messages SET is_deleted = 1 WHERE id = ?1 AND identity_id = ?2 AND source != 'archive'",
+                params![id, identity_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// `(message_id, emoji)` of every reaction the identity has placed in a channel,
+    /// as of the last sync, skipping deleted and restored archive messages.
+    pub fn query_own_reactions(
+        app: &AppHandle,
+        identity_id: &str,
+        channel_id: &str,
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let conn = Self::get_connection(app)?;
+        let mut stmt = conn.prepare(
+            "SELECT r.message_id, r.emoji FROM own_reactions r
+             JOIN messages m ON m.id = r.message_id AND m.identity_id = r.identity_id
+             WHERE r.identity_id = ?1 AND r.channel_id = ?2 AND m.is_deleted = 0
+               AND m.source != 'archive'
+             ORDER BY r.message_id, r.emoji",
+        )?;
+        let rows = stmt.query_map(params![identity_id, channel_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Drops reactions that were removed so later cache-driven runs skip them.
+    pub fn forget_own_reactions(
+        app: &AppHandle,
+        identity_id: &str,
+        reactions: &[(String, String)],
+    ) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        for (message_id, emoji) in reactions {
+            tx.execute(
+                "DELETE FROM own_reactions WHERE message_id = ?1 AND identity_id = ?2 AND emoji = ?3",
+                params![message_id, identity_id, emoji],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Removes everything indexed for a single identity, leaving other accounts intact.
+    pub fn wipe_identity(app: &AppHandle, identity_id: &str) -> Result<(), AppError> {
+        let mut conn = Self::get_connection(app)?;
+        let tx = conn.transaction()?;
+        for table in [
+            "messages",
+            "attachments",
+            "channels",
+            "guilds",
+            "relationships",
+            "channel_recipients",
+            "connections",
+            "sync_cursors",
+            "packages",
+            "activity_events",
+            "activity_observations",
+            "own_reactions",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE identity_id = ?1", table),
+                [identity_id],
+            )?;
+        }
+        tx.commit()?;
+        Logger::info(
+            app,
+            &format!("[CACHE] Cache wiped for identity {}", identity_id),
+            None,
+        );
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn wipe_cache(app: &AppHandle) -> Result<(), AppError> {
         let db_path = Self::get_db_path(app)?;
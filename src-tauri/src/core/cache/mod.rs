@@ -1,6 +1,7 @@
 // src-tauri/src/core/cache/mod.rs
 
+pub mod archive;
 pub mod manager;
 pub mod schema;
 
-pub use manager::CacheManager;
+pub use manager::{CacheManager, PurgeTargetFilter, reaction_key};
@@ -1,5 +1,8 @@
 // src-tauri/src/core/cache/schema.rs
 
+/// Current value of `PRAGMA user_version` once all migrations have run.
+pub const SCHEMA_VERSION: i64 = 5;
+
 pub const SCHEMA: &str = "
 CREATE TABLE IF NOT EXISTS guilds (
     id TEXT,
@@ -8,6 +11,7 @@ CREATE TABLE IF NOT EXISTS guilds (
     icon TEXT,
     owner BOOLEAN NOT NULL,
     last_synced INTEGER NOT NULL,
+    source TEXT NOT NULL DEFAULT 'live', -- 'live' (API) or 'package' (GDPR data package)
     PRIMARY KEY (id, identity_id)
 );
 
@@ -23,7 +27,7 @@ CREATE TABLE IF NOT EXISTS channels (
 );
 
 CREATE TABLE IF NOT EXISTS messages (
-    id TEXT PRIMARY KEY,
+    id TEXT NOT NULL,
     identity_id TEXT NOT NULL,
     channel_id TEXT NOT NULL,
     author_id TEXT NOT NULL,
@@ -31,15 +35,165 @@ CREATE TABLE IF NOT EXISTS messages (
     timestamp INTEGER NOT NULL,
     has_attachments BOOLEAN NOT NULL,
     is_deleted BOOLEAN DEFAULT 0,
+    source TEXT NOT NULL DEFAULT 'live', -- 'live' (API), 'package' (GDPR data package) or 'archive' (restored export)
+    PRIMARY KEY (id, identity_id),
     FOREIGN KEY(channel_id, identity_id) REFERENCES channels(id, identity_id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS attachments (
+    id TEXT NOT NULL,
+    identity_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT,
+    size INTEGER NOT NULL DEFAULT 0,
+    url TEXT,
+    PRIMARY KEY (id, identity_id)
+);
+
+CREATE TABLE IF NOT EXISTS relationships (
+    user_id TEXT NOT NULL,
+    identity_id TEXT NOT NULL,
+    username TEXT,
+    type INTEGER NOT NULL,
+    last_synced INTEGER NOT NULL,
+    source TEXT NOT NULL DEFAULT 'live', -- 'live' (API) or 'package' (GDPR data package)
+    PRIMARY KEY (user_id, identity_id)
+);
+
+CREATE TABLE IF NOT EXISTS channel_recipients (
+    channel_id TEXT NOT NULL,
+    identity_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    username TEXT,
+    PRIMARY KEY (channel_id, identity_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS connections (
+    identity_id TEXT NOT NULL,
+    type TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    name TEXT,
+    last_synced INTEGER NOT NULL,
+    PRIMARY KEY (identity_id, type, account_id)
+);
+
+CREATE TABLE IF NOT EXISTS sync_cursors (
+    channel_id TEXT NOT NULL,
+    identity_id TEXT NOT NULL,
+    newest_message_id TEXT,
+    last_scanned INTEGER NOT NULL,
+    PRIMARY KEY (channel_id, identity_id)
+);
+
+CREATE TABLE IF NOT EXISTS packages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    identity_id TEXT NOT NULL,
+    source_path TEXT NOT NULL,
+    imported_at INTEGER NOT NULL,
+    channel_count INTEGER NOT NULL,
+    message_count INTEGER NOT NULL,
+    guild_count INTEGER NOT NULL,
+    relationship_count INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS activity_events (
+    identity_id TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    category TEXT NOT NULL, -- analytics, reporting, tns, modeling
+    event_type TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    properties TEXT NOT NULL, -- encrypted JSON object
+    PRIMARY KEY (identity_id, event_id)
+);
+
+-- Distinct device, OS and location values seen across activity events.
+CREATE TABLE IF NOT EXISTS activity_observations (
+    identity_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    value_hash TEXT NOT NULL, -- keyed hash of the value, for grouping
+    value TEXT NOT NULL, -- encrypted
+    count INTEGER NOT NULL,
+    first_seen INTEGER NOT NULL,
+    last_seen INTEGER NOT NULL,
+    PRIMARY KEY (identity_id, field, value_hash)
+);
+
+-- Reactions the identity had placed on each message when it was last synced.
+CREATE TABLE IF NOT EXISTS own_reactions (
+    message_id TEXT NOT NULL,
+    identity_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    emoji TEXT NOT NULL, -- unicode emoji, or name:id for custom emoji
+    PRIMARY KEY (message_id, identity_id, emoji)
+);
+
 CREATE TABLE IF NOT EXISTS discovery (
     key TEXT PRIMARY KEY,
     value TEXT NOT NULL
 );
 
 CREATE INDEX IF NOT EXISTS idx_messages_channel ON messages(channel_id, identity_id);
-CREATE INDEX IF NOT EXISTS idx_messages_author ON messages(author_id);
+CREATE INDEX IF NOT EXISTS idx_messages_author ON messages(author_id, identity_id);
+CREATE INDEX IF NOT EXISTS idx_attachments_message ON attachments(message_id, identity_id);
 CREATE INDEX IF NOT EXISTS idx_channels_guild ON channels(guild_id, identity_id);
+CREATE INDEX IF NOT EXISTS idx_own_reactions_channel ON own_reactions(channel_id, identity_id);
+CREATE INDEX IF NOT EXISTS idx_activity_type ON activity_events(identity_id, event_type);
 ";
+
+/// v1: `messages.id` was a global primary key, so the same message indexed by two
+/// identities overwrote each other. Rebuilds the table keyed by `(id, identity_id)`.
+pub const MIGRATION_V1_MESSAGE_IDENTITY_KEY: &str = "
+CREATE TABLE messages_v1 (
+    id TEXT NOT NULL,
+    identity_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    has_attachments BOOLEAN NOT NULL,
+    is_deleted BOOLEAN DEFAULT 0,
+    PRIMARY KEY (id, identity_id),
+    FOREIGN KEY(channel_id, identity_id) REFERENCES channels(id, identity_id) ON DELETE CASCADE
+);
+
+INSERT OR IGNORE INTO messages_v1 (id, identity_id, channel_id, author_id, content, timestamp, has_attachments, is_deleted)
+    SELECT id, identity_id, channel_id, author_id, content, timestamp, has_attachments, is_deleted FROM messages;
+
+DROP TABLE messages;
+ALTER TABLE messages_v1 RENAME TO messages;
+
+CREATE INDEX IF NOT EXISTS idx_messages_channel ON messages(channel_id, identity_id);
+CREATE INDEX IF NOT EXISTS idx_messages_author ON messages(author_id, identity_id);
+";
+
+/// v2: messages record where they came from so package imports never masquerade as live data.
+pub const MIGRATION_V2_MESSAGE_SOURCE: &str =
+    "ALTER TABLE messages ADD COLUMN source TEXT NOT NULL DEFAULT 'live';";
+
+/// v3: observation values were stored in plaintext next to the encrypted events. The old
+/// counters are dropped and rebuilt from the events on the next activity summary.
+pub const MIGRATION_V3_ENCRYPTED_OBSERVATIONS: &str = "
+DROP TABLE activity_observations;
+CREATE TABLE activity_observations (
+    identity_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    value_hash TEXT NOT NULL,
+    value TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    first_seen INTEGER NOT NULL,
+    last_seen INTEGER NOT NULL,
+    PRIMARY KEY (identity_id, field, value_hash)
+);
+";
+
+/// v4: guilds and relationships record where they came from, like messages in v2.
+pub const MIGRATION_V4_MEMBERSHIP_SOURCE: &str = "
+ALTER TABLE guilds ADD COLUMN source TEXT NOT NULL DEFAULT 'live';
+ALTER TABLE relationships ADD COLUMN source TEXT NOT NULL DEFAULT 'live';
+";
+
+/// v5: observation hashes moved to an HMAC under a derived key. The counters are dropped
+/// and rebuilt from the events on the next activity summary, as in v3.
+pub const MIGRATION_V5_OBSERVATION_HMAC: &str = "DELETE FROM activity_observations;";
@@ -0,0 +1,293 @@
+// src-tauri/src/core/forensics/analytics.rs
+
+use crate::core::cache::CacheManager;
+use crate::core::crypto::Crypto;
+use crate::core::error::AppError;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// Number of entries returned by the "top N" rankings.
+const TOP_LIMIT: i64 = 10;
+
+#[derive(Serialize, Default)]
+pub struct ActivityAnalytics {
+    /// Authored messages per calendar month (`YYYY-MM`), oldest first.
+    pub monthly_heatmap: Vec<MonthBucket>,
+    /// 24 buckets, index 0 is midnight in the requested offset.
+    pub hour_of_day: Vec<i64>,
+    /// 7 buckets, index 0 is Sunday.
+    pub day_of_week: Vec<i64>,
+    pub top_dm_partners: Vec<RankedEntity>,
+    pub top_channels: Vec<RankedEntity>,
+    pub average_message_length: f64,
+    pub attachment_volume: Vec<AttachmentVolume>,
+    pub oldest_surviving: Vec<OldestMessage>,
+}
+
+#[derive(Serialize)]
+pub struct MonthBucket {
+    pub month: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct RankedEntity {
+    pub id: String,
+    pub name: Option<String>,
+    pub message_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct AttachmentVolume {
+    pub category: String,
+    pub count: i64,
+    pub total_bytes: i64,
+}
+
+#[derive(Serialize)]
+pub struct OldestMessage {
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    pub message_id: String,
+    pub timestamp: i64,
+}
+
+/// Time-based exposure analytics over an identity's own cached messages.
+pub struct FootprintAnalytics;
+
+impl FootprintAnalytics {
+    /// Computes every analytics view for `identity_id`.
+    /// Calendar buckets are shifted by `utc_offset_minutes` so they match the user's clock.
+    pub fn compute(
+        app: &AppHandle,
+        identity_id: &str,
+        utc_offset_minutes: i32,
+    ) -> Result<ActivityAnalytics, AppError> {
+        let conn = CacheManager::get_connection(app)?;
+        let offset = format!("{:+} minutes", utc_offset_minutes);
+
+        Ok(ActivityAnalytics {
+            monthly_heatmap: Self::monthly_heatmap(&conn, identity_id, &offset)?,
+            hour_of_day: Self::distribution(&conn, identity_id, &offset, "%H", 24)?,
+            day_of_week: Self::distribution(&conn, identity_id, &offset, "%w", 7)?,
+            top_dm_partners: Self::top_dm_partners(&conn, identity_id)?,
+            top_channels: Self::top_channels(&conn, identity_id)?,
+            average_message_length: Self::average_message_length(app, &conn, identity_id)?,
+            attachment_volume: Self::attachment_volume(&conn, identity_id)?,
+            oldest_surviving: Self::oldest_surviving(&conn, identity_id)?,
+        })
+    }
+
+    fn monthly_heatmap(
+        conn: &Connection,
+        identity_id: &str,
+        offset: &str,
+    ) -> Result<Vec<MonthBucket>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT strftime('%Y-%m', timestamp / 1000, 'unixepoch', ?2) AS month, COUNT(*)
+             FROM messages
+             WHERE identity_id = ?1 AND author_id = ?1 AND timestamp > 0
+               AND is_deleted = 0 AND source != 'archive'
+             GROUP BY month ORDER BY month",
+        )?;
+        let rows = stmt.query_map(params![identity_id, offset], |row| {
+            Ok(MonthBucket {
+                month: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    fn distribution(
+        conn: &Connection,
+        identity_id: &str,
+        offset: &str,
+        format: &str,
+        buckets: usize,
+    ) -> Result<Vec<i64>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT CAST(strftime(?3, timestamp / 1000, 'unixepoch', ?2) AS INTEGER) AS bucket, COUNT(*)
+             FROM messages
+             WHERE identity_id = ?1 AND author_id = ?1 AND timestamp > 0
+               AND is_deleted = 0 AND source != 'archive'
+             GROUP BY bucket",
+        )?;
+        let rows = stmt.query_map(params![identity_id, offset, format], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut result = vec![0; buckets];
+        for (bucket, count) in rows.flatten() {
+            if let Some(slot) = result.get_mut(bucket as usize) {
+                *slot = count;
+            }
+        }
+        Ok(result)
+    }
+
+    fn top_dm_partners(
+        conn: &Connection,
+        identity_id: &str,
+    ) -> Result<Vec<RankedEntity>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT r.user_id, r.username, COUNT(m.id) AS total
+             FROM channel_recipients r
+             JOIN channels c ON c.id = r.channel_id AND c.identity_id = r.identity_id
+             JOIN messages m ON m.channel_id = r.channel_id AND m.identity_id = r.identity_id
+             WHERE r.identity_id = ?1 AND c.guild_id IS NULL AND m.author_id = ?1
+               AND m.is_deleted = 0 AND m.source != 'archive'
+             GROUP BY r.user_id ORDER BY total DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![identity_id, TOP_LIMIT], |row| {
+            Ok(RankedEntity {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                message_count: row.get(2)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    fn top_channels(conn: &Connection, identity_id: &str) -> Result<Vec<RankedEntity>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT m.channel_id, c.name, COUNT(*) AS total
+             FROM messages m
+             LEFT JOIN channels c ON c.id = m.channel_id AND c.identity_id = m.identity_id
+             WHERE m.identity_id = ?1 AND m.author_id = ?1
+               AND m.is_deleted = 0 AND m.source != 'archive'
+             GROUP BY m.channel_id ORDER BY total DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![identity_id, TOP_LIMIT], |row| {
+            Ok(RankedEntity {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                message_count: row.get(2)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Content is stored encrypted, so lengths are measured after decryption.
+    fn average_message_length(
+        app: &AppHandle,
+        conn: &Connection,
+        identity_id: &str,
+    ) -> Result<f64, AppError> {
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+        let mut stmt = conn.prepare(
+            "SELECT content FROM messages
+             WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source != 'archive'",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| row.get::<_, String>(0))?;
+
+        let (mut total_chars, mut count) = (0usize, 0usize);
+        for encrypted in rows.flatten() {
+            if let Ok(content) = Crypto::decrypt(&enc_key, &encrypted) {
+                total_chars += content.chars().count();
+                count += 1;
+            }
+        }
+        Ok(if count == 0 {
+            0.0
+        } else {
+            total_chars as f64 / count as f64
+        })
+    }
+
+    fn attachment_volume(
+        conn: &Connection,
+        identity_id: &str,
+    ) -> Result<Vec<AttachmentVolume>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT a.content_type, a.filename, a.size
+             FROM attachments a
+             JOIN messages m ON m.id = a.message_id AND m.identity_id = a.identity_id
+             WHERE a.identity_id = ?1 AND m.author_id = ?1
+               AND m.is_deleted = 0 AND m.source != 'archive'",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+
+        let mut volumes: HashMap<&'static str, (i64, i64)> = HashMap::new();
+        for (content_type, filename, size) in rows.flatten() {
+            let entry = volumes
+                .entry(Self::attachment_category(
+                    content_type.as_deref(),
+                    &filename,
+                ))
+                .or_default();
+            entry.0 += 1;
+            entry.1 += size;
+        }
+
+        let mut result: Vec<AttachmentVolume> = volumes
+            .into_iter()
+            .map(|(category, (count, total_bytes))| AttachmentVolume {
+                category: category.to_string(),
+                count,
+                total_bytes,
+            })
+            .collect();
+        result.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes));
+        Ok(result)
+    }
+
+    /// Buckets an attachment by MIME type, falling back to the file extension.
+    pub fn attachment_category(content_type: Option<&str>, filename: &str) -> &'static str {
+        let major = content_type.and_then(|ct| ct.split('/').next());
+        match major {
+            Some("image") => return "image",
+            Some("video") => return "video",
+            Some("audio") => return "audio",
+            Some("text") => return "text",
+            _ => {}
+        }
+
+        let ext = filename
+            .rsplit_once('.')
+            .map(|(_, e)| e.to_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "heic" => "image",
+            "mp4" | "mov" | "webm" | "mkv" | "avi" => "video",
+            "mp3" | "ogg" | "wav" | "flac" | "m4a" => "audio",
+            "txt" | "md" | "log" | "csv" | "json" => "text",
+            "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" => "document",
+            "zip" | "rar" | "7z" | "tar" | "gz" => "archive",
+            _ => "other",
+        }
+    }
+
+    fn oldest_surviving(
+        conn: &Connection,
+        identity_id: &str,
+    ) -> Result<Vec<OldestMessage>, AppError> {
+        // SQLite returns the bare columns from the row that produced MIN().
+        let mut stmt = conn.prepare(
+            "SELECT m.channel_id, c.name, m.id, MIN(m.timestamp)
+             FROM messages m
+             LEFT JOIN channels c ON c.id = m.channel_id AND c.identity_id = m.identity_id
+             WHERE m.identity_id = ?1 AND m.author_id = ?1 AND m.is_deleted = 0 AND m.timestamp > 0
+               AND m.source != 'archive'
+             GROUP BY m.channel_id ORDER BY MIN(m.timestamp)",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok(OldestMessage {
+                channel_id: row.get(0)?,
+                channel_name: row.get(1)?,
+                message_id: row.get(2)?,
+                timestamp: row.get(3)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+}
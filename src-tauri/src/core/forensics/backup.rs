@@ -2,46 +2,54 @@
 
 use crate::core::crypto::Crypto;
 use crate::core::error::AppError;
+use crate::core::forensics::container::ContainerWriter;
 use std::fs::File;
-use std::io::Write;
+use std::io::BufWriter;
 use tauri::AppHandle;
 
-#[allow(dead_code)]
 pub struct ForensicBackup;
 
 impl ForensicBackup {
-    /// Creates an encrypted backup of messages from the local cache.
-    #[allow(dead_code)]
+    /// Creates a passphrase-encrypted backup of a channel's cached messages.
+    /// Unlike the vault key, the passphrase lets the backup be opened on any machine.
     pub fn create_encrypted_backup(
         app: &AppHandle,
+        identity_id: &str,
         channel_id: &str,
         output_path: &str,
+        passphrase: &str,
     ) -> Result<(), AppError> {
         let conn = crate::core::cache::CacheManager::get_connection(app)?;
+        // Cached content is sealed with this machine's vault key; the backup carries plaintext.
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
         let mut stmt = conn
-            .prepare("SELECT content, author_id, timestamp FROM messages WHERE channel_id = ?1")
+            .prepare(
+                "SELECT id, content, author_id, timestamp FROM messages WHERE channel_id = ?1 AND identity_id = ?2",
+            )
             .map_err(AppError::from)?;
 
         let messages: Vec<serde_json::Value> = stmt
-            .query_map([channel_id], |row| {
+            .query_map([channel_id, identity_id], |row| {
                 Ok(serde_json::json!({
-                    "content": row.get::<_, String>(0)?,
-                    "author_id": row.get::<_, String>(1)?,
-                    "timestamp": row.get::<_, i64>(2)?,
+                    "id": row.get::<_, String>(0)?,
+                    "channel_id": channel_id,
+                    "content": Crypto::decrypt(&enc_key, &row.get::<_, String>(1)?).unwrap_or_default(),
+                    "author_id": row.get::<_, String>(2)?,
+                    "timestamp": row.get::<_, i64>(3)?,
                 }))
             })
             .map_err(AppError::from)?
             .filter_map(|m| m.ok())
             .collect();
 
-        let json = serde_json::to_string(&messages)?;
-        let enc_key =
-            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
-        let encrypted = Crypto::encrypt(&enc_key, &json)?;
-
-        let mut file = File::create(output_path).map_err(AppError::from)?;
-        file.write_all(encrypted.as_bytes())
-            .map_err(AppError::from)?;
+        let mut writer = ContainerWriter::new(
+            BufWriter::new(File::create(output_path)?),
+            passphrase,
+            &format!("backup_{}.json", channel_id),
+        )?;
+        serde_json::to_writer(&mut writer, &messages)?;
+        writer.finish()?;
 
         Ok(())
     }
@@ -0,0 +1,352 @@
+// src-tauri/src/core/forensics/container.rs
+
+use crate::core::crypto::Crypto;
+use crate::core::error::AppError;
+use aes_gcm::{
+    Aes256Gcm, Nonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use rand::{RngCore, rngs::OsRng};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"DPUSEAL\0";
+const VERSION: u8 = 1;
+const SALT_BYTES: usize = 16;
+const PREFIX_LEN: usize = 7;
+const TAG_LEN: usize = 16;
+
+/// Plaintext bytes per sealed chunk.
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// File extension of sealed containers.
+pub const CONTAINER_EXTENSION: &str = "dpuseal";
+
+/// What a successful decrypt or verify found inside a container.
+#[derive(Debug, Serialize)]
+pub struct ContainerInfo {
+    /// Name of the sealed payload, e.g. `chat_export.zip`.
+    pub content_name: String,
+    pub size: u64,
+    pub chunks: u64,
+    pub sha256: String,
+}
+
+/// Passphrase-protected container: an Argon2id key (via `Crypto::derive_key`) and
+/// AES-256-GCM over fixed-size chunks. Each nonce carries the chunk index and a
+/// final-chunk flag, and the header is authenticated with every chunk, so
+/// reordered, truncated or extended files fail to open.
+///
+/// Layout: magic, version, salt length + salt, chunk size (u32 BE), nonce prefix,
+/// name length (u16 BE) + name, then `u32 BE length + ciphertext` per chunk.
+pub struct ContainerWriter<W: Write> {
+    inner: W,
+    cipher: Aes256Gcm,
+    header: Vec<u8>,
+    prefix: [u8; PREFIX_LEN],
+    chunk_size: usize,
+    counter: u32,
+    buffer: Vec<u8>,
+}
+
+fn nonce_for(prefix: &[u8; PREFIX_LEN], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..PREFIX_LEN].copy_from_slice(prefix);
+    nonce[PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Rejects empty passphrases up front, before an exporter starts writing.
+pub fn check_passphrase(passphrase: &str) -> Result<(), AppError> {
+    if passphrase.is_empty() {
+        return Err(AppError::new(
+            "A passphrase is required to encrypt the export.",
+            "passphrase_required",
+        ));
+    }
+    Ok(())
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::new(message, "container_invalid")
+}
+
+impl<W: Write> ContainerWriter<W> {
+    pub fn new(inner: W, passphrase: &str, content_name: &str) -> Result<Self, AppError> {
+        Self::with_chunk_size(inner, passphrase, content_name, CHUNK_SIZE)
+    }
+
+    pub fn with_chunk_size(
+        mut inner: W,
+        passphrase: &str,
+        content_name: &str,
+        chunk_size: usize,
+    ) -> Result<Self, AppError> {
+        check_passphrase(passphrase)?;
+        if chunk_size == 0 || chunk_size > CHUNK_SIZE {
+            return Err(invalid("Unsupported container chunk size."));
+        }
+        let mut salt_bytes = [0u8; SALT_BYTES];
+        OsRng.fill_bytes(&mut salt_bytes);
+        let salt = hex::encode(salt_bytes);
+        let mut prefix = [0u8; PREFIX_LEN];
+        OsRng.fill_bytes(&mut prefix);
+
+        let mut header = Vec::new();
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.push(salt.len() as u8);
+        header.extend_from_slice(salt.as_bytes());
+        header.extend_from_slice(&(chunk_size as u32).to_be_bytes());
+        header.extend_from_slice(&prefix);
+        let name = content_name.as_bytes();
+        header.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        header.extend_from_slice(name);
+        inner.write_all(&header)?;
+
+        let key = Crypto::derive_key(passphrase, &salt)?;
+        Ok(Self {
+            inner,
+            cipher: Aes256Gcm::new((&*key).into()),
+            header,
+            prefix,
+            chunk_size,
+            counter: 0,
+            buffer: Vec::with_capacity(chunk_size),
+        })
+    }
+
+    fn seal(&mut self, plaintext: &[u8], last: bool) -> io::Result<()> {
+        let nonce = nonce_for(&self.prefix, self.counter, last);
+        let sealed = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &self.header,
+                },
+            )
+            .map_err(|_| io::Error::other("chunk encryption failed"))?;
+        self.inner.write_all(&(sealed.len() as u32).to_be_bytes())?;
+        self.inner.write_all(&sealed)?;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("container holds too many chunks"))?;
+        Ok(())
+    }
+
+    /// Seals the remaining bytes as the final chunk and returns the inner writer.
+    pub fn finish(mut self) -> Result<W, AppError> {
+        let rest = std::mem::take(&mut self.buffer);
+        self.seal(&rest, true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for ContainerWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        // Keep at least one byte back so the final chunk is only sealed by `finish`.
+        while self.buffer.len() > self.chunk_size {
+            let chunk: Vec<u8> = self.buffer.drain(..self.chunk_size).collect();
+            self.seal(&chunk, false)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn read_exact_or_invalid(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), AppError> {
+    reader
+        .read_exact(buf)
+        .map_err(|_| invalid("The container is truncated."))
+}
+
+/// Decrypts a container into `out`, checking every chunk. Pass `io::sink()` to only verify.
+pub fn open_container(
+    reader: &mut impl Read,
+    passphrase: &str,
+    out: &mut impl Write,
+) -> Result<ContainerInfo, AppError> {
+    let mut header = vec![0u8; MAGIC.len() + 2];
+    read_exact_or_invalid(reader, &mut header)?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(invalid("Not an encrypted export container."));
+    }
+    if header[MAGIC.len()] != VERSION {
+        return Err(invalid("Unsupported container version."));
+    }
+
+    let mut salt = vec![0u8; header[MAGIC.len() + 1] as usize];
+    read_exact_or_invalid(reader, &mut salt)?;
+    let mut fixed = [0u8; 4 + PREFIX_LEN + 2];
+    read_exact_or_invalid(reader, &mut fixed)?;
+    let chunk_size = u32::from_be_bytes(fixed[..4].try_into().unwrap()) as usize;
+    // The header is only authenticated by the first chunk, so bound the size before
+    // it sizes any allocation.
+    if chunk_size == 0 || chunk_size > CHUNK_SIZE {
+        return Err(invalid("Unsupported container chunk size."));
+    }
+    let prefix: [u8; PREFIX_LEN] = fixed[4..4 + PREFIX_LEN].try_into().unwrap();
+    let mut name =
+        vec![0u8; u16::from_be_bytes(fixed[4 + PREFIX_LEN..].try_into().unwrap()) as usize];
+    read_exact_or_invalid(reader, &mut name)?;
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&fixed);
+    header.extend_from_slice(&name);
+
+    let salt = String::from_utf8(salt)?;
+    let key = Crypto::derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new((&*key).into());
+
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut counter = 0u32;
+    loop {
+        let mut len = [0u8; 4];
+        read_exact_or_invalid(reader, &mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len < TAG_LEN || len > chunk_size + TAG_LEN {
+            return Err(invalid("The container is corrupted."));
+        }
+        let mut sealed = vec![0u8; len];
+        read_exact_or_invalid(reader, &mut sealed)?;
+
+        // A chunk opens under exactly one of the two flags.
+        let mut opened = None;
+        for last in [false, true] {
+            let nonce = nonce_for(&prefix, counter, last);
+            if let Ok(plain) = cipher.decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &sealed,
+                    aad: &header,
+                },
+            ) {
+                opened = Some((plain, last));
+                break;
+            }
+        }
+        let Some((plain, last)) = opened else {
+            return Err(AppError::new(
+                "Wrong passphrase or the container was modified.",
+                "container_auth_failed",
+            ));
+        };
+
+        hasher.update(&plain);
+        out.write_all(&plain)?;
+        size += plain.len() as u64;
+        counter += 1;
+
+        if last {
+            if reader.read(&mut [0u8; 1])? != 0 {
+                return Err(invalid("Unexpected data after the final chunk."));
+            }
+            out.flush()?;
+            return Ok(ContainerInfo {
+                content_name: String::from_utf8_lossy(&name).to_string(),
+                size,
+                chunks: counter as u64,
+                sha256: hex::encode(hasher.finalize()),
+            });
+        }
+    }
+}
+
+/// Uniquely named temp folder for plaintext that is about to be sealed. It is removed on
+/// drop, so an error on any path leaves nothing decrypted behind.
+pub struct StagingDir {
+    path: PathBuf,
+}
+
+impl StagingDir {
+    pub fn create(prefix: &str) -> Result<Self, AppError> {
+        let path = std::env::temp_dir().join(format!("{}_{}", prefix, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Encrypts the file at `plain` into `dest` and removes the plaintext.
+pub fn seal_file(plain: &Path, dest: &Path, passphrase: &str) -> Result<(), AppError> {
+    let name = plain
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut writer = ContainerWriter::new(BufWriter::new(File::create(dest)?), passphrase, &name)?;
+    io::copy(&mut BufReader::new(File::open(plain)?), &mut writer)?;
+    writer.finish()?;
+    fs::remove_file(plain)?;
+    Ok(())
+}
+
+/// Zips everything under `dir` into `zip_path`, keeping paths relative to `dir`.
+pub fn zip_directory(dir: &Path, zip_path: &Path) -> Result<(), AppError> {
+    let mut zip = zip::ZipWriter::new(File::create(zip_path)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(&current)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            zip.start_file(name, options)?;
+            io::copy(&mut File::open(&path)?, &mut zip)?;
+        }
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Whether `path` starts with the container magic.
+pub fn is_container(path: &Path) -> bool {
+    let mut magic = [0u8; 8];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok()
+        && &magic == MAGIC
+}
+
+/// `dest` with the container extension appended, e.g. `archive.zip.dpuseal`.
+pub fn sealed_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_os_string();
+    name.push(".");
+    name.push(CONTAINER_EXTENSION);
+    PathBuf::from(name)
+}
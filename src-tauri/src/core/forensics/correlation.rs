@@ -1,26 +1,93 @@
 // src-tauri/src/core/forensics/correlation.rs
 
+use crate::core::cache::CacheManager;
 use crate::core::error::AppError;
 use crate::core::logger::Logger;
 use crate::core::vault::Vault;
-use std::collections::{HashMap, HashSet};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use tauri::AppHandle;
 
 /// Cross-identity link analysis module.
-#[allow(dead_code)]
 pub struct IdentityCorrelator;
 
-#[allow(dead_code)]
-#[derive(serde::Serialize)]
+/// The kind of shared node that ties two or more identities together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// One of our identities has another one in its relationships list.
+    DirectRelationship,
+    /// The same external account (Steam, GitHub, ...) is connected to several identities.
+    SharedConnection,
+    SharedDmPartner,
+    MutualFriend,
+    MutualGuild,
+    ActiveHours,
+}
+
+impl LinkKind {
+    /// How strongly a single shared node of this kind de-anonymizes the identities.
+    pub fn weight(&self) -> f64 {
+        match self {
+            LinkKind::DirectRelationship => 1.0,
+            LinkKind::SharedConnection => 0.95,
+            LinkKind::SharedDmPartner => 0.6,
+            LinkKind::MutualFriend => 0.5,
+            LinkKind::ActiveHours => 0.4,
+            LinkKind::MutualGuild => 0.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CorrelationLink {
+    pub kind: LinkKind,
+    pub subject_id: String,
+    pub subject_name: Option<String>,
+    pub identities: Vec<String>,
+    pub weight: f64,
+    /// Cosine similarity of the active hours, for `ActiveHours` links.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+}
+
+/// Aggregated exposure between two of our identities.
+#[derive(Debug, Clone, Serialize)]
+pub struct IdentityPairScore {
+    pub identity_a: String,
+    pub identity_b: String,
+    pub score: f64,
+    pub link_count: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct CorrelationReport {
-    pub mutual_guilds: Vec<serde_json::Value>,
-    pub mutual_friends: Vec<serde_json::Value>,
+    pub mutual_guilds: Vec<CorrelationLink>,
+    pub mutual_friends: Vec<CorrelationLink>,
+    pub shared_dm_partners: Vec<CorrelationLink>,
+    pub shared_connections: Vec<CorrelationLink>,
+    pub direct_relationships: Vec<CorrelationLink>,
+    pub active_hour_overlaps: Vec<CorrelationLink>,
+    /// Every link above, strongest first.
+    pub ranked_links: Vec<CorrelationLink>,
+    /// Identity pairs ordered by how strongly they are tied together.
+    pub pair_scores: Vec<IdentityPairScore>,
 }
 
+/// Hour-of-day histograms with a cosine similarity above this are reported as overlapping.
+const ACTIVE_HOURS_THRESHOLD: f64 = 0.8;
+
+/// Minimum authored messages before an identity's activity pattern is considered meaningful.
+const ACTIVE_HOURS_MIN_SAMPLES: u64 = 50;
+
+/// subject_id -> (display name, identities that share it)
+type Membership = HashMap<String, (Option<String>, BTreeSet<String>)>;
+
 impl IdentityCorrelator {
-    /// Identifies common nodes (guilds/friends) across all identities in the Vault.
+    /// Identifies common nodes (guilds, friends, DM partners, connections, activity
+    /// patterns) across all identities in the Vault.
     /// This is crucial for verifying that 'alt' accounts are truly isolated.
-    #[allow(dead_code)]
     pub fn analyze_all(app: &AppHandle) -> Result<CorrelationReport, AppError> {
         Logger::info(
             app,
@@ -28,28 +95,256 @@ impl IdentityCorrelator {
             None,
         );
 
-        let identities = Vault::list_identities(app);
+        let identities: HashSet<String> = Vault::list_identities(app)
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
         if identities.len() < 2 {
-            return Ok(CorrelationReport {
-                mutual_guilds: Vec::new(),
-                mutual_friends: Vec::new(),
-            });
+            return Ok(CorrelationReport::default());
+        }
+
+        let conn = CacheManager::get_connection(app)?;
+
+        let guilds = Self::load_membership(&conn, "SELECT id, name, identity_id FROM guilds")?;
+        let friends = Self::load_membership(
+            &conn,
+            "SELECT user_id, username, identity_id FROM relationships WHERE type = 1",
+        )?;
+        let dm_partners = Self::load_membership(
+            &conn,
+            "SELECT user_id, username, identity_id FROM channel_recipients",
+        )?;
+        let connections = Self::load_membership(
+            &conn,
+            "SELECT type || ':' || account_id, name, identity_id FROM connections",
+        )?;
+        let hours = Self::load_hour_histograms(&conn)?;
+
+        let mut report = CorrelationReport {
+            mutual_guilds: Self::shared_links(LinkKind::MutualGuild, &guilds, &identities),
+            mutual_friends: Self::shared_links(LinkKind::MutualFriend, &friends, &identities),
+            shared_dm_partners: Self::shared_links(
+                LinkKind::SharedDmPartner,
+                &dm_partners,
+                &identities,
+            ),
+            shared_connections: Self::shared_links(
+                LinkKind::SharedConnection,
+                &connections,
+                &identities,
+            ),
+            direct_relationships: Self::direct_links(&friends, &dm_partners, &identities),
+            active_hour_overlaps: Self::active_hour_links(&hours, &identities),
+            ..Default::default()
+        };
+
+        let mut ranked: Vec<CorrelationLink> = report
+            .direct_relationships
+            .iter()
+            .chain(&report.shared_connections)
+            .chain(&report.shared_dm_partners)
+            .chain(&report.mutual_friends)
+            .chain(&report.active_hour_overlaps)
+            .chain(&report.mutual_guilds)
+            .cloned()
+            .collect();
+        ranked.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        report.pair_scores = Self::score_pairs(&ranked);
+        report.ranked_links = ranked;
+
+        Logger::info(
+            app,
+            &format!(
+                "[FORENSICS] Correlation audit complete: {} links across {} identity pairs",
+                report.ranked_links.len(),
+                report.pair_scores.len()
+            ),
+            None,
+        );
+        Ok(report)
+    }
+
+    fn load_membership(conn: &Connection, sql: &str) -> Result<Membership, AppError> {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut map: Membership = HashMap::new();
+        for (subject, name, identity_id) in rows.flatten() {
+            let entry = map.entry(subject).or_default();
+            if entry.0.is_none() {
+                entry.0 = name;
+            }
+            entry.1.insert(identity_id);
         }
+        Ok(map)
+    }
 
-        // In a real implementation, we would query the local cache (CacheManager)
-        // since we now have indexed data for each identity.
-        let _conn = crate::core::cache::CacheManager::get_connection(app)?;
+    /// Builds a 24-bucket UTC hour-of-day histogram of each identity's own messages.
+    fn load_hour_histograms(conn: &Connection) -> Result<HashMap<String, [u64; 24]>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT identity_id, (timestamp / 3600000) % 24, COUNT(*) FROM messages
+             WHERE author_id = identity_id AND timestamp > 0
+               AND is_deleted = 0 AND source != 'archive'
+             GROUP BY identity_id, (timestamp / 3600000) % 24",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
 
-        // Find mutual guilds
-        let _guild_map: HashMap<String, HashSet<String>> = HashMap::new(); // guild_id -> set of identity_ids
+        let mut histograms: HashMap<String, [u64; 24]> = HashMap::new();
+        for (identity_id, hour, count) in rows.flatten() {
+            let bucket = histograms.entry(identity_id).or_insert([0; 24]);
+            bucket[hour.rem_euclid(24) as usize] += count.max(0) as u64;
+        }
+        Ok(histograms)
+    }
 
-        // This requires us to have identity_id in our cache schema.
-        // Let's check the schema I just wrote.
-        // Ah, I missed identity_id in the schema! I must update it.
+    /// Turns a membership map into links for every subject shared by two or more of our identities.
+    pub fn shared_links(
+        kind: LinkKind,
+        membership: &Membership,
+        identities: &HashSet<String>,
+    ) -> Vec<CorrelationLink> {
+        let mut links: Vec<CorrelationLink> = membership
+            .iter()
+            .filter(|(subject, _)| !identities.contains(*subject))
+            .filter_map(|(subject, (name, members))| {
+                let ours: Vec<String> = members
+                    .iter()
+                    .filter(|m| identities.contains(*m))
+                    .cloned()
+                    .collect();
+                (ours.len() >= 2).then(|| CorrelationLink {
+                    kind,
+                    subject_id: subject.clone(),
+                    subject_name: name.clone(),
+                    // Every pair on the node gets the same weight, however many share it.
+                    weight: kind.weight(),
+                    identities: ours,
+                    similarity: None,
+                })
+            })
+            .collect();
+        links.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        links
+    }
+
+    /// Finds identities that are friends with, or have DMed, another of our identities.
+    fn direct_links(
+        friends: &Membership,
+        dm_partners: &Membership,
+        identities: &HashSet<String>,
+    ) -> Vec<CorrelationLink> {
+        let mut seen = HashSet::new();
+        let mut links = Vec::new();
+        for (subject, (name, members)) in friends.iter().chain(dm_partners.iter()) {
+            if !identities.contains(subject) {
+                continue;
+            }
+            for member in members.iter().filter(|m| identities.contains(*m)) {
+                let mut pair = [subject.clone(), member.clone()];
+                pair.sort();
+                if pair[0] == pair[1] || !seen.insert(pair.clone()) {
+                    continue;
+                }
+                links.push(CorrelationLink {
+                    kind: LinkKind::DirectRelationship,
+                    subject_id: subject.clone(),
+                    subject_name: name.clone(),
+                    identities: pair.to_vec(),
+                    weight: LinkKind::DirectRelationship.weight(),
+                    similarity: None,
+                });
+            }
+        }
+        links
+    }
+
+    fn active_hour_links(
+        histograms: &HashMap<String, [u64; 24]>,
+        identities: &HashSet<String>,
+    ) -> Vec<CorrelationLink> {
+        let mut ids: Vec<&String> = histograms
+            .keys()
+            .filter(|id| identities.contains(*id))
+            .filter(|id| histograms[*id].iter().sum::<u64>() >= ACTIVE_HOURS_MIN_SAMPLES)
+            .collect();
+        ids.sort();
+
+        let mut links = Vec::new();
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                let similarity = Self::hour_similarity(&histograms[*a], &histograms[*b]);
+                if similarity >= ACTIVE_HOURS_THRESHOLD {
+                    links.push(CorrelationLink {
+                        kind: LinkKind::ActiveHours,
+                        subject_id: "active_hours".to_string(),
+                        subject_name: None,
+                        identities: vec![(*a).clone(), (*b).clone()],
+                        weight: LinkKind::ActiveHours.weight() * similarity,
+                        similarity: Some(similarity),
+                    });
+                }
+            }
+        }
+        links
+    }
+
+    /// Cosine similarity between two hour-of-day activity histograms.
+    pub fn hour_similarity(a: &[u64; 24], b: &[u64; 24]) -> f64 {
+        let dot: f64 = a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| (*x as f64) * (*y as f64))
+            .sum();
+        let norm_a = a.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+        let norm_b = b.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+        if norm_a == 0.0 || norm_b == 0.0 {
+            return 0.0;
+        }
+        dot / (norm_a * norm_b)
+    }
+
+    /// Sums link weights per identity pair, strongest pair first.
+    pub fn score_pairs(links: &[CorrelationLink]) -> Vec<IdentityPairScore> {
+        let mut pairs: HashMap<(String, String), (f64, usize)> = HashMap::new();
+        for link in links {
+            for (i, a) in link.identities.iter().enumerate() {
+                for b in &link.identities[i + 1..] {
+                    let key = if a < b {
+                        (a.clone(), b.clone())
+                    } else {
+                        (b.clone(), a.clone())
+                    };
+                    let entry = pairs.entry(key).or_insert((0.0, 0));
+                    entry.0 += link.weight;
+                    entry.1 += 1;
+                }
+            }
+        }
 
-        Ok(CorrelationReport {
-            mutual_guilds: Vec::new(),
-            mutual_friends: Vec::new(),
-        })
+        let mut scores: Vec<IdentityPairScore> = pairs
+            .into_iter()
+            .map(
+                |((identity_a, identity_b), (score, link_count))| IdentityPairScore {
+                    identity_a,
+                    identity_b,
+                    score,
+                    link_count,
+                },
+            )
+            .collect();
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scores
     }
 }
@@ -3,73 +3,539 @@
 use crate::core::cache::CacheManager;
 use crate::core::crypto::Crypto;
 use crate::core::error::AppError;
-use serde_json::json;
+use crate::core::vault::identity::DiscordIdentity;
+use rusqlite::Connection;
+use serde::Serialize;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value, json};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
-use std::io::Write;
-use tauri::AppHandle;
+use std::io::{BufReader, BufWriter, Write};
+use tauri::{AppHandle, Manager};
 
-pub struct ExportForensics;
+/// Property holding each record's own digest; excluded when the digest is computed.
+pub const HASH_PROPERTY: &str = "sha256";
 
-impl ExportForensics {
-    /// Generates a standardized forensic export of all cached identity data.
-    /// Includes SHA-256 integrity hashes for each exported node.
-    pub fn generate_json_ld(app: &AppHandle, output_path: &str) -> Result<(), AppError> {
-        let guilds = CacheManager::get_connection(app)?
-            .prepare("SELECT id, name, owner, last_synced FROM guilds")?
-            .query_map([], |row| {
-                Ok(json!({
-                    "@type": "DiscordGuild",
-                    "identifier": row.get::<_, String>(0)?,
-                    "name": row.get::<_, String>(1)?,
-                    "isOwner": row.get::<_, bool>(2)?,
-                    "indexedAt": row.get::<_, i64>(3)?,
-                }))
-            })?
-            .filter_map(|g| g.ok())
-            .collect::<Vec<_>>();
-
-        let messages = CacheManager::search_messages(app, "")?;
-
-        let export_obj = json!({
-            "@context": "https://www.discordprivacy.util/forensics/v1",
-            "@type": "ForensicAuditExport",
-            "generatedAt": chrono::Utc::now().to_rfc3339(),
-            "software": "Discord Privacy Utility v1.0.4",
-            "content": {
-                "guilds": guilds,
-                "messages": messages,
+/// Prefix byte for interior Merkle nodes, so a node can never collide with a record hash.
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+/// Prefix byte for the root that seals the records together with the custody metadata.
+const CUSTODY_PREFIX: u8 = 0x02;
+
+/// schema.org terms, with Discord-specific ones under `urn:discord:`.
+fn context() -> Value {
+    json!({
+        "@vocab": "https://schema.org/",
+        "discord": "urn:discord:",
+        "sha256": "https://w3id.org/security#digestValue",
+        "integrity": "discord:integrity",
+        "timeSource": "discord:timeSource",
+    })
+}
+
+/// Serializes with object keys sorted at every level, so a record hashes the same
+/// however it was written or re-read.
+pub fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let members: Vec<String> = keys
+                .into_iter()
+                .map(|k| {
+                    format!(
+                        "{}:{}",
+                        serde_json::to_string(k).unwrap_or_default(),
+                        canonical_json(&map[k])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", members.join(","))
+        }
+        Value::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(canonical_json)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        other => other.to_string(),
+    }
+}
+
+/// SHA-256 of the record's canonical form without its own `sha256` member.
+pub fn record_hash(record: &Value) -> [u8; 32] {
+    let mut unsigned = record.clone();
+    if let Some(map) = unsigned.as_object_mut() {
+        map.remove(HASH_PROPERTY);
+    }
+    Sha256::digest(canonical_json(&unsigned).as_bytes()).into()
+}
+
+/// Everything outside `@graph`, minus the root itself, as covered by `custody_root`.
+fn custody_members(header: &Map<String, Value>) -> Value {
+    let mut custody = header.clone();
+    custody.remove("@graph");
+    if let Some(integrity) = custody.get_mut("integrity").and_then(Value::as_object_mut) {
+        integrity.remove("merkleRoot");
+    }
+    Value::Object(custody)
+}
+
+/// `SHA-256(0x02 || records root || canonical custody members)`, so collector, producer
+/// and time metadata cannot change without the published root changing too.
+pub fn custody_root(records_root: &str, header: &Map<String, Value>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([CUSTODY_PREFIX]);
+    hasher.update(records_root.as_bytes());
+    hasher.update(canonical_json(&custody_members(header)).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Incremental Merkle tree over record hashes. Interior nodes are
+/// `SHA-256(0x01 || left || right)`; an unpaired node is carried up unchanged.
+#[derive(Default)]
+pub struct MerkleBuilder {
+    stack: Vec<(u32, [u8; 32])>,
+    pub leaves: usize,
+}
+
+fn merkle_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+impl MerkleBuilder {
+    pub fn push(&mut self, leaf: [u8; 32]) {
+        self.leaves += 1;
+        let mut node = (0, leaf);
+        while let Some(&(height, left)) = self.stack.last() {
+            if height != node.0 {
+                break;
             }
-        });
+            self.stack.pop();
+            node = (height + 1, merkle_node(&left, &node.1));
+        }
+        self.stack.push(node);
+    }
 
-        let json_str = serde_json::to_string_pretty(&export_obj)?;
+    /// Hex root, or the hash of nothing for an empty export.
+    pub fn root(&self) -> String {
+        let mut nodes = self.stack.iter().rev().map(|(_, hash)| *hash);
+        let Some(mut acc) = nodes.next() else {
+            return hex::encode(Sha256::digest(b""));
+        };
+        for left in nodes {
+            acc = merkle_node(&left, &acc);
+        }
+        hex::encode(acc)
+    }
+}
+
+/// Writes `@graph` records one at a time, hashing each as it goes.
+struct GraphWriter<W: Write> {
+    out: W,
+    merkle: MerkleBuilder,
+}
+
+impl<W: Write> GraphWriter<W> {
+    fn write(&mut self, mut record: Value) -> Result<(), AppError> {
+        let hash = record_hash(&record);
+        record[HASH_PROPERTY] = Value::String(hex::encode(hash));
+        if self.merkle.leaves > 0 {
+            self.out.write_all(b",")?;
+        }
+        self.out.write_all(b"\n    ")?;
+        serde_json::to_writer(&mut self.out, &record)?;
+        self.merkle.push(hash);
+        Ok(())
+    }
+}
+
+fn node_id(kind: &str, id: &str) -> String {
+    format!("discord:{}:{}", kind, id)
+}
+
+fn iso_millis(ms: i64) -> Value {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|dt| Value::String(dt.to_rfc3339()))
+        .unwrap_or(Value::Null)
+}
 
-        // Use Crypto for forensic-grade integrity verification
-        let audit_session_id = Crypto::generate_key();
-        let mut hasher = Sha256::new();
-        hasher.update(json_str.as_bytes());
-        let hash = hex::encode(hasher.finalize());
+pub struct ExportForensics;
+
+impl ExportForensics {
+    /// Streams every cached guild, channel, DM, relationship and message of an identity
+    /// as a schema.org JSON-LD dataset. Each record carries its SHA-256 and the file ends
+    /// with a Merkle root over all records plus chain-of-custody metadata.
+    pub fn generate_json_ld(
+        app: &AppHandle,
+        collector: &DiscordIdentity,
+        output_path: &str,
+    ) -> Result<(), AppError> {
+        let identity_id = collector.id.as_str();
+        let conn = CacheManager::get_connection(app)?;
+        let started_at = chrono::Utc::now();
+        let package = app.package_info();
 
-        let forensics_token = format!(
-            "v1-{}-{}-{}",
-            audit_session_id,
-            hash,
-            chrono::Utc::now().timestamp()
+        let mut header = Map::new();
+        header.insert("@context".into(), context());
+        header.insert("@type".into(), json!("Dataset"));
+        header.insert(
+            "name".into(),
+            json!(format!("Discord cache of {}", collector.username)),
+        );
+        header.insert(
+            "creator".into(),
+            json!({
+                "@type": "Person",
+                "identifier": identity_id,
+                "name": collector.username,
+            }),
+        );
+        header.insert(
+            "producer".into(),
+            json!({
+                "@type": "SoftwareApplication",
+                "name": package.name,
+                "softwareVersion": package.version.to_string(),
+                "operatingSystem": std::env::consts::OS,
+            }),
+        );
+        header.insert(
+            "timeSource".into(),
+            json!({
+                "clock": "system",
+                "timezone": "UTC",
+                "startTime": started_at.to_rfc3339(),
+            }),
         );
 
-        let final_export = json!({
-            "integrity": {
+        let mut out = BufWriter::new(File::create(output_path)?);
+        out.write_all(b"{")?;
+        for (key, value) in &header {
+            write!(out, "\n  {}: {},", json!(key), value)?;
+        }
+        out.write_all(b"\n  \"@graph\": [")?;
+
+        let mut graph = GraphWriter {
+            out,
+            merkle: MerkleBuilder::default(),
+        };
+        Self::write_guilds(&conn, identity_id, &mut graph)?;
+        Self::write_channels(&conn, identity_id, &mut graph)?;
+        Self::write_relationships(&conn, identity_id, &mut graph)?;
+        Self::write_messages(app, &conn, identity_id, &mut graph)?;
+
+        let records_root = graph.merkle.root();
+        header.insert(
+            "integrity".into(),
+            json!({
                 "algorithm": "SHA-256",
-                "hash": hash,
-                "auditSessionId": audit_session_id,
-                "token": forensics_token
-            },
-            "data": export_obj
-        });
+                "recordCount": graph.merkle.leaves,
+                "recordHash": "SHA-256 of the record with sorted keys and no sha256 member",
+                "merkleNode": "SHA-256(0x01 || left || right); unpaired nodes are carried up",
+                "recordsRoot": records_root,
+                "merkleRootHash": "SHA-256(0x02 || recordsRoot || members outside @graph, sorted, without merkleRoot)",
+                "endTime": chrono::Utc::now().to_rfc3339(),
+            }),
+        );
+        let merkle_root = custody_root(&records_root, &header);
+        header["integrity"]["merkleRoot"] = Value::String(merkle_root);
+        write!(
+            graph.out,
+            "\n  ],\n  \"integrity\": {}\n}}\n",
+            header["integrity"]
+        )?;
+        graph.out.flush()?;
+        Ok(())
+    }
 
-        let mut file = File::create(output_path)?;
-        file.write_all(serde_json::to_string_pretty(&final_export)?.as_bytes())?;
+    fn write_guilds<W: Write>(
+        conn: &Connection,
+        identity_id: &str,
+        graph: &mut GraphWriter<W>,
+    ) -> Result<(), AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, name, owner, last_synced FROM guilds WHERE identity_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok(json!({
+                "@id": node_id("guild", &row.get::<_, String>(0)?),
+                "@type": "Organization",
+                "identifier": row.get::<_, String>(0)?,
+                "name": row.get::<_, String>(1)?,
+                "discord:isOwner": row.get::<_, bool>(2)?,
+                "dateModified": iso_millis(row.get::<_, i64>(3)? * 1000),
+            }))
+        })?;
+        for record in rows {
+            graph.write(record?)?;
+        }
+        Ok(())
+    }
+
+    fn write_channels<W: Write>(
+        conn: &Connection,
+        identity_id: &str,
+        graph: &mut GraphWriter<W>,
+    ) -> Result<(), AppError> {
+        let mut recipients: HashMap<String, Vec<Value>> = HashMap::new();
+        let mut stmt = conn.prepare(
+            "SELECT channel_id, user_id, username FROM channel_recipients WHERE identity_id = ?1 ORDER BY user_id",
+        )?;
+        for row in stmt.query_map([identity_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })? {
+            let (channel_id, user_id, username) = row?;
+            recipients.entry(channel_id).or_default().push(json!({
+                "@id": node_id("user", &user_id),
+                "@type": "Person",
+                "identifier": user_id,
+                "name": username,
+            }));
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, guild_id, name, type FROM channels WHERE identity_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, guild_id, name, kind) = row?;
+            let mut record = json!({
+                "@id": node_id("channel", &id),
+                "@type": "Conversation",
+                "identifier": id,
+                "name": name,
+                "discord:channelType": kind,
+            });
+            match guild_id {
+                Some(guild_id) => {
+                    record["isPartOf"] = json!({ "@id": node_id("guild", &guild_id) })
+                }
+                None => {
+                    record["participant"] = Value::Array(recipients.remove(&id).unwrap_or_default())
+                }
+            }
+            graph.write(record)?;
+        }
+        Ok(())
+    }
+
+    fn write_relationships<W: Write>(
+        conn: &Connection,
+        identity_id: &str,
+        graph: &mut GraphWriter<W>,
+    ) -> Result<(), AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT user_id, username, type FROM relationships WHERE identity_id = ?1 ORDER BY user_id",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok(json!({
+                "@id": node_id("user", &row.get::<_, String>(0)?),
+                "@type": "Person",
+                "identifier": row.get::<_, String>(0)?,
+                "name": row.get::<_, Option<String>>(1)?,
+                "discord:relationshipType": row.get::<_, i64>(2)?,
+            }))
+        })?;
+        for record in rows {
+            graph.write(record?)?;
+        }
+        Ok(())
+    }
+
+    fn write_messages<W: Write>(
+        app: &AppHandle,
+        conn: &Connection,
+        identity_id: &str,
+        graph: &mut GraphWriter<W>,
+    ) -> Result<(), AppError> {
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+
+        let mut attachments: HashMap<String, Vec<Value>> = HashMap::new();
+        let mut stmt = conn.prepare(
+            "SELECT message_id, id, filename, content_type, size, url FROM attachments WHERE identity_id = ?1 ORDER BY id",
+        )?;
+        for row in stmt.query_map([identity_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                json!({
+                    "@type": "MediaObject",
+                    "identifier": row.get::<_, String>(1)?,
+                    "name": row.get::<_, String>(2)?,
+                    "encodingFormat": row.get::<_, Option<String>>(3)?,
+                    "contentSize": row.get::<_, i64>(4)?,
+                    "contentUrl": row.get::<_, Option<String>>(5)?,
+                }),
+            ))
+        })? {
+            let (message_id, attachment) = row?;
+            attachments.entry(message_id).or_default().push(attachment);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, channel_id, author_id, content, timestamp, is_deleted, source FROM messages WHERE identity_id = ?1 ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, Option<bool>>(5)?.unwrap_or(false),
+                row.get::<_, String>(6)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, channel_id, author_id, encrypted, timestamp, deleted, source) = row?;
+            let mut record = json!({
+                "@id": node_id("message", &id),
+                "@type": "Message",
+                "identifier": id,
+                "isPartOf": { "@id": node_id("channel", &channel_id) },
+                "sender": { "@id": node_id("user", &author_id) },
+                "text": Crypto::decrypt(&enc_key, &encrypted).unwrap_or_default(),
+                "dateSent": iso_millis(timestamp),
+                "discord:deleted": deleted,
+                "discord:source": source,
+            });
+            if let Some(list) = attachments.remove(&id) {
+                record["messageAttachment"] = Value::Array(list);
+            }
+            graph.write(record)?;
+        }
+        Ok(())
+    }
+
+    /// Recomputes every record hash and the Merkle root of an export, streaming the
+    /// `@graph` so large files are never held in memory. The root also covers the
+    /// custody header and integrity metadata.
+    pub fn verify_json_ld(path: &str) -> Result<ForensicVerification, AppError> {
+        let mut state = VerifyState::default();
+        let mut deserializer =
+            serde_json::Deserializer::from_reader(BufReader::new(File::open(path)?));
+        de::Deserializer::deserialize_map(&mut deserializer, ExportVisitor(&mut state))?;
+        deserializer.end()?;
+        Ok(state.finish())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForensicVerification {
+    pub records: usize,
+    /// `@id`s (or positions) of records whose stored hash does not match their content.
+    pub tampered: Vec<String>,
+    pub merkle_root: String,
+    pub expected_merkle_root: Option<String>,
+    pub expected_records: Option<u64>,
+    pub valid: bool,
+}
+
+#[derive(Default)]
+struct VerifyState {
+    merkle: MerkleBuilder,
+    tampered: Vec<String>,
+    /// Top-level members other than `@graph`.
+    header: Map<String, Value>,
+}
+
+impl VerifyState {
+    fn check(&mut self, record: Value) {
+        let hash = record_hash(&record);
+        if record[HASH_PROPERTY].as_str() != Some(hex::encode(hash).as_str()) {
+            self.tampered.push(
+                record["@id"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| format!("#{}", self.merkle.leaves)),
+            );
+        }
+        self.merkle.push(hash);
+    }
+
+    fn finish(self) -> ForensicVerification {
+        let merkle_root = custody_root(&self.merkle.root(), &self.header);
+        let integrity = self.header.get("integrity").cloned().unwrap_or_default();
+        let expected_merkle_root = integrity["merkleRoot"].as_str().map(|s| s.to_string());
+        let expected_records = integrity["recordCount"].as_u64();
+        let valid = self.tampered.is_empty()
+            && expected_merkle_root.as_deref() == Some(merkle_root.as_str())
+            && expected_records == Some(self.merkle.leaves as u64);
+        ForensicVerification {
+            records: self.merkle.leaves,
+            tampered: self.tampered,
+            merkle_root,
+            expected_merkle_root,
+            expected_records,
+            valid,
+        }
+    }
+}
+
+struct ExportVisitor<'a>(&'a mut VerifyState);
+
+impl<'de> Visitor<'de> for ExportVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a forensic JSON-LD export")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "@graph" => map.next_value_seed(GraphSeed(&mut *self.0))?,
+                _ => {
+                    let value = map.next_value()?;
+                    self.0.header.insert(key, value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct GraphSeed<'a>(&'a mut VerifyState);
+
+impl<'de> DeserializeSeed<'de> for GraphSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for GraphSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of records")
+    }
 
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(record) = seq.next_element::<Value>()? {
+            self.0.check(record);
+        }
         Ok(())
     }
 }
@@ -0,0 +1,320 @@
+// src-tauri/src/core/forensics/exposure.rs
+
+use crate::core::forensics::auditor::IntegrationAuditor;
+use crate::core::forensics::pii::PIIResult;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Areas contributing to the exposure score, each capped independently so a single
+/// noisy source cannot dominate the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExposureCategory {
+    MessageFootprint,
+    PersonalData,
+    Integrations,
+    Connections,
+    Sessions,
+}
+
+impl ExposureCategory {
+    pub fn max_points(&self) -> f64 {
+        match self {
+            ExposureCategory::MessageFootprint => 20.0,
+            ExposureCategory::PersonalData => 40.0,
+            ExposureCategory::Integrations => 20.0,
+            ExposureCategory::Connections => 10.0,
+            ExposureCategory::Sessions => 10.0,
+        }
+    }
+}
+
+/// A one-click fix: the Tauri command the UI should open, pre-filled with `args`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Remediation {
+    pub label: String,
+    pub command: String,
+    pub args: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExposureFinding {
+    pub category: ExposureCategory,
+    pub title: String,
+    pub points: f64,
+    pub remediation: Option<Remediation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryScore {
+    pub category: ExposureCategory,
+    pub points: f64,
+    pub max_points: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExposureReport {
+    /// 0 (nothing exposed) to 100 (maximum exposure).
+    pub score: u32,
+    pub categories: Vec<CategoryScore>,
+    /// Strongest contributors first.
+    pub findings: Vec<ExposureFinding>,
+    /// Data sources that could not be audited (e.g. restricted in OAuth mode).
+    pub skipped_sources: Vec<String>,
+}
+
+/// Complete `bulk_delete_messages` arguments, so a remediation deserializes as is.
+fn purge_args(
+    channel_ids: &[&str],
+    only_attachments: bool,
+    message_ids: Option<&BTreeMap<&str, Vec<&str>>>,
+) -> Value {
+    json!({ "options": {
+        "channelIds": channel_ids,
+        "purgeReactions": false,
+        "simulation": false,
+        "onlyAttachments": only_attachments,
+        "closeEmptyDms": false,
+        "useCache": true,
+        "messageIds": message_ids
+    } })
+}
+
+/// Builds explainable findings from the individual audit sources and folds them into a score.
+pub struct ExposureScorer;
+
+impl ExposureScorer {
+    /// Scores the sheer volume of cached history; grows logarithmically. `channel_ids`
+    /// are the channels holding those messages, which the purge remediations target.
+    pub fn footprint_findings(
+        total_messages: i64,
+        total_attachments: i64,
+        channel_ids: &[String],
+    ) -> Vec<ExposureFinding> {
+        let channel_ids: Vec<&str> = channel_ids.iter().map(|c| c.as_str()).collect();
+        let mut findings = Vec::new();
+        if total_messages > 0 {
+            findings.push(ExposureFinding {
+                category: ExposureCategory::MessageFootprint,
+                title: format!("{} indexed messages authored by you", total_messages),
+                points: ((total_messages as f64 + 1.0).log10() * 4.0).min(16.0),
+                remediation: Some(Remediation {
+                    label: "Open bulk message purge".into(),
+                    command: "bulk_delete_messages".into(),
+                    args: purge_args(&channel_ids, false, None),
+                }),
+            });
+        }
+        if total_attachments > 0 {
+            findings.push(ExposureFinding {
+                category: ExposureCategory::MessageFootprint,
+                title: format!("{} messages with attachments", total_attachments),
+                points: ((total_attachments as f64 + 1.0).log10() * 2.0).min(4.0),
+                remediation: Some(Remediation {
+                    label: "Purge attachment messages".into(),
+                    command: "bulk_delete_messages".into(),
+                    args: purge_args(&channel_ids, true, None),
+                }),
+            });
+        }
+        findings
+    }
+
+    fn pii_weight(kind: &str) -> f64 {
+        match kind {
+            "Credit Card" | "SSN (US)" | "Discord Token" => 10.0,
+            "Email" => 5.0,
+            _ => 3.0,
+        }
+    }
+
+    /// Groups PII hits by type and reports how widely each type is spread.
+    /// `channel_guilds` maps channel IDs to their guild (`None` for DMs).
+    pub fn pii_findings(
+        results: &[PIIResult],
+        channel_guilds: &HashMap<String, Option<String>>,
+    ) -> Vec<ExposureFinding> {
+        let mut by_type: BTreeMap<&str, (usize, BTreeMap<&str, Vec<&str>>)> = BTreeMap::new();
+        for result in results {
+            for kind in &result.detected_types {
+                let entry = by_type.entry(kind.as_str()).or_default();
+                entry.0 += 1;
+                entry
+                    .1
+                    .entry(result.channel_id.as_str())
+                    .or_default()
+                    .push(result.message_id.as_str());
+            }
+        }
+
+        by_type
+            .into_iter()
+            .map(|(kind, (count, message_ids))| {
+                let channels: Vec<&str> = message_ids.keys().copied().collect();
+                let guilds: HashSet<&String> = channels
+                    .iter()
+                    .filter_map(|c| channel_guilds.get(*c).and_then(|g| g.as_ref()))
+                    .collect();
+                let dm_count = channels
+                    .iter()
+                    .filter(|c| matches!(channel_guilds.get(**c), Some(None)))
+                    .count();
+
+                let mut scope = Vec::new();
+                if !guilds.is_empty() {
+                    scope.push(format!("{} servers", guilds.len()));
+                }
+                if dm_count > 0 {
+                    scope.push(format!("{} DMs", dm_count));
+                }
+                if scope.is_empty() {
+                    scope.push(format!("{} channels", channels.len()));
+                }
+
+                ExposureFinding {
+                    category: ExposureCategory::PersonalData,
+                    title: format!(
+                        "{} messages containing {} in {}",
+                        count,
+                        kind,
+                        scope.join(" and ")
+                    ),
+                    points: Self::pii_weight(kind) * (1.0 + (count as f64).log10()),
+                    remediation: Some(Remediation {
+                        label: format!("Purge messages containing {}", kind),
+                        command: "bulk_delete_messages".into(),
+                        args: purge_args(&channels, false, Some(&message_ids)),
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Flags authorized applications through the existing integration risk audit.
+    pub fn integration_findings(tokens: &[Value]) -> Vec<ExposureFinding> {
+        let mut by_scope: BTreeMap<String, Vec<&Value>> = BTreeMap::new();
+        let mut findings = Vec::new();
+
+        for token in tokens {
+            let report = IntegrationAuditor::audit_app(token);
+            if report.risk_score == 0 {
+                continue;
+            }
+            for scope in token["scopes"].as_array().into_iter().flatten() {
+                if let Some(s) = scope.as_str() {
+                    by_scope.entry(s.to_string()).or_default().push(token);
+                }
+            }
+            let name = token["application"]["name"]
+                .as_str()
+                .unwrap_or("Unknown application");
+            findings.push(ExposureFinding {
+                category: ExposureCategory::Integrations,
+                title: format!("{}: {}", name, report.warnings.join(", ")),
+                points: report.risk_score as f64 / 5.0,
+                remediation: token["id"].as_str().map(|id| Remediation {
+                    label: format!("Revoke {}", name),
+                    command: "revoke_oauth_token".into(),
+                    args: json!({ "tokenId": id }),
+                }),
+            });
+        }
+
+        // Summary lines such as "2 apps with messages.read" carry no extra points.
+        for (scope, apps) in by_scope {
+            if apps.len() > 1 && ["messages.read", "rpc", "guilds.join"].contains(&scope.as_str()) {
+                findings.push(ExposureFinding {
+                    category: ExposureCategory::Integrations,
+                    title: format!("{} apps with {}", apps.len(), scope),
+                    points: 0.0,
+                    remediation: None,
+                });
+            }
+        }
+        findings
+    }
+
+    /// Connected accounts visible to everyone link the Discord identity to other platforms.
+    pub fn connection_findings(connections: &[Value]) -> Vec<ExposureFinding> {
+        let public: Vec<String> = connections
+            .iter()
+            .filter(|c| c["visibility"].as_u64() == Some(1))
+            .map(|c| {
+                format!(
+                    "{} ({})",
+                    c["type"].as_str().unwrap_or("unknown"),
+                    c["name"].as_str().unwrap_or("?")
+                )
+            })
+            .collect();
+
+        if public.is_empty() {
+            return Vec::new();
+        }
+        vec![ExposureFinding {
+            category: ExposureCategory::Connections,
+            title: format!(
+                "{} publicly visible connections: {}",
+                public.len(),
+                public.join(", ")
+            ),
+            points: public.len() as f64 * 2.5,
+            remediation: Some(Remediation {
+                label: "Review connection visibility".into(),
+                command: "open_discord_url_for_action".into(),
+                args: json!({ "actionType": "data_privacy" }),
+            }),
+        }]
+    }
+
+    /// Every session beyond the current one is another device holding a live token.
+    pub fn session_findings(sessions: &Value) -> Vec<ExposureFinding> {
+        let count = sessions["user_sessions"]
+            .as_array()
+            .map(|s| s.len())
+            .unwrap_or(0);
+        if count <= 1 {
+            return Vec::new();
+        }
+        vec![ExposureFinding {
+            category: ExposureCategory::Sessions,
+            title: format!("{} active sessions on other devices", count - 1),
+            points: (count - 1) as f64 * 2.0,
+            remediation: Some(Remediation {
+                label: "Log out all other sessions".into(),
+                command: "terminate_all_sessions".into(),
+                args: json!({}),
+            }),
+        }]
+    }
+
+    /// Caps each category, sums the result and orders findings by contribution.
+    pub fn build_report(
+        mut findings: Vec<ExposureFinding>,
+        skipped_sources: Vec<String>,
+    ) -> ExposureReport {
+        let mut totals: BTreeMap<ExposureCategory, f64> = BTreeMap::new();
+        for finding in &findings {
+            *totals.entry(finding.category).or_default() += finding.points;
+        }
+
+        let categories: Vec<CategoryScore> = totals
+            .into_iter()
+            .map(|(category, points)| CategoryScore {
+                category,
+                points: points.min(category.max_points()),
+                max_points: category.max_points(),
+            })
+            .collect();
+        let score = categories.iter().map(|c| c.points).sum::<f64>().min(100.0);
+
+        findings.sort_by(|a, b| b.points.total_cmp(&a.points));
+        ExposureReport {
+            score: score.round() as u32,
+            categories,
+            findings,
+            skipped_sources,
+        }
+    }
+}
@@ -1,10 +1,13 @@
 // src-tauri/src/core/forensics/mod.rs
 
+pub mod analytics;
 pub mod auditor;
 pub mod backup;
 pub mod burner;
+pub mod container;
 pub mod correlation;
 pub mod export;
+pub mod exposure;
 pub mod honey;
 pub mod metadata;
 pub mod pii;
@@ -13,6 +13,7 @@ static PII_PATTERNS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
 pub struct PIIResult {
     pub message_id: String,
     pub channel_id: String,
+    pub author_id: String,
     pub detected_types: Vec<String>,
     pub snippet: String,
 }
@@ -47,9 +48,24 @@ impl PIIClassifier {
         })
     }
 
-    /// Scans the entire local cache for Personally Identifiable Information.
-    pub fn scan_cache(app: &AppHandle) -> Result<Vec<PIIResult>, AppError> {
-        let messages = CacheManager::search_messages(app, "")?; // Get all cached messages
+    /// Scans an identity's local cache for Personally Identifiable Information.
+    pub fn scan_cache(app: &AppHandle, identity_id: &str) -> Result<Vec<PIIResult>, AppError> {
+        let messages = CacheManager::search_messages(app, identity_id, "")?; // Get all cached messages
+        Ok(Self::classify(messages))
+    }
+
+    /// Scans every live message the identity wrote itself.
+    pub fn scan_own_messages(
+        app: &AppHandle,
+        identity_id: &str,
+    ) -> Result<Vec<PIIResult>, AppError> {
+        Ok(Self::classify(CacheManager::own_messages(
+            app,
+            identity_id,
+        )?))
+    }
+
+    fn classify(messages: Vec<serde_json::Value>) -> Vec<PIIResult> {
         let patterns = Self::get_patterns();
         let mut results = Vec::new();
 
@@ -67,12 +83,13 @@ impl PIIClassifier {
                 results.push(PIIResult {
                     message_id: msg["id"].as_str().unwrap_or_default().to_string(),
                     channel_id: msg["channel_id"].as_str().unwrap_or_default().to_string(),
+                    author_id: msg["author_id"].as_str().unwrap_or_default().to_string(),
                     detected_types: detected,
                     snippet: content.chars().take(100).collect(), // Redacted snippet would be better, but for now...
                 });
             }
         }
 
-        Ok(results)
+        results
     }
 }
@@ -0,0 +1,341 @@
+// src-tauri/src/core/gdpr/activity.rs
+
+use crate::core::cache::CacheManager;
+use crate::core::crypto::Crypto;
+use crate::core::error::AppError;
+use crate::core::gdpr::parser::ActivityEvent;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rusqlite::params;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+/// Event properties that describe the device, client or location an event came from.
+pub const OBSERVED_FIELDS: &[&str] = &[
+    "os",
+    "os_version",
+    "os_sdk_version",
+    "device",
+    "device_vendor_id",
+    "browser",
+    "browser_user_agent",
+    "client_version",
+    "release_channel",
+    "system_locale",
+    "ip",
+    "isp",
+    "city",
+    "region_code",
+    "country_code",
+    "time_zone",
+];
+
+#[derive(Serialize, Default)]
+pub struct ActivitySummary {
+    pub total_events: i64,
+    pub by_category: Vec<CategoryCount>,
+    /// Most frequent event types first.
+    pub by_type: Vec<EventTypeCount>,
+    /// Device, OS and location values Discord recorded, most frequent first.
+    pub observed: Vec<ObservedValue>,
+}
+
+#[derive(Serialize)]
+pub struct CategoryCount {
+    pub category: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct EventTypeCount {
+    pub event_type: String,
+    pub count: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+}
+
+#[derive(Serialize)]
+pub struct ObservedValue {
+    pub field: String,
+    pub value: String,
+    pub count: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+}
+
+#[derive(Serialize)]
+pub struct ActivityRecord {
+    pub category: String,
+    pub event_type: String,
+    pub timestamp: i64,
+    pub properties: serde_json::Value,
+}
+
+/// Telemetry events retained in a data package's `activity/` folder.
+pub struct ActivityLog;
+
+impl ActivityLog {
+    /// Extracts the device/OS/location values of a single event.
+    pub fn observed_values(event: &ActivityEvent) -> Vec<(&'static str, String)> {
+        OBSERVED_FIELDS
+            .iter()
+            .filter_map(|field| {
+                let value = match event.properties.get(*field)? {
+                    serde_json::Value::String(s) => s.trim_matches('"').to_string(),
+                    serde_json::Value::Number(n) => n.to_string(),
+                    _ => return None,
+                };
+                (!value.is_empty()).then_some((*field, value))
+            })
+            .collect()
+    }
+
+    /// Key for `observation_hash`, derived from the content key so the two never coincide.
+    pub fn observation_key(content_key: &str) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, content_key.as_bytes())
+            .expand(b"activity-observation", &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        key
+    }
+
+    /// HMAC that groups equal observed values without storing them in the clear.
+    pub fn observation_hash(key: &[u8; 32], field: &str, value: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(field.as_bytes());
+        mac.update(&[0u8]);
+        mac.update(value.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn record_observations(
+        conn: &rusqlite::Connection,
+        enc_key: &str,
+        hash_key: &[u8; 32],
+        identity_id: &str,
+        event: &ActivityEvent,
+    ) -> Result<(), AppError> {
+        for (field, value) in Self::observed_values(event) {
+            conn.execute(
+                "INSERT INTO activity_observations (identity_id, field, value_hash, value, count, first_seen, last_seen)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)
+                 ON CONFLICT(identity_id, field, value_hash) DO UPDATE SET
+                    count = count + 1,
+                    first_seen = CASE
+                        WHEN first_seen = 0 THEN excluded.first_seen
+                        WHEN excluded.first_seen = 0 THEN first_seen
+                        ELSE MIN(first_seen, excluded.first_seen)
+                    END,
+                    last_seen = MAX(last_seen, excluded.last_seen)",
+                params![
+                    identity_id,
+                    field,
+                    Self::observation_hash(hash_key, field, &value),
+                    Crypto::encrypt(enc_key, &value)?,
+                    event.timestamp
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Recounts observations from the encrypted events, for caches whose plaintext
+    /// counters were dropped by the schema migration.
+    fn rebuild_observations(app: &AppHandle, identity_id: &str) -> Result<(), AppError> {
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+        let hash_key = Self::observation_key(&enc_key);
+        let mut conn = CacheManager::get_connection(app)?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "SELECT event_type, timestamp, properties FROM activity_events WHERE identity_id = ?1",
+            )?;
+            let rows = stmt.query_map([identity_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?;
+            // One event at a time, so a large history is never decrypted all at once.
+            for (event_type, timestamp, encrypted) in rows.flatten() {
+                let Some(properties) = Crypto::decrypt(&enc_key, &encrypted)
+                    .ok()
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                else {
+                    continue;
+                };
+                let event = ActivityEvent {
+                    event_id: None,
+                    event_type,
+                    timestamp,
+                    properties,
+                };
+                Self::record_observations(&tx, &enc_key, &hash_key, identity_id, &event)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Events without an `event_id` are keyed by a hash of their contents instead.
+    fn event_key(event: &ActivityEvent) -> String {
+        event.event_id.clone().unwrap_or_else(|| {
+            let serialized = serde_json::to_string(&event.properties).unwrap_or_default();
+            hex::encode(Sha256::digest(serialized.as_bytes()))
+        })
+    }
+
+    /// Stores a batch of events with encrypted properties and updates the observation
+    /// counters. Events already imported are ignored. Returns the number of new events.
+    pub fn import_batch(
+        app: &AppHandle,
+        identity_id: &str,
+        category: &str,
+        events: &[ActivityEvent],
+    ) -> Result<usize, AppError> {
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+        let hash_key = Self::observation_key(&enc_key);
+        let mut conn = CacheManager::get_connection(app)?;
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+
+        for event in events {
+            let properties = serde_json::to_string(&event.properties)?;
+            let added = tx.execute(
+                "INSERT OR IGNORE INTO activity_events (identity_id, event_id, category, event_type, timestamp, properties) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    identity_id,
+                    Self::event_key(event),
+                    category,
+                    event.event_type,
+                    event.timestamp,
+                    Crypto::encrypt(&enc_key, &properties)?
+                ],
+            )?;
+            if added == 0 {
+                continue;
+            }
+            inserted += 1;
+            Self::record_observations(&tx, &enc_key, &hash_key, identity_id, event)?;
+        }
+
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    pub fn summary(app: &AppHandle, identity_id: &str) -> Result<ActivitySummary, AppError> {
+        let conn = CacheManager::get_connection(app)?;
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+
+        let (events, observations): (i64, i64) = conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM activity_events WHERE identity_id = ?1),
+                    (SELECT COUNT(*) FROM activity_observations WHERE identity_id = ?1)",
+            [identity_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if events > 0 && observations == 0 {
+            Self::rebuild_observations(app, identity_id)?;
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT category, COUNT(*) AS total FROM activity_events
+             WHERE identity_id = ?1 GROUP BY category ORDER BY total DESC",
+        )?;
+        let by_category: Vec<CategoryCount> = stmt
+            .query_map([identity_id], |row| {
+                Ok(CategoryCount {
+                    category: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .flatten()
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT event_type, COUNT(*) AS total, MIN(NULLIF(timestamp, 0)), MAX(timestamp)
+             FROM activity_events WHERE identity_id = ?1
+             GROUP BY event_type ORDER BY total DESC",
+        )?;
+        let by_type: Vec<EventTypeCount> = stmt
+            .query_map([identity_id], |row| {
+                Ok(EventTypeCount {
+                    event_type: row.get(0)?,
+                    count: row.get(1)?,
+                    first_seen: row.get::<_, Option<i64>>(2)?.unwrap_or(0),
+                    last_seen: row.get(3)?,
+                })
+            })?
+            .flatten()
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT field, value, count, first_seen, last_seen FROM activity_observations
+             WHERE identity_id = ?1 ORDER BY field, count DESC",
+        )?;
+        let observed: Vec<ObservedValue> = stmt
+            .query_map([identity_id], |row| {
+                Ok(ObservedValue {
+                    field: row.get(0)?,
+                    value: Crypto::decrypt(&enc_key, &row.get::<_, String>(1)?).unwrap_or_default(),
+                    count: row.get(2)?,
+                    first_seen: row.get(3)?,
+                    last_seen: row.get(4)?,
+                })
+            })?
+            .flatten()
+            .collect();
+
+        Ok(ActivitySummary {
+            total_events: by_category.iter().map(|c| c.count).sum(),
+            by_category,
+            by_type,
+            observed,
+        })
+    }
+
+    /// Pages through decrypted events, newest first, optionally filtered by type.
+    pub fn events(
+        app: &AppHandle,
+        identity_id: &str,
+        event_type: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ActivityRecord>, AppError> {
+        let conn = CacheManager::get_connection(app)?;
+        let enc_key =
+            crate::core::vault::encryption::EncryptionManager::get_or_create_encryption_key(app)?;
+        let mut stmt = conn.prepare(
+            "SELECT category, event_type, timestamp, properties FROM activity_events
+             WHERE identity_id = ?1 AND (?2 IS NULL OR event_type = ?2)
+             ORDER BY timestamp DESC LIMIT ?3 OFFSET ?4",
+        )?;
+        let rows = stmt.query_map(params![identity_id, event_type, limit, offset], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        Ok(rows
+            .flatten()
+            .map(
+                |(category, event_type, timestamp, encrypted)| ActivityRecord {
+                    category,
+                    event_type,
+                    timestamp,
+                    properties: Crypto::decrypt(&enc_key, &encrypted)
+                        .ok()
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or(serde_json::Value::Null),
+                },
+            )
+            .collect())
+    }
+}
@@ -0,0 +1,152 @@
+// src-tauri/src/core/gdpr/diff.rs
+
+use crate::api::discord::gdpr::GdprDiscovery;
+use crate::core::cache::CacheManager;
+use crate::core::error::AppError;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tauri::AppHandle;
+
+/// The comparable state of an account at one point in time.
+#[derive(Debug, Default)]
+pub struct PackageSnapshot {
+    /// Own message IDs keyed by channel ID.
+    pub messages: HashMap<String, HashSet<String>>,
+    pub guilds: HashSet<String>,
+    /// `type:account_id`
+    pub connections: HashSet<String>,
+    pub relationships: HashSet<String>,
+    /// When set, only these channels are fully known (e.g. deep scanned in the cache),
+    /// so message differences elsewhere are not reported.
+    pub complete_channels: Option<HashSet<String>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SetDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SetDiff {
+    pub fn between(old: &HashSet<String>, new: &HashSet<String>) -> Self {
+        let sorted = |set: HashSet<&String>| -> Vec<String> {
+            set.into_iter()
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        SetDiff {
+            added: sorted(new.difference(old).collect()),
+            removed: sorted(old.difference(new).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChannelMessageDiff {
+    pub channel_id: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PackageDiff {
+    /// Only channels whose messages changed.
+    pub messages: Vec<ChannelMessageDiff>,
+    pub messages_added: usize,
+    pub messages_removed: usize,
+    pub guilds: SetDiff,
+    pub connections: SetDiff,
+    pub relationships: SetDiff,
+}
+
+impl PackageSnapshot {
+    pub fn from_discovery(discovery: &GdprDiscovery) -> Self {
+        PackageSnapshot {
+            messages: discovery
+                .messages
+                .iter()
+                .map(|(channel, msgs)| {
+                    (channel.clone(), msgs.iter().map(|m| m.id.clone()).collect())
+                })
+                .collect(),
+            guilds: discovery.guild_ids.iter().cloned().collect(),
+            connections: discovery.connections.iter().cloned().collect(),
+            relationships: discovery.user_ids.iter().cloned().collect(),
+            complete_channels: None,
+        }
+    }
+
+    /// Builds a snapshot from live data in the cache. Messages, guilds and relationships
+    /// imported from a package are excluded, and only deep-scanned channels are treated
+    /// as complete.
+    pub fn from_cache(app: &AppHandle, identity_id: &str) -> Result<Self, AppError> {
+        let conn = CacheManager::get_connection(app)?;
+        let mut snapshot = PackageSnapshot::default();
+
+        let mut stmt = conn.prepare(
+            "SELECT channel_id, id FROM messages
+             WHERE identity_id = ?1 AND author_id = ?1 AND is_deleted = 0 AND source = 'live'",
+        )?;
+        let rows = stmt.query_map([identity_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for (channel_id, id) in rows.flatten() {
+            snapshot.messages.entry(channel_id).or_default().insert(id);
+        }
+
+        let collect = |sql: &str| -> Result<HashSet<String>, AppError> {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map([identity_id], |row| row.get::<_, String>(0))?;
+            Ok(rows.flatten().collect())
+        };
+        snapshot.guilds =
+            collect("SELECT id FROM guilds WHERE identity_id = ?1 AND source = 'live'")?;
+        snapshot.connections =
+            collect("SELECT type || ':' || account_id FROM connections WHERE identity_id = ?1")?;
+        snapshot.relationships = collect(
+            "SELECT user_id FROM relationships WHERE identity_id = ?1 AND source = 'live'",
+        )?;
+        snapshot.complete_channels = Some(collect(
+            "SELECT channel_id FROM sync_cursors WHERE identity_id = ?1",
+        )?);
+        Ok(snapshot)
+    }
+}
+
+impl PackageDiff {
+    /// Differences going from `old` to `new`: "added" exists only in `new`.
+    pub fn between(old: &PackageSnapshot, new: &PackageSnapshot) -> Self {
+        let empty = HashSet::new();
+        let is_complete = |channel: &String| {
+            [&old.complete_channels, &new.complete_channels]
+                .iter()
+                .all(|c| c.as_ref().is_none_or(|set| set.contains(channel)))
+        };
+
+        let channels: BTreeSet<&String> = old.messages.keys().chain(new.messages.keys()).collect();
+        let mut diff = PackageDiff::default();
+        for channel in channels.into_iter().filter(|c| is_complete(*c)) {
+            let set = SetDiff::between(
+                old.messages.get(channel).unwrap_or(&empty),
+                new.messages.get(channel).unwrap_or(&empty),
+            );
+            if set.added.is_empty() && set.removed.is_empty() {
+                continue;
+            }
+            diff.messages_added += set.added.len();
+            diff.messages_removed += set.removed.len();
+            diff.messages.push(ChannelMessageDiff {
+                channel_id: channel.clone(),
+                added: set.added,
+                removed: set.removed,
+            });
+        }
+
+        diff.guilds = SetDiff::between(&old.guilds, &new.guilds);
+        diff.connections = SetDiff::between(&old.connections, &new.connections);
+        diff.relationships = SetDiff::between(&old.relationships, &new.relationships);
+        diff
+    }
+}
@@ -0,0 +1,323 @@
+// src-tauri/src/core/gdpr/harvest.rs
+
+use crate::api::rate_limiter::ApiHandle;
+use crate::core::error::AppError;
+use crate::core::gdpr::importer::PackageImporter;
+use crate::core::logger::Logger;
+use crate::core::vault::Vault;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Discord allows one data package request every 30 days.
+pub const HARVEST_COOLDOWN_SECS: i64 = 30 * 86400;
+
+/// Pending requests are polled at most this often.
+const POLL_INTERVAL_SECS: i64 = 6 * 3600;
+
+const RECORDS_KEY: &str = "harvest_records";
+const SETTINGS_KEY: &str = "harvest_settings";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HarvestStatus {
+    Pending,
+    Ready,
+    Imported,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HarvestRecord {
+    pub identity_id: String,
+    pub harvest_id: Option<String>,
+    pub status: HarvestStatus,
+    pub requested_at: i64,
+    pub completed_at: Option<i64>,
+    pub last_polled: Option<i64>,
+    /// Earliest time Discord will accept another request for this identity.
+    pub next_request_at: i64,
+    pub imported_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HarvestSettings {
+    /// Folder the browser downloads packages into (e.g. ~/Downloads).
+    pub watch_dir: Option<String>,
+    pub auto_import: bool,
+}
+
+/// Persists data package requests and drives them from request to import.
+pub struct HarvestTracker;
+
+impl HarvestTracker {
+    pub fn load_records(app: &AppHandle) -> Vec<HarvestRecord> {
+        Vault::get_credential(app, RECORDS_KEY)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_records(app: &AppHandle, records: &[HarvestRecord]) -> Result<(), AppError> {
+        Vault::set_credential(app, RECORDS_KEY, &serde_json::to_string(records)?)
+    }
+
+    pub fn load_settings(app: &AppHandle) -> HarvestSettings {
+        Vault::get_credential(app, SETTINGS_KEY)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_settings(app: &AppHandle, settings: &HarvestSettings) -> Result<(), AppError> {
+        Vault::set_credential(app, SETTINGS_KEY, &serde_json::to_string(settings)?)
+    }
+
+    /// Returns an error while the identity is still inside Discord's request cooldown.
+    pub fn ensure_request_allowed(app: &AppHandle, identity_id: &str) -> Result<(), AppError> {
+        let now = chrono::Utc::now().timestamp();
+        if let Some(next) = Self::load_records(app)
+            .iter()
+            .filter(|r| r.identity_id == identity_id)
+            .map(|r| r.next_request_at)
+            .max()
+            && next > now
+        {
+            let allowed_on = chrono::DateTime::from_timestamp(next, 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            return Err(AppError::new(
+                &format!("A new data package can be requested on {}.", allowed_on),
+                "harvest_cooldown",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stores a freshly created request from the `POST /users/@me/harvest` response.
+    pub fn record_request(
+        app: &AppHandle,
+        identity_id: &str,
+        response: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        let now = chrono::Utc::now().timestamp();
+        let requested_at = Self::parse_time(&response["created_at"]).unwrap_or(now);
+        let mut records = Self::load_records(app);
+        records.retain(|r| !(r.identity_id == identity_id && r.status == HarvestStatus::Pending));
+        records.push(HarvestRecord {
+            identity_id: identity_id.to_string(),
+            harvest_id: response["harvest_id"].as_str().map(|s| s.to_string()),
+            status: HarvestStatus::Pending,
+            requested_at,
+            completed_at: None,
+            last_polled: Some(now),
+            next_request_at: requested_at + HARVEST_COOLDOWN_SECS,
+            imported_path: None,
+        });
+        Self::save_records(app, &records)
+    }
+
+    /// Applies a `GET /users/@me/harvest` response to the identity's pending request.
+    /// Returns true when the request just became ready.
+    pub fn apply_status(
+        records: &mut [HarvestRecord],
+        identity_id: &str,
+        status: &serde_json::Value,
+        now: i64,
+    ) -> bool {
+        let Some(record) = records
+            .iter_mut()
+            .rev()
+            .find(|r| r.identity_id == identity_id && r.status == HarvestStatus::Pending)
+        else {
+            return false;
+        };
+        record.last_polled = Some(now);
+        if record.harvest_id.is_none() {
+            record.harvest_id = status["harvest_id"].as_str().map(|s| s.to_string());
+        }
+        match Self::parse_time(&status["completed_at"]) {
+            Some(completed_at) => {
+                record.status = HarvestStatus::Ready;
+                record.completed_at = Some(completed_at);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Records a status poll made through the UI.
+    pub fn update_from_status(
+        app: &AppHandle,
+        identity_id: &str,
+        status: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        let mut records = Self::load_records(app);
+        let now = chrono::Utc::now().timestamp();
+        if Self::apply_status(&mut records, identity_id, status, now) {
+            Self::notify_ready(app, identity_id);
+        }
+        Self::save_records(app, &records)
+    }
+
+    fn parse_time(value: &serde_json::Value) -> Option<i64> {
+        value
+            .as_str()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.timestamp())
+    }
+
+    fn notify_ready(app: &AppHandle, identity_id: &str) {
+        Logger::info(
+            app,
+            &format!(
+                "[GDPR] Data package for {} is ready to download",
+                identity_id
+            ),
+            None,
+        );
+        let _ = app.emit(
+            "harvest_ready",
+            serde_json::json!({ "identity_id": identity_id }),
+        );
+    }
+
+    /// Janitor hook: polls pending requests and imports downloaded packages.
+    pub async fn run_cycle(app: &AppHandle) -> Result<(), AppError> {
+        let mut records = Self::load_records(app);
+        if records.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp();
+        let api_handle = app.state::<ApiHandle>();
+
+        let due: Vec<String> = records
+            .iter()
+            .filter(|r| r.status == HarvestStatus::Pending)
+            .filter(|r| now - r.last_polled.unwrap_or(0) >= POLL_INTERVAL_SECS)
+            .map(|r| r.identity_id.clone())
+            .collect();
+        for identity_id in due {
+            let Ok(identity) = Vault::get_identity(app, &identity_id) else {
+                continue;
+            };
+            match api_handle
+                .send_request_json(
+                    reqwest::Method::GET,
+                    "https://discord.com/api/v9/users/@me/harvest",
+                    None,
+                    &identity.token,
+                    identity.is_oauth,
+                    None,
+                )
+                .await
+            {
+                Ok(status) => {
+                    if Self::apply_status(&mut records, &identity_id, &status, now) {
+                        Self::notify_ready(app, &identity_id);
+                    }
+                }
+                Err(e) => Logger::warn(
+                    app,
+                    &format!("[GDPR] Harvest poll failed for {}: {}", identity_id, e),
+                    None,
+                ),
+            }
+        }
+
+        let settings = Self::load_settings(app);
+        if settings.auto_import
+            && let Some(dir) = &settings.watch_dir
+        {
+            Self::import_downloads(app, &mut records, Path::new(dir)).await;
+        }
+
+        Self::save_records(app, &records)
+    }
+
+    /// Imports the newest package zip in `dir` owned by each ready request's identity.
+    /// Packages that add nothing leave the request ready for a later download.
+    async fn import_downloads(app: &AppHandle, records: &mut [HarvestRecord], dir: &Path) {
+        let mut imported: Vec<String> = records
+            .iter()
+            .filter_map(|r| r.imported_path.clone())
+            .collect();
+
+        for record in records
+            .iter_mut()
+            .filter(|r| r.status == HarvestStatus::Ready)
+        {
+            let since = record.completed_at.unwrap_or(record.requested_at);
+            let Some(path) = Self::find_package(dir, since, &imported, |path| {
+                PackageImporter::package_owner(path).as_deref() == Some(&record.identity_id)
+            }) else {
+                continue;
+            };
+            let path_str = path.to_string_lossy().to_string();
+            imported.push(path_str.clone());
+
+            Logger::info(
+                app,
+                &format!("[GDPR] Auto-importing downloaded package {}", path_str),
+                None,
+            );
+            let (app_clone, path_clone) = (app.clone(), path_str.clone());
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                PackageImporter::import(&app_clone, &path_clone)
+            })
+            .await
+            .map_err(|e| AppError::new(&e.to_string(), "harvest_import_failed"))
+            .and_then(|result| result);
+            match result {
+                Ok(summary) if summary.is_empty() => Logger::warn(
+                    app,
+                    &format!("[GDPR] {} contained no importable data", path_str),
+                    None,
+                ),
+                Ok(summary) => {
+                    record.status = HarvestStatus::Imported;
+                    record.imported_path = Some(path_str);
+                    let _ = app.emit("harvest_imported", &summary);
+                }
+                Err(e) => Logger::warn(
+                    app,
+                    &format!("[GDPR] Auto-import of {} failed: {}", path_str, e),
+                    None,
+                ),
+            }
+        }
+    }
+
+    /// Newest `.zip` in `dir` modified after `since` that has not been imported yet and
+    /// that `owned` accepts.
+    pub fn find_package(
+        dir: &Path,
+        since: i64,
+        imported: &[String],
+        owned: impl Fn(&str) -> bool,
+    ) -> Option<PathBuf> {
+        let mut candidates: Vec<(i64, PathBuf)> = std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+            })
+            .filter(|path| !imported.contains(&path.to_string_lossy().to_string()))
+            .filter_map(|path| {
+                let modified = std::fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()?
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .ok()?
+                    .as_secs() as i64;
+                (modified >= since).then_some((modified, path))
+            })
+            .collect();
+        candidates.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        candidates
+            .into_iter()
+            .map(|(_, path)| path)
+            .find(|path| owned(&path.to_string_lossy()))
+    }
+}
@@ -0,0 +1,238 @@
+// src-tauri/src/core/gdpr/importer.rs
+
+use crate::api::discord::types::Relationship;
+use crate::core::cache::CacheManager;
+use crate::core::error::AppError;
+use crate::core::gdpr::activity::ActivityLog;
+use crate::core::gdpr::parser::{self, PackageEntry, PackageMessage};
+use crate::core::gdpr::reader::PackageReader;
+use crate::core::logger::Logger;
+use crate::core::vault::Vault;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Read;
+use tauri::{AppHandle, Emitter};
+
+/// Activity events are written in transactions of this many rows.
+const ACTIVITY_BATCH_SIZE: usize = 5000;
+
+/// Package messages are written in transactions of this many rows.
+const MESSAGE_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub identity_id: String,
+    pub channels: usize,
+    pub messages: usize,
+    /// Messages that were new to the cache (not already indexed from the live API).
+    pub new_messages: usize,
+    pub guilds: usize,
+    pub relationships: usize,
+    pub activity_files: usize,
+    /// Telemetry events that were new to the cache.
+    pub activity_events: usize,
+    /// Entries that could not be read or parsed, with the reason.
+    pub skipped_entries: Vec<String>,
+}
+
+impl ImportSummary {
+    /// True when the package contributed nothing to the cache.
+    pub fn is_empty(&self) -> bool {
+        self.channels == 0
+            && self.messages == 0
+            && self.guilds == 0
+            && self.relationships == 0
+            && self.activity_events == 0
+    }
+}
+
+/// Loads a Discord data package ("Request all of my Data"), zipped or extracted,
+/// into the forensic cache.
+pub struct PackageImporter;
+
+impl PackageImporter {
+    /// User ID from the package's account file, if it is a readable data package.
+    pub fn package_owner(zip_path: &str) -> Option<String> {
+        let mut reader = PackageReader::open(zip_path).ok()?;
+        let account = reader.read_first(&PackageEntry::Account)?;
+        let account = serde_json::from_str::<Value>(&account).ok()?;
+        parser::snowflake(&account["id"])
+    }
+
+    pub fn import(app: &AppHandle, zip_path: &str) -> Result<ImportSummary, AppError> {
+        Logger::info(
+            app,
+            &format!("[GDPR] Importing data package: {}", zip_path),
+            None,
+        );
+
+        let mut reader = PackageReader::open(zip_path)?;
+        let account = reader
+            .read_first(&PackageEntry::Account)
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+        let identity_id = Self::resolve_identity(app, account.as_ref())?;
+        let mut summary = ImportSummary {
+            identity_id: identity_id.clone(),
+            ..Default::default()
+        };
+
+        if let Some(account) = &account {
+            let relationships: Vec<Relationship> = account["relationships"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|r| serde_json::from_value(r.clone()).ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+            CacheManager::import_package_relationships(app, &identity_id, &relationships)?;
+            summary.relationships = relationships.len();
+        }
+
+        let mut channels = HashSet::new();
+        let mut guilds = HashSet::new();
+        let mut last_percent = None;
+
+        reader.for_each(
+            |file, content| {
+                match &file.kind {
+                    PackageEntry::MessagesCsv { channel_id }
+                    | PackageEntry::MessagesJson { channel_id } => {
+                        let mut batch = Vec::with_capacity(MESSAGE_BATCH_SIZE);
+                        let mut import = |batch: &mut Vec<PackageMessage>| -> Result<(), AppError> {
+                            summary.new_messages += CacheManager::import_package_messages(
+                                app,
+                                &identity_id,
+                                channel_id,
+                                batch,
+                            )?;
+                            summary.messages += batch.len();
+                            batch.clear();
+                            Ok(())
+                        };
+                        let collect = |message: PackageMessage| -> Result<(), AppError> {
+                            batch.push(message);
+                            if batch.len() >= MESSAGE_BATCH_SIZE {
+                                import(&mut batch)?;
+                            }
+                            Ok(())
+                        };
+                        if matches!(file.kind, PackageEntry::MessagesCsv { .. }) {
+                            parser::read_messages_csv(content, collect)?;
+                        } else {
+                            parser::read_messages_json(content, collect)?;
+                        }
+                        import(&mut batch)?;
+                        channels.insert(channel_id.clone());
+                    }
+                    PackageEntry::ChannelMeta { .. } => {
+                        let mut text = String::new();
+                        content.read_to_string(&mut text)?;
+                        let channel = parser::parse_channel_json(&text).ok_or_else(|| {
+                            AppError::new("Unreadable channel metadata", "parse_error")
+                        })?;
+                        CacheManager::import_package_channel(app, &identity_id, &channel)?;
+                        if let Some(guild_id) = &channel.guild_id {
+                            guilds.insert(guild_id.clone());
+                        }
+                        channels.insert(channel.id);
+                    }
+                    PackageEntry::ServersIndex => {
+                        let mut text = String::new();
+                        content.read_to_string(&mut text)?;
+                        let servers = parser::parse_servers_index(&text);
+                        CacheManager::import_package_guilds(app, &identity_id, &servers)?;
+                        guilds.extend(servers.into_iter().map(|(id, _)| id));
+                    }
+                    PackageEntry::GuildMeta { guild_id } => {
+                        let guild: Value = serde_json::from_reader(content)?;
+                        let name = guild["name"].as_str().map(|s| s.to_string());
+                        CacheManager::import_package_guilds(
+                            app,
+                            &identity_id,
+                            &[(guild_id.clone(), name)],
+                        )?;
+                        guilds.insert(guild_id.clone());
+                    }
+                    PackageEntry::Activity { category } => {
+                        let mut batch = Vec::with_capacity(ACTIVITY_BATCH_SIZE);
+                        for line in content.lines() {
+                            if let Some(event) = parser::parse_activity_line(&line?) {
+                                batch.push(event);
+                            }
+                            if batch.len() >= ACTIVITY_BATCH_SIZE {
+                                summary.activity_events +=
+                                    ActivityLog::import_batch(app, &identity_id, category, &batch)?;
+                                batch.clear();
+                            }
+                        }
+                        summary.activity_events +=
+                            ActivityLog::import_batch(app, &identity_id, category, &batch)?;
+                        summary.activity_files += 1;
+                    }
+                    _ => {}
+                }
+                Ok(())
+            },
+            |progress| {
+                let percent = progress.percent();
+                if last_percent != Some(percent) {
+                    last_percent = Some(percent);
+                    let _ = app.emit("gdpr_progress", progress);
+                }
+            },
+        )?;
+        summary.skipped_entries = reader.skipped;
+
+        summary.channels = channels.len();
+        summary.guilds = guilds.len();
+        CacheManager::record_package_import(
+            app,
+            &identity_id,
+            zip_path,
+            summary.channels,
+            summary.messages,
+            summary.guilds,
+            summary.relationships,
+        )?;
+
+        Logger::info(
+            app,
+            &format!(
+                "[GDPR] Import complete: {} messages ({} new) in {} channels, {} guilds, {} activity events, {} skipped entries",
+                summary.messages,
+                summary.new_messages,
+                summary.channels,
+                summary.guilds,
+                summary.activity_events,
+                summary.skipped_entries.len()
+            ),
+            None,
+        );
+        Ok(summary)
+    }
+
+    /// The package owner must be a saved identity; messages are stored as that
+    /// account's own, so another person's package is never attributed to us.
+    fn resolve_identity(app: &AppHandle, account: Option<&Value>) -> Result<String, AppError> {
+        let Some(user_id) = account.and_then(|a| parser::snowflake(&a["id"])) else {
+            return Err(AppError::new(
+                "The package has no account ID, so its owner cannot be verified.",
+                "package_owner_unknown",
+            ));
+        };
+        if !Vault::list_identities(app).iter().any(|i| i.id == user_id) {
+            Logger::warn(
+                app,
+                &format!("[GDPR] Package owner {} is not a saved identity", user_id),
+                None,
+            );
+            return Err(AppError::new(
+                "This package belongs to an account that is not saved here. Add that account first.",
+                "package_owner_mismatch",
+            ));
+        }
+        Ok(user_id)
+    }
+}
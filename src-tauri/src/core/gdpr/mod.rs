@@ -0,0 +1,8 @@
+// src-tauri/src/core/gdpr/mod.rs
+
+pub mod activity;
+pub mod diff;
+pub mod harvest;
+pub mod importer;
+pub mod parser;
+pub mod reader;
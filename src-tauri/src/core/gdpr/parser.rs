@@ -0,0 +1,431 @@
+// src-tauri/src/core/gdpr/parser.rs
+
+use crate::core::error::AppError;
+use serde::de::{self, SeqAccess, Visitor};
+use serde_json::Value;
+use std::fmt;
+use std::io::BufRead;
+
+/// A message we authored, as listed in a data package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageMessage {
+    pub id: String,
+    /// Unix milliseconds; 0 when the package timestamp could not be parsed.
+    pub timestamp: i64,
+    pub content: String,
+    pub attachments: Vec<String>,
+}
+
+/// Channel metadata from a `messages/c<id>/channel.json` entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageChannel {
+    pub id: String,
+    pub channel_type: u8,
+    pub name: Option<String>,
+    pub guild_id: Option<String>,
+    pub guild_name: Option<String>,
+    pub recipients: Vec<String>,
+}
+
+/// The kind of file a package entry holds, derived from its path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageEntry {
+    MessagesCsv {
+        channel_id: String,
+    },
+    MessagesJson {
+        channel_id: String,
+    },
+    ChannelMeta {
+        channel_id: String,
+    },
+    MessagesIndex,
+    ServersIndex,
+    GuildMeta {
+        guild_id: String,
+    },
+    Account,
+    RelationshipsIndex,
+    /// JSONL event log under `activity/<category>/` (analytics, reporting, tns, modeling).
+    Activity {
+        category: String,
+    },
+    Other,
+}
+
+impl PackageEntry {
+    /// Classifies a zip entry or relative file path.
+    /// Older packages use lowercase folders, newer ones capitalize them (`Messages/`).
+    pub fn classify(path: &str) -> Self {
+        let normalized = path.replace('\\', "/").to_lowercase();
+        let parts: Vec<&str> = normalized
+            .trim_start_matches("./")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        match parts.as_slice() {
+            ["messages", "index.json"] => PackageEntry::MessagesIndex,
+            ["messages", folder, file] => {
+                let channel_id = folder.trim_start_matches('c').to_string();
+                match *file {
+                    "messages.csv" => PackageEntry::MessagesCsv { channel_id },
+                    "messages.json" => PackageEntry::MessagesJson { channel_id },
+                    "channel.json" => PackageEntry::ChannelMeta { channel_id },
+                    _ => PackageEntry::Other,
+                }
+            }
+            ["servers", "index.json"] => PackageEntry::ServersIndex,
+            ["servers", guild_id, "guild.json"] => PackageEntry::GuildMeta {
+                guild_id: guild_id.to_string(),
+            },
+            ["account", "user.json"] => PackageEntry::Account,
+            ["relationships", "index.json"] => PackageEntry::RelationshipsIndex,
+            ["activity", category, .., _] if normalized.ends_with(".json") => {
+                PackageEntry::Activity {
+                    category: category.to_string(),
+                }
+            }
+            _ => PackageEntry::Other,
+        }
+    }
+}
+
+/// A telemetry event from an `activity/` log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityEvent {
+    /// Discord's own event ID, used to avoid duplicates when a package is re-imported.
+    pub event_id: Option<String>,
+    pub event_type: String,
+    /// Unix milliseconds; 0 when the event carries no usable timestamp.
+    pub timestamp: i64,
+    pub properties: serde_json::Map<String, Value>,
+}
+
+/// Parses one JSONL line of an activity log.
+pub fn parse_activity_line(line: &str) -> Option<ActivityEvent> {
+    let Ok(Value::Object(properties)) = serde_json::from_str::<Value>(line.trim()) else {
+        return None;
+    };
+    let event_type = properties.get("event_type")?.as_str()?.to_string();
+    // Timestamps are serialized as JSON strings that themselves contain quotes.
+    let timestamp = [
+        "timestamp",
+        "client_send_timestamp",
+        "client_track_timestamp",
+    ]
+    .iter()
+    .filter_map(|key| properties.get(*key)?.as_str())
+    .map(|raw| parse_timestamp(raw.trim_matches('"')))
+    .find(|ts| *ts > 0)
+    .unwrap_or(0);
+
+    Some(ActivityEvent {
+        event_id: properties.get("event_id").and_then(snowflake),
+        event_type,
+        timestamp,
+        properties,
+    })
+}
+
+/// Parses the timestamp formats used across package generations into unix milliseconds.
+pub fn parse_timestamp(raw: &str) -> i64 {
+    let raw = raw.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S%.f%:z") {
+        return dt.timestamp_millis();
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return dt.timestamp_millis();
+    }
+    let naive = raw.trim_end_matches(" UTC");
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(naive, "%Y-%m-%d %H:%M:%S%.f") {
+        return dt.and_utc().timestamp_millis();
+    }
+    0
+}
+
+/// Streams RFC 4180 CSV records from a reader, honouring quoted fields that contain
+/// separators, escaped quotes or newlines. Only one record is held in memory at a time.
+pub struct CsvRecords<R> {
+    reader: R,
+    line: String,
+}
+
+impl<R: BufRead> CsvRecords<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CsvRecords<R> {
+    type Item = std::io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut read_any = false;
+
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) if !read_any => return None,
+                Ok(0) => {
+                    record.push(field);
+                    return Some(Ok(record));
+                }
+                Ok(_) => read_any = true,
+                Err(e) => return Some(Err(e)),
+            }
+
+            let mut chars = self.line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    match c {
+                        '"' if chars.peek() == Some(&'"') => {
+                            field.push('"');
+                            chars.next();
+                        }
+                        '"' => in_quotes = false,
+                        _ => field.push(c),
+                    }
+                    continue;
+                }
+
+                match c {
+                    '"' => in_quotes = true,
+                    ',' => record.push(std::mem::take(&mut field)),
+                    '\r' => {}
+                    '\n' => {
+                        record.push(field);
+                        return Some(Ok(record));
+                    }
+                    _ => field.push(c),
+                }
+            }
+        }
+    }
+}
+
+fn split_attachments(raw: &str) -> Vec<String> {
+    raw.split_whitespace()
+        .filter(|s| s.starts_with("http"))
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Reads a snowflake that may be serialized as either a string or a number.
+pub fn snowflake(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Streams the legacy `ID,Timestamp,Contents,Attachments` CSV format from a reader,
+/// handing each message to `visit`; fails on unreadable (e.g. non UTF-8) data.
+pub fn read_messages_csv<R, F>(reader: R, mut visit: F) -> Result<(), AppError>
+where
+    R: BufRead,
+    F: FnMut(PackageMessage) -> Result<(), AppError>,
+{
+    let mut records = CsvRecords::new(reader);
+    let header: Vec<String> = match records.next() {
+        Some(h) => h?.iter().map(|c| c.trim().to_lowercase()).collect(),
+        None => return Ok(()),
+    };
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(id_col), Some(ts_col)) = (column("id"), column("timestamp")) else {
+        return Ok(());
+    };
+    let content_col = column("contents");
+    let attachments_col = column("attachments");
+
+    for row in records {
+        let row = row?;
+        let Some(id) = row.get(id_col).map(|s| s.trim().to_string()) else {
+            continue;
+        };
+        if id.is_empty() {
+            continue;
+        }
+        visit(PackageMessage {
+            id,
+            timestamp: parse_timestamp(row.get(ts_col).map(|s| s.as_str()).unwrap_or("")),
+            content: content_col
+                .and_then(|c| row.get(c))
+                .cloned()
+                .unwrap_or_default(),
+            attachments: attachments_col
+                .and_then(|c| row.get(c))
+                .map(String::as_str)
+                .map(split_attachments)
+                .unwrap_or_default(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Parses a single message object from the newer JSON format.
+pub fn parse_message_value(value: &Value) -> Option<PackageMessage> {
+    let id = snowflake(&value["ID"]).or_else(|| snowflake(&value["id"]))?;
+    let timestamp = value["Timestamp"]
+        .as_str()
+        .or_else(|| value["timestamp"].as_str())
+        .map(parse_timestamp)
+        .unwrap_or(0);
+    let content = value["Contents"]
+        .as_str()
+        .or_else(|| value["content"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    let attachments = value["Attachments"]
+        .as_str()
+        .map(split_attachments)
+        .unwrap_or_default();
+
+    Some(PackageMessage {
+        id,
+        timestamp,
+        content,
+        attachments,
+    })
+}
+
+/// Streams the newer `messages.json` format, an array of
+/// `{ID, Timestamp, Contents, Attachments}`, handing each message to `visit` as it is read.
+pub fn read_messages_json<R, F>(reader: R, visit: F) -> Result<(), AppError>
+where
+    R: BufRead,
+    F: FnMut(PackageMessage) -> Result<(), AppError>,
+{
+    let mut seed = MessageSeq {
+        visit,
+        failure: None,
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let parsed = de::Deserializer::deserialize_seq(&mut deserializer, &mut seed)
+        .and_then(|()| deserializer.end());
+    match seed.failure {
+        Some(e) => Err(e),
+        None => Ok(parsed?),
+    }
+}
+
+/// Visits a message array one element at a time. A failing callback stops the walk and
+/// keeps its own error, which `read_messages_json` returns instead of the parse error.
+struct MessageSeq<F> {
+    visit: F,
+    failure: Option<AppError>,
+}
+
+impl<'de, F> Visitor<'de> for &mut MessageSeq<F>
+where
+    F: FnMut(PackageMessage) -> Result<(), AppError>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of messages")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(value) = seq.next_element::<Value>()? {
+            let Some(message) = parse_message_value(&value) else {
+                continue;
+            };
+            if let Err(e) = (self.visit)(message) {
+                self.failure = Some(e);
+                return Err(de::Error::custom("message visitor failed"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maps both numeric and named (`"DM"`, `"GUILD_TEXT"`, ...) channel types.
+fn channel_type(value: &Value) -> u8 {
+    if let Some(n) = value.as_u64() {
+        return n as u8;
+    }
+    match value.as_str().unwrap_or_default() {
+        "DM" => 1,
+        "GUILD_VOICE" => 2,
+        "GROUP_DM" => 3,
+        "GUILD_ANNOUNCEMENT" | "GUILD_NEWS" => 5,
+        "ANNOUNCEMENT_THREAD" | "GUILD_NEWS_THREAD" => 10,
+        "PUBLIC_THREAD" | "GUILD_PUBLIC_THREAD" => 11,
+        "PRIVATE_THREAD" | "GUILD_PRIVATE_THREAD" => 12,
+        "GUILD_STAGE_VOICE" => 13,
+        "GUILD_FORUM" => 15,
+        _ => 0,
+    }
+}
+
+pub fn parse_channel_json(input: &str) -> Option<PackageChannel> {
+    let json: Value = serde_json::from_str(input).ok()?;
+    let recipients = json["recipients"]
+        .as_array()
+        .map(|r| {
+            r.iter()
+                .filter_map(|u| snowflake(u).or_else(|| snowflake(&u["id"])))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(PackageChannel {
+        id: snowflake(&json["id"])?,
+        channel_type: channel_type(&json["type"]),
+        name: json["name"].as_str().map(|s| s.to_string()),
+        guild_id: snowflake(&json["guild"]["id"]),
+        guild_name: json["guild"]["name"].as_str().map(|s| s.to_string()),
+        recipients,
+    })
+}
+
+/// `servers/index.json` is an `{id: name}` map in current packages and an array of
+/// `{id, name}` objects in some older ones.
+pub fn parse_servers_index(input: &str) -> Vec<(String, Option<String>)> {
+    match serde_json::from_str::<Value>(input) {
+        Ok(Value::Object(map)) => map
+            .into_iter()
+            .map(|(id, name)| (id, name.as_str().map(|s| s.to_string())))
+            .collect(),
+        Ok(Value::Array(arr)) => arr
+            .iter()
+            .filter_map(|s| {
+                Some((
+                    snowflake(&s["id"])?,
+                    s["name"].as_str().map(|n| n.to_string()),
+                ))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `messages/index.json` maps channel IDs to a human readable description.
+pub fn parse_messages_index(input: &str) -> Vec<(String, Option<String>)> {
+    match serde_json::from_str::<Value>(input) {
+        Ok(Value::Object(map)) => map
+            .into_iter()
+            .map(|(id, name)| (id, name.as_str().map(|s| s.to_string())))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Extracts the attachment ID and file name from a CDN URL
+/// (`/attachments/<channel>/<attachment>/<filename>`).
+pub fn attachment_from_url(url: &str) -> Option<(String, String)> {
+    let parsed = url::Url::parse(url).ok()?;
+    let segments: Vec<&str> = parsed.path_segments()?.collect();
+    let pos = segments.iter().position(|s| *s == "attachments")?;
+    let id = segments.get(pos + 2)?.to_string();
+    let filename = segments.get(pos + 3)?.to_string();
+    Some((id, filename))
+}
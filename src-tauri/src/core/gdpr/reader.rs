@@ -0,0 +1,220 @@
+// src-tauri/src/core/gdpr/reader.rs
+
+use crate::core::error::AppError;
+use crate::core::gdpr::parser::PackageEntry;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+/// A single file inside a data package.
+#[derive(Debug, Clone)]
+pub struct PackageFile {
+    /// Path relative to the package root, always `/`-separated.
+    pub path: String,
+    pub kind: PackageEntry,
+    /// Uncompressed size in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageProgress {
+    pub entries_done: usize,
+    pub entries_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub current: String,
+}
+
+impl PackageProgress {
+    pub fn percent(&self) -> u8 {
+        if self.bytes_total == 0 {
+            return 100;
+        }
+        ((self.bytes_done.min(self.bytes_total) * 100) / self.bytes_total) as u8
+    }
+}
+
+enum PackageSource {
+    Zip(ZipArchive<BufReader<File>>),
+    Directory(PathBuf, Vec<PackageFile>),
+}
+
+/// Reads a data package either from the original zip or from an extracted folder.
+/// Entries are streamed one at a time; unreadable entries are recorded and skipped.
+pub struct PackageReader {
+    source: PackageSource,
+    pub skipped: Vec<String>,
+}
+
+/// Errors that only affect the current entry (corrupt data, bad encoding, malformed JSON).
+fn is_entry_error(e: &AppError) -> bool {
+    matches!(
+        e.error_code.as_str(),
+        "io_error" | "zip_error" | "utf8_error" | "parse_error"
+    )
+}
+
+impl PackageReader {
+    pub fn open(path: &str) -> Result<Self, AppError> {
+        let root = Path::new(path);
+        let source = if root.is_dir() {
+            PackageSource::Directory(root.to_path_buf(), Self::collect_files(root))
+        } else {
+            let file = File::open(root).map_err(|e| {
+                AppError::new(&format!("Failed to open data package: {}", e), "io_error")
+            })?;
+            let archive = ZipArchive::new(BufReader::new(file))
+                .map_err(|e| AppError::new(&format!("Invalid zip archive: {}", e), "zip_error"))?;
+            PackageSource::Zip(archive)
+        };
+        Ok(Self {
+            source,
+            skipped: Vec::new(),
+        })
+    }
+
+    fn collect_files(root: &Path) -> Vec<PackageFile> {
+        let mut files: Vec<PackageFile> = WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|entry| {
+                let relative = entry
+                    .path()
+                    .strip_prefix(root)
+                    .ok()?
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                Some(PackageFile {
+                    kind: PackageEntry::classify(&relative),
+                    size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    path: relative,
+                })
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    fn len(&self) -> usize {
+        match &self.source {
+            PackageSource::Zip(archive) => archive.len(),
+            PackageSource::Directory(_, files) => files.len(),
+        }
+    }
+
+    /// Uncompressed size of every entry, read from the zip central directory.
+    pub fn total_bytes(&mut self) -> u64 {
+        match &mut self.source {
+            PackageSource::Zip(archive) => (0..archive.len())
+                .filter_map(|i| archive.by_index_raw(i).ok().map(|f| f.size()))
+                .sum(),
+            PackageSource::Directory(_, files) => files.iter().map(|f| f.size).sum(),
+        }
+    }
+
+    /// Reads the first entry of the given kind completely, e.g. `account/user.json`.
+    pub fn read_first(&mut self, kind: &PackageEntry) -> Option<String> {
+        let mut content = String::new();
+        match &mut self.source {
+            PackageSource::Zip(archive) => {
+                let index = (0..archive.len()).find(|i| {
+                    archive
+                        .name_for_index(*i)
+                        .is_some_and(|n| &PackageEntry::classify(n) == kind)
+                })?;
+                archive
+                    .by_index(index)
+                    .ok()?
+                    .read_to_string(&mut content)
+                    .ok()?;
+            }
+            PackageSource::Directory(root, files) => {
+                let file = files.iter().find(|f| &f.kind == kind)?;
+                File::open(root.join(&file.path))
+                    .ok()?
+                    .read_to_string(&mut content)
+                    .ok()?;
+            }
+        }
+        Some(content)
+    }
+
+    /// Streams every recognized entry to `visit`. Entry-level failures are recorded in
+    /// `skipped`; any other error (e.g. a cache write failing) aborts the walk.
+    pub fn for_each<F, P>(&mut self, mut visit: F, mut progress: P) -> Result<(), AppError>
+    where
+        F: FnMut(&PackageFile, &mut dyn BufRead) -> Result<(), AppError>,
+        P: FnMut(&PackageProgress),
+    {
+        let entries_total = self.len();
+        let bytes_total = self.total_bytes();
+        let mut state = PackageProgress {
+            entries_done: 0,
+            entries_total,
+            bytes_done: 0,
+            bytes_total,
+            current: String::new(),
+        };
+
+        for i in 0..entries_total {
+            let result = match &mut self.source {
+                PackageSource::Zip(archive) => match archive.by_index(i) {
+                    Ok(mut entry) if !entry.is_dir() => {
+                        let file = PackageFile {
+                            path: entry.name().to_string(),
+                            kind: PackageEntry::classify(entry.name()),
+                            size: entry.size(),
+                        };
+                        let outcome = Self::visit_entry(&file, &mut entry, &mut visit);
+                        Some((file, outcome))
+                    }
+                    Ok(_) => None,
+                    Err(e) => {
+                        self.skipped.push(format!("#{}: {}", i, e));
+                        None
+                    }
+                },
+                PackageSource::Directory(root, files) => {
+                    let file = files[i].clone();
+                    let outcome = match File::open(root.join(&file.path)) {
+                        Ok(mut handle) => Self::visit_entry(&file, &mut handle, &mut visit),
+                        Err(e) => Err(AppError::from(e)),
+                    };
+                    Some((file, outcome))
+                }
+            };
+
+            state.entries_done = i + 1;
+            if let Some((file, outcome)) = result {
+                state.bytes_done += file.size;
+                match outcome {
+                    Err(e) if is_entry_error(&e) => self.skipped.push(format!(
+                        "{}: {}",
+                        file.path,
+                        e.technical_details.unwrap_or(e.user_message)
+                    )),
+                    Err(e) => return Err(e),
+                    Ok(()) => {}
+                }
+                state.current = file.path;
+                progress(&state);
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_entry<F>(file: &PackageFile, raw: &mut dyn Read, visit: &mut F) -> Result<(), AppError>
+    where
+        F: FnMut(&PackageFile, &mut dyn BufRead) -> Result<(), AppError>,
+    {
+        if file.kind == PackageEntry::Other {
+            return Ok(());
+        }
+        let mut reader = BufReader::new(raw);
+        visit(file, &mut reader)
+    }
+}
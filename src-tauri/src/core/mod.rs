@@ -5,6 +5,7 @@ pub mod cleanup;
 pub mod crypto;
 pub mod error;
 pub mod forensics;
+pub mod gdpr;
 pub mod logger;
 pub mod op_manager;
 pub mod protobuf;
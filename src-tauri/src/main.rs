@@ -175,9 +175,15 @@ fn main() {
             api::discord::fetch_relationships,
             api::discord::fetch_preview_messages,
             api::discord::get_digital_footprint,
+            api::discord::compute_exposure_report,
             api::discord::search_local_cache,
+            api::discord::import_local_archive,
+            api::discord::wipe_identity_cache,
             api::discord::start_deep_scan,
             api::discord::bulk_delete_messages,
+            api::discord::bulk_remove_reactions,
+            api::discord::purge_package_messages,
+            api::discord::plan_dm_reopen,
             api::discord::bulk_leave_guilds,
             api::discord::bulk_cleanup_relationships,
             api::discord::stealth_privacy_wipe,
@@ -196,10 +202,23 @@ fn main() {
             api::discord::tools::sanitize_media_metadata,
             api::discord::tools::start_burner_protocol,
             api::discord::tools::scan_for_pii,
+            api::discord::tools::analyze_identity_correlation,
             api::discord::tools::start_forensic_export,
+            api::discord::tools::verify_forensic_export,
+            api::discord::tools::create_encrypted_backup,
+            api::discord::tools::decrypt_export_container,
+            api::discord::tools::verify_export_container,
             api::discord::trigger_data_harvest,
             api::discord::get_harvest_status,
+            api::discord::list_harvest_requests,
+            api::discord::get_harvest_settings,
+            api::discord::set_harvest_watch_dir,
             api::discord::process_gdpr_data,
+            api::discord::import_gdpr_package,
+            api::discord::diff_gdpr_packages,
+            api::discord::diff_gdpr_package_with_cache,
+            api::discord::get_activity_summary,
+            api::discord::list_activity_events,
             api::discord::fetch_oauth_tokens,
             api::discord::revoke_oauth_token,
             api::discord::fetch_sessions,
@@ -215,6 +234,7 @@ fn main() {
             api::discord::start_attachment_harvest,
             api::discord::start_chat_export,
             api::discord::start_guild_user_archive,
+            api::discord::start_guild_archive,
             api::rate_limiter::set_proxy,
             clear_all_app_data
         ])
@@ -0,0 +1,34 @@
+// src-tauri/src/tests/analytics_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::forensics::analytics::FootprintAnalytics;
+
+    #[test]
+    fn test_attachment_category_prefers_mime_type() {
+        assert_eq!(
+            FootprintAnalytics::attachment_category(Some("image/png"), "file.bin"),
+            "image"
+        );
+        assert_eq!(
+            FootprintAnalytics::attachment_category(Some("video/mp4"), "clip"),
+            "video"
+        );
+    }
+
+    #[test]
+    fn test_attachment_category_falls_back_to_extension() {
+        assert_eq!(
+            FootprintAnalytics::attachment_category(None, "Report.PDF"),
+            "document"
+        );
+        assert_eq!(
+            FootprintAnalytics::attachment_category(Some("application/octet-stream"), "a.zip"),
+            "archive"
+        );
+        assert_eq!(
+            FootprintAnalytics::attachment_category(None, "noextension"),
+            "other"
+        );
+    }
+}
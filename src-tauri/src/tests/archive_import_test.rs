@@ -0,0 +1,93 @@
+// src-tauri/src/tests/archive_import_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::cache::archive::{
+        channel_from_file_name, normalize_message, parse_json_document, parse_jsonl,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_normalizes_discord_and_flattened_shapes() {
+        let raw = json!({
+            "id": "10",
+            "channel_id": "1",
+            "author": { "id": "42" },
+            "content": "hello",
+            "timestamp": "2024-01-01T00:00:00+00:00",
+            "attachments": [{ "id": "a1", "filename": "x.png" }]
+        });
+        let msg = normalize_message(&raw, None).unwrap();
+        assert_eq!(msg.author_id, "42");
+        assert_eq!(msg.timestamp, 1_704_067_200_000);
+        assert_eq!(msg.attachments.len(), 1);
+
+        // Backup rows carry `author_id` and millisecond timestamps.
+        let flat = json!({ "id": "11", "author_id": "42", "content": "hi", "timestamp": 5 });
+        let msg = normalize_message(&flat, Some("2")).unwrap();
+        assert_eq!((msg.channel_id.as_str(), msg.timestamp), ("2", 5));
+
+        // Channel lists, manifests and members have no timestamp.
+        assert!(normalize_message(&json!({ "id": "1", "name": "general" }), Some("1")).is_none());
+        assert!(normalize_message(&json!({ "timestamp": 1, "content": "x" }), Some("1")).is_none());
+    }
+
+    #[test]
+    fn test_parses_export_documents() {
+        let forensic = json!({
+            "integrity": { "hash": "abc" },
+            "data": { "content": { "messages": [
+                { "id": "1", "channel_id": "9", "author_id": "42", "content": "a", "timestamp": 1 }
+            ] } }
+        });
+        assert_eq!(parse_json_document(&forensic, None).len(), 1);
+
+        let json_ld = json!({
+            "@graph": [
+                { "@id": "discord:channel:9", "@type": "Conversation", "identifier": "9" },
+                {
+                    "@id": "discord:message:1", "@type": "Message", "identifier": "1",
+                    "isPartOf": { "@id": "discord:channel:9" },
+                    "sender": { "@id": "discord:user:42" },
+                    "text": "a", "dateSent": "2024-01-01T00:00:00+00:00",
+                    "messageAttachment": [{ "@type": "MediaObject", "identifier": "5", "name": "a.png" }]
+                }
+            ]
+        });
+        let messages = parse_json_document(&json_ld, None);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            (
+                messages[0].channel_id.as_str(),
+                messages[0].author_id.as_str()
+            ),
+            ("9", "42")
+        );
+        assert_eq!(messages[0].attachments[0]["filename"], "a.png");
+
+        let array = json!([
+            { "id": "1", "content": "a", "timestamp": 1 },
+            { "id": "2", "content": "b", "timestamp": 2 }
+        ]);
+        let messages = parse_json_document(&array, Some("7"));
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|m| m.channel_id == "7"));
+
+        let jsonl = "{\"id\":\"1\",\"content\":\"a\",\"timestamp\":1}\nnot json\n\n{\"id\":\"2\",\"timestamp\":2}\n";
+        assert_eq!(parse_jsonl(jsonl.as_bytes(), Some("7")).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_channel_from_file_name() {
+        assert_eq!(channel_from_file_name("123.jsonl").as_deref(), Some("123"));
+        assert_eq!(
+            channel_from_file_name("channels/123_pins.json").as_deref(),
+            Some("123")
+        );
+        assert_eq!(
+            channel_from_file_name("/tmp/x/123_page2.html").as_deref(),
+            Some("123")
+        );
+        assert_eq!(channel_from_file_name("manifest.json"), None);
+    }
+}
@@ -0,0 +1,135 @@
+// src-tauri/src/tests/attachment_store_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::export::attachments::{
+        AttachmentStore, StoredAttachment, sanitize_filename,
+    };
+    use crate::api::discord::export::cdn::{DownloadJob, signed_url_expired};
+    use serde_json::{Value, json};
+    use sha2::{Digest, Sha256};
+
+    fn temp_root(name: &str) -> std::path::PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("attachment_store_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        root
+    }
+
+    /// Stands in for a finished download: writes the partial file, then commits it.
+    fn put(
+        store: &mut AttachmentStore,
+        channel_id: &str,
+        message_id: &str,
+        attachment: &Value,
+        bytes: &[u8],
+    ) -> StoredAttachment {
+        let part = store.partial_path(attachment["id"].as_str().unwrap());
+        std::fs::create_dir_all(part.parent().unwrap()).unwrap();
+        std::fs::write(&part, bytes).unwrap();
+        let sha256 = hex::encode(Sha256::digest(bytes));
+        let entry = store
+            .commit(
+                channel_id,
+                message_id,
+                attachment,
+                bytes.len() as u64,
+                &sha256,
+            )
+            .unwrap();
+        assert!(!part.exists());
+        entry
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("image.png"), "image.png");
+        assert_eq!(sanitize_filename("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize_filename("..."), "file");
+    }
+
+    #[test]
+    fn test_store_layout_and_dedupe() {
+        let root = temp_root("dedupe");
+        let mut store = AttachmentStore::open(&root).unwrap();
+
+        let first = json!({ "id": "a1", "filename": "image.png", "content_type": "image/png" });
+        let second = json!({ "id": "a2", "filename": "image.png", "content_type": "image/png" });
+        let other = json!({ "id": "a3", "filename": "image.png" });
+
+        let a = put(&mut store, "c1", "m1", &first, b"same bytes");
+        let b = put(&mut store, "c2", "m2", &second, b"same bytes");
+        let c = put(&mut store, "c1", "m3", &other, b"different");
+
+        assert_eq!(a.path, "c1/m1/a1-image.png");
+        assert_eq!(b.path, a.path);
+        assert_eq!(c.path, "c1/m3/a3-image.png");
+        assert!(!root.join("c2/m2").exists());
+
+        store.save_manifest().unwrap();
+        store.write_index_pages().unwrap();
+        assert!(root.join("index.html").is_file());
+        assert!(root.join("c2/index.html").is_file());
+
+        // Re-opening keeps deduplicating against the manifest.
+        let reopened = AttachmentStore::open(&root).unwrap();
+        assert_eq!(reopened.get("a2").unwrap().path, "c1/m1/a1-image.png");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_leftover_files_are_adopted_or_refetched() {
+        let root = temp_root("resume");
+        let mut store = AttachmentStore::open(&root).unwrap();
+        let complete = json!({ "id": "a1", "filename": "done.bin", "size": 5 });
+        let truncated = json!({ "id": "a2", "filename": "cut.bin", "size": 10 });
+
+        std::fs::create_dir_all(root.join("c1/m1")).unwrap();
+        std::fs::write(root.join("c1/m1/a1-done.bin"), b"hello").unwrap();
+        std::fs::write(root.join("c1/m1/a2-cut.bin"), b"hel").unwrap();
+
+        let adopted = store.find_existing("c1", "m1", &complete).unwrap().unwrap();
+        assert_eq!(adopted.sha256, hex::encode(Sha256::digest(b"hello")));
+        assert!(
+            store
+                .find_existing("c1", "m1", &truncated)
+                .unwrap()
+                .is_none()
+        );
+
+        // A manifest entry whose file shrank is dropped on reopen.
+        store.save_manifest().unwrap();
+        std::fs::write(root.join("c1/m1/a1-done.bin"), b"he").unwrap();
+        assert!(AttachmentStore::open(&root).unwrap().get("a1").is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_signed_url_expiry() {
+        let url = "https://cdn.discordapp.com/attachments/1/2/a.png?ex=65f0a000&is=65ef4e80&hm=abc";
+        let expires = 0x65f0a000;
+        assert!(!signed_url_expired(url, expires - 1));
+        assert!(signed_url_expired(url, expires));
+        assert!(!signed_url_expired(
+            "https://cdn.discordapp.com/attachments/1/2/a.png",
+            expires
+        ));
+    }
+
+    #[test]
+    fn test_jobs_from_message() {
+        let msg = json!({
+            "id": "m1",
+            "attachments": [
+                { "id": "a1", "filename": "a.png", "url": "https://cdn.discordapp.com/a.png" },
+                { "id": "a2", "filename": "b.png" }
+            ]
+        });
+        let jobs = DownloadJob::from_message("c1", &msg);
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].message_id, "m1");
+        assert_eq!(jobs[0].attachment["id"], "a1");
+    }
+}
@@ -0,0 +1,180 @@
+// src-tauri/src/tests/cache_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::cache::CacheManager;
+    use crate::core::cache::schema::{SCHEMA, SCHEMA_VERSION};
+    use crate::core::gdpr::activity::ActivityLog;
+    use rusqlite::Connection;
+
+    const LEGACY_MESSAGES: &str = "
+        CREATE TABLE messages (
+            id TEXT PRIMARY KEY,
+            identity_id TEXT NOT NULL,
+            channel_id TEXT NOT NULL,
+            author_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            has_attachments BOOLEAN NOT NULL,
+            is_deleted BOOLEAN DEFAULT 0
+        );
+        INSERT INTO messages (id, identity_id, channel_id, author_id, content, timestamp, has_attachments)
+            VALUES ('1', 'alice', 'c1', 'alice', 'x', 0, 0);
+    ";
+
+    fn pk_columns(conn: &Connection) -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('messages') WHERE pk > 0",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fresh_cache_uses_composite_message_key() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        CacheManager::run_migrations(&conn).unwrap();
+
+        assert_eq!(pk_columns(&conn), 2);
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_legacy_message_key_is_migrated() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(LEGACY_MESSAGES).unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        CacheManager::run_migrations(&conn).unwrap();
+
+        assert_eq!(pk_columns(&conn), 2);
+
+        // The same message can now be indexed by a second identity.
+        conn.execute(
+            "INSERT INTO messages (id, identity_id, channel_id, author_id, content, timestamp, has_attachments)
+                VALUES ('1', 'bob', 'c1', 'alice', 'x', 0, 0)",
+            [],
+        )
+        .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM messages WHERE id = '1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // Existing rows are tagged as live data by the v2 migration.
+        let source: String = conn
+            .query_row(
+                "SELECT source FROM messages WHERE id = '1' AND identity_id = 'alice'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(source, "live");
+    }
+
+    #[test]
+    fn test_plaintext_observations_are_dropped() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE activity_observations (
+                identity_id TEXT NOT NULL,
+                field TEXT NOT NULL,
+                value TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                first_seen INTEGER NOT NULL,
+                last_seen INTEGER NOT NULL,
+                PRIMARY KEY (identity_id, field, value)
+            );
+            INSERT INTO activity_observations VALUES ('alice', 'ip', '203.0.113.7', 1, 0, 0);",
+        )
+        .unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        CacheManager::run_migrations(&conn).unwrap();
+
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM activity_observations", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        let has_hash: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('activity_observations') WHERE name = 'value_hash'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(rows, 0);
+        assert_eq!(has_hash, 1);
+    }
+
+    #[test]
+    fn test_observation_hash_is_keyed() {
+        let key = ActivityLog::observation_key("key");
+        assert_ne!(key, ActivityLog::observation_key("other"));
+        assert_ne!(&key[..], "key".as_bytes());
+
+        let hash = ActivityLog::observation_hash(&key, "ip", "203.0.113.7");
+        assert_eq!(
+            hash,
+            ActivityLog::observation_hash(&key, "ip", "203.0.113.7")
+        );
+        assert_ne!(
+            hash,
+            ActivityLog::observation_hash(
+                &ActivityLog::observation_key("other"),
+                "ip",
+                "203.0.113.7"
+            )
+        );
+        assert_ne!(
+            hash,
+            ActivityLog::observation_hash(&key, "city", "203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn test_legacy_membership_rows_count_as_live() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE guilds (
+                id TEXT,
+                identity_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                icon TEXT,
+                owner BOOLEAN NOT NULL,
+                last_synced INTEGER NOT NULL,
+                PRIMARY KEY (id, identity_id)
+            );
+            CREATE TABLE relationships (
+                user_id TEXT NOT NULL,
+                identity_id TEXT NOT NULL,
+                username TEXT,
+                type INTEGER NOT NULL,
+                last_synced INTEGER NOT NULL,
+                PRIMARY KEY (user_id, identity_id)
+            );
+            INSERT INTO guilds VALUES ('g1', 'alice', 'Home', NULL, 0, 0);",
+        )
+        .unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        CacheManager::run_migrations(&conn).unwrap();
+
+        let source: String = conn
+            .query_row("SELECT source FROM guilds WHERE id = 'g1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(source, "live");
+        conn.execute(
+            "INSERT INTO relationships (user_id, identity_id, type, last_synced, source) VALUES ('u1', 'alice', 1, 0, 'package')",
+            [],
+        )
+        .unwrap();
+    }
+}
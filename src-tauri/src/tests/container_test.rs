@@ -0,0 +1,110 @@
+// src-tauri/src/tests/container_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::forensics::container::{ContainerWriter, open_container};
+    use sha2::{Digest, Sha256};
+    use std::io::Write;
+
+    fn seal(payload: &[u8], chunk_size: usize) -> Vec<u8> {
+        let mut writer =
+            ContainerWriter::with_chunk_size(Vec::new(), "correct horse", "export.zip", chunk_size)
+                .unwrap();
+        // Odd-sized writes so chunk boundaries fall inside a write.
+        for piece in payload.chunks(7) {
+            writer.write_all(piece).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_across_chunks() {
+        let payload: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let sealed = seal(&payload, 64);
+
+        let mut out = Vec::new();
+        let info = open_container(&mut sealed.as_slice(), "correct horse", &mut out).unwrap();
+        assert_eq!(out, payload);
+        assert_eq!(info.content_name, "export.zip");
+        assert_eq!(info.size, 1000);
+        assert_eq!(info.chunks, 16);
+        assert_eq!(info.sha256, hex::encode(Sha256::digest(&payload)));
+    }
+
+    #[test]
+    fn test_empty_payload() {
+        let sealed = seal(b"", 64);
+        let info = open_container(
+            &mut sealed.as_slice(),
+            "correct horse",
+            &mut std::io::sink(),
+        )
+        .unwrap();
+        assert_eq!(info.size, 0);
+        assert_eq!(info.chunks, 1);
+    }
+
+    #[test]
+    fn test_rejects_wrong_passphrase_and_tampering() {
+        let payload = vec![42u8; 300];
+        let sealed = seal(&payload, 64);
+
+        let err =
+            open_container(&mut sealed.as_slice(), "wrong", &mut std::io::sink()).unwrap_err();
+        assert_eq!(err.error_code, "container_auth_failed");
+
+        let mut flipped = sealed.clone();
+        let last = flipped.len() - 1;
+        flipped[last] ^= 1;
+        let err = open_container(
+            &mut flipped.as_slice(),
+            "correct horse",
+            &mut std::io::sink(),
+        )
+        .unwrap_err();
+        assert_eq!(err.error_code, "container_auth_failed");
+
+        // Dropping the final chunk must not pass as a shorter valid file.
+        let final_chunk = 4 + (300 % 64) + 16;
+        let truncated = &sealed[..sealed.len() - final_chunk];
+        let err =
+            open_container(&mut &truncated[..], "correct horse", &mut std::io::sink()).unwrap_err();
+        assert_eq!(err.error_code, "container_invalid");
+
+        let mut extended = sealed.clone();
+        extended.push(0);
+        assert!(
+            open_container(
+                &mut extended.as_slice(),
+                "correct horse",
+                &mut std::io::sink()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rejects_foreign_files() {
+        let err = open_container(
+            &mut &b"PK\x03\x04 not a container"[..],
+            "x",
+            &mut std::io::sink(),
+        )
+        .unwrap_err();
+        assert_eq!(err.error_code, "container_invalid");
+    }
+
+    #[test]
+    fn test_rejects_oversized_chunk_size() {
+        let mut sealed = seal(b"payload", 64);
+        // Magic, version, salt length and a 32 character hex salt precede the chunk size.
+        sealed[42..46].copy_from_slice(&u32::MAX.to_be_bytes());
+        let err = open_container(
+            &mut sealed.as_slice(),
+            "correct horse",
+            &mut std::io::sink(),
+        )
+        .unwrap_err();
+        assert_eq!(err.error_code, "container_invalid");
+    }
+}
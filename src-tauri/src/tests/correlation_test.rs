@@ -0,0 +1,102 @@
+// src-tauri/src/tests/correlation_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::forensics::correlation::{IdentityCorrelator, LinkKind};
+    use std::collections::{BTreeSet, HashMap, HashSet};
+
+    fn identities() -> HashSet<String> {
+        ["alice", "bob", "carol"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_shared_links_require_two_of_our_identities() {
+        let mut membership = HashMap::new();
+        membership.insert(
+            "guild_shared".to_string(),
+            (
+                Some("Shared".to_string()),
+                BTreeSet::from(["alice".to_string(), "bob".to_string()]),
+            ),
+        );
+        membership.insert(
+            "guild_solo".to_string(),
+            (None, BTreeSet::from(["alice".to_string()])),
+        );
+
+        let links =
+            IdentityCorrelator::shared_links(LinkKind::MutualGuild, &membership, &identities());
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].subject_id, "guild_shared");
+        assert_eq!(links[0].identities, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_hour_similarity() {
+        let mut night = [0u64; 24];
+        night[2] = 10;
+        night[3] = 5;
+        let mut day = [0u64; 24];
+        day[14] = 10;
+
+        assert!((IdentityCorrelator::hour_similarity(&night, &night) - 1.0).abs() < 1e-9);
+        assert_eq!(IdentityCorrelator::hour_similarity(&night, &day), 0.0);
+        assert_eq!(IdentityCorrelator::hour_similarity(&night, &[0; 24]), 0.0);
+    }
+
+    #[test]
+    fn test_pairs_ranked_by_link_strength() {
+        let mut guilds = HashMap::new();
+        guilds.insert(
+            "g1".to_string(),
+            (
+                None,
+                BTreeSet::from(["alice".to_string(), "carol".to_string()]),
+            ),
+        );
+        let mut connections = HashMap::new();
+        connections.insert(
+            "steam:42".to_string(),
+            (
+                None,
+                BTreeSet::from(["alice".to_string(), "bob".to_string()]),
+            ),
+        );
+
+        let mut links =
+            IdentityCorrelator::shared_links(LinkKind::MutualGuild, &guilds, &identities());
+        links.extend(IdentityCorrelator::shared_links(
+            LinkKind::SharedConnection,
+            &connections,
+            &identities(),
+        ));
+
+        let pairs = IdentityCorrelator::score_pairs(&links);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].identity_a, "alice");
+        assert_eq!(pairs[0].identity_b, "bob");
+    }
+
+    #[test]
+    fn test_third_identity_does_not_raise_pair_score() {
+        let mut guilds = HashMap::new();
+        guilds.insert(
+            "g_all".to_string(),
+            (
+                None,
+                BTreeSet::from(["alice".to_string(), "bob".to_string(), "carol".to_string()]),
+            ),
+        );
+        let links = IdentityCorrelator::shared_links(LinkKind::MutualGuild, &guilds, &identities());
+        let pairs = IdentityCorrelator::score_pairs(&links);
+
+        // Sharing the node with carol leaves the alice-bob relationship unchanged.
+        assert_eq!(pairs.len(), 3);
+        for pair in pairs {
+            assert!((pair.score - LinkKind::MutualGuild.weight()).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// src-tauri/src/tests/discord_routes_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord_routes::bucket_key;
+    use reqwest::Method;
+
+    #[test]
+    fn test_message_buckets_split_by_method_not_message() {
+        let first = "https://discord.com/api/v9/channels/1/messages/10";
+        let second = "https://discord.com/api/v9/channels/1/messages/11";
+
+        assert_eq!(
+            bucket_key(&Method::DELETE, first),
+            bucket_key(&Method::DELETE, second)
+        );
+        assert_eq!(
+            bucket_key(&Method::PATCH, first),
+            "PATCH channels/1/messages/:id"
+        );
+        assert_ne!(
+            bucket_key(&Method::PATCH, first),
+            bucket_key(&Method::DELETE, first)
+        );
+        assert_ne!(
+            bucket_key(&Method::DELETE, first),
+            bucket_key(
+                &Method::DELETE,
+                "https://discord.com/api/v9/channels/2/messages/10"
+            )
+        );
+        assert_eq!(
+            bucket_key(
+                &Method::DELETE,
+                "https://discord.com/api/v9/channels/1/messages/10/reactions/%F0%9F%91%8D/@me"
+            ),
+            "DELETE channels/1/messages/:id/reactions"
+        );
+        assert_eq!(
+            bucket_key(
+                &Method::POST,
+                "https://discord.com/api/v9/channels/1/messages/bulk-delete"
+            ),
+            "POST channels/1/messages/bulk-delete"
+        );
+        assert_eq!(
+            bucket_key(&Method::GET, "https://discord.com/api/v9/users/@me/guilds"),
+            "GET users/@me/guilds"
+        );
+    }
+
+    #[test]
+    fn test_reaction_removals_bucket_per_channel() {
+        let reaction = |channel: &str| {
+            format!(
+                "https://discord.com/api/v9/channels/{}/messages/10/reactions/%F0%9F%91%8D/@me",
+                channel
+            )
+        };
+
+        assert_eq!(
+            bucket_key(&Method::DELETE, &reaction("1")),
+            "DELETE channels/1/messages/:id/reactions"
+        );
+        assert_ne!(
+            bucket_key(&Method::DELETE, &reaction("1")),
+            bucket_key(&Method::DELETE, &reaction("2"))
+        );
+        assert_eq!(
+            bucket_key(&Method::GET, "https://discord.com/api/v9/users/@me"),
+            "GET users/@me"
+        );
+    }
+}
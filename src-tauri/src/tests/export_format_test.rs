@@ -0,0 +1,205 @@
+// src-tauri/src/tests/export_format_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::export::csv::csv_field;
+    use crate::api::discord::export::format::{ChannelInfo, format_for, page_file_name};
+    use crate::api::discord::export::markdown::MarkdownRenderer;
+    use crate::api::discord::export::mbox::{encode_header, escape_from_lines, quoted_printable};
+    use crate::api::discord::export::render::RenderContext;
+    use chrono::FixedOffset;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn render(format: &str, messages: &[serde_json::Value]) -> String {
+        let mut exporter = format_for(format, true, Arc::new(RenderContext::default())).unwrap();
+        let channel = ChannelInfo {
+            id: "1".into(),
+            name: "general".into(),
+            guild_id: None,
+        };
+        let mut out = Vec::new();
+        exporter.begin(&mut out, &channel, 1, None).unwrap();
+        for msg in messages {
+            exporter.write_message(&mut out, msg).unwrap();
+        }
+        exporter.end(&mut out, None).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn message(id: &str, timestamp: &str, content: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "timestamp": timestamp,
+            "content": content,
+            "author": { "id": "10", "username": "alice" },
+        })
+    }
+
+    #[test]
+    fn test_unknown_format_is_rejected() {
+        let err = format_for("docx", false, Arc::new(RenderContext::default()))
+            .err()
+            .unwrap();
+        assert_eq!(err.error_code, "invalid_format");
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_csv_field_neutralises_formulas() {
+        assert_eq!(csv_field("=1+1"), "\"'=1+1\"");
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("@SUM(A1)"), "\"'@SUM(A1)\"");
+        assert_eq!(csv_field("-2"), "\"'-2\"");
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
+
+    fn context() -> RenderContext {
+        let mut ctx = RenderContext::default();
+        ctx.users.insert("7".into(), "carol".into());
+        ctx.channels.insert("99".into(), "general".into());
+        ctx
+    }
+
+    #[test]
+    fn test_html_escapes_and_renders_markdown() {
+        let ctx = context();
+        let msg = json!({ "mentions": [{ "id": "42", "username": "bob" }] });
+        let html = MarkdownRenderer::new(&ctx, &msg)
+            .render("<script>x</script> **bold** ||secret|| `<b>` <@42> <@7> <#99>");
+        assert!(html.contains("&lt;script&gt;x&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<span class=\"spoiler\">secret</span>"));
+        assert!(html.contains("<code>&lt;b&gt;</code>"));
+        assert!(html.contains(">@bob</span>"));
+        assert!(html.contains(">@carol</span>"));
+        assert!(html.contains(">#general</span>"));
+    }
+
+    #[test]
+    fn test_html_code_blocks_quotes_and_links() {
+        let ctx = context();
+        let msg = json!({});
+        let html = MarkdownRenderer::new(&ctx, &msg).render(
+            "> quoted **line**\nplain https://example.com/a?b=1&c=2.\n```rust\nlet x = \"<*a*>\";\n```",
+        );
+        assert!(html.contains("<blockquote>quoted <strong>line</strong></blockquote>"));
+        assert!(html.contains("<a href=\"https://example.com/a?b=1&amp;c=2\">"));
+        assert!(html.contains("</a>."));
+        assert!(html.contains(
+            "<pre><code class=\"language-rust\">let x = &quot;&lt;*a*&gt;&quot;;\n</code></pre>"
+        ));
+
+        let masked = MarkdownRenderer::new(&ctx, &msg)
+            .render("[click](javascript:alert(1)) [ok](https://ok.test/_x_)");
+        assert!(!masked.contains("href=\"javascript"));
+        assert!(masked.contains("[click](javascript:alert(1))"));
+        assert!(masked.contains("<a href=\"https://ok.test/_x_\">ok</a>"));
+    }
+
+    #[test]
+    fn test_tokens_use_export_time_zone() {
+        let mut ctx = context();
+        ctx.offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let msg = json!({});
+        assert_eq!(
+            ctx.resolve_plain(&msg, "<t:0:T> <:blob:123> <#99> <@5>"),
+            "02:00:00 :blob: #general @5"
+        );
+        assert_eq!(
+            ctx.format_timestamp("2024-01-01T23:30:00+00:00"),
+            "2024-01-02 01:30:00"
+        );
+        let html = MarkdownRenderer::new(&ctx, &msg).render("<a:dance:55>");
+        assert!(html.contains("https://cdn.discordapp.com/emojis/55.gif"));
+    }
+
+    #[test]
+    fn test_text_formats_insert_day_separators() {
+        let messages = [
+            message("1", "2024-01-01T10:00:00+00:00", "first"),
+            message("2", "2024-01-01T11:00:00+00:00", "second"),
+            message("3", "2024-01-02T09:00:00+00:00", "third"),
+        ];
+        let txt = render("txt", &messages);
+        assert_eq!(txt.matches("-----").count(), 4);
+        assert!(txt.contains("[2024-01-01 10:00:00] alice: first"));
+
+        let md = render("markdown", &messages);
+        assert!(md.contains("## Monday, January 1, 2024"));
+        assert!(md.contains("## Tuesday, January 2, 2024"));
+    }
+
+    #[test]
+    fn test_jsonl_and_raw_output_parse() {
+        let messages = [
+            message("1", "2024-01-01T10:00:00+00:00", "a"),
+            message("2", "2024-01-01T10:01:00+00:00", "b"),
+        ];
+        let jsonl = render("jsonl", &messages);
+        assert_eq!(jsonl.lines().count(), 2);
+        for line in jsonl.lines() {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+
+        let raw: Vec<serde_json::Value> = serde_json::from_str(&render("raw", &messages)).unwrap();
+        assert_eq!(raw.len(), 2);
+        let empty: Vec<serde_json::Value> = serde_json::from_str(&render("raw", &[])).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_mbox_threads_replies_and_attaches_files() {
+        let mut reply = message("2", "2024-01-01T10:01:00+00:00", "From here on");
+        reply["message_reference"] = json!({ "message_id": "1" });
+        reply["attachments"] = json!([
+            { "id": "5", "filename": "a.png", "data_uri": "data:image/png;base64,AAAA" },
+            { "id": "6", "filename": "b.zip", "url": "https://cdn.example/b.zip" }
+        ]);
+        let mut second = message("3", "2024-01-01T10:02:00+00:00", "ok");
+        second["message_reference"] = json!({ "message_id": "2" });
+        let mbox = render(
+            "mbox",
+            &[
+                message("1", "2024-01-01T10:00:00+00:00", "hi"),
+                reply,
+                second,
+            ],
+        );
+
+        assert_eq!(mbox.matches("\nFrom 10@users.discord.com ").count() + 1, 3);
+        assert!(mbox.starts_with("From 10@users.discord.com Mon Jan  1 10:00:00 2024\n"));
+        assert!(mbox.contains("From: \"alice\" <10@users.discord.com>"));
+        assert!(mbox.contains("Message-ID: <2.1@discord.com>\nIn-Reply-To: <1.1@discord.com>"));
+        assert!(mbox.contains("References: <1.1@discord.com>\n <2.1@discord.com>"));
+        assert!(mbox.contains("Content-Disposition: attachment; filename=\"a.png\""));
+        assert!(mbox.contains("[attachment] b.zip https://cdn.example/b.zip"));
+        assert!(mbox.contains("\n>From here on"));
+    }
+
+    #[test]
+    fn test_mbox_encodings() {
+        assert_eq!(encode_header("plain"), "plain");
+        assert_eq!(encode_header("é"), "=?UTF-8?B?w6k=?=");
+        assert_eq!(quoted_printable("a=b é \nx"), "a=3Db =C3=A9=20\nx");
+        let long = quoted_printable(&"x".repeat(100));
+        assert!(long.lines().all(|l| l.len() <= 76));
+        assert_eq!(
+            escape_from_lines("From a\n>From b\nFromage"),
+            ">From a\n>>From b\nFromage"
+        );
+    }
+
+    #[test]
+    fn test_page_file_names() {
+        assert_eq!(page_file_name("123", 1, "html"), "123.html");
+        assert_eq!(page_file_name("123", 3, "html"), "123_page3.html");
+    }
+}
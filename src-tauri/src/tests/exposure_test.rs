@@ -0,0 +1,91 @@
+// src-tauri/src/tests/exposure_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::bulk::messages::PurgeOptions;
+    use crate::core::forensics::exposure::{ExposureCategory, ExposureFinding, ExposureScorer};
+    use crate::core::forensics::pii::PIIResult;
+    use std::collections::HashMap;
+
+    fn pii(message_id: &str, channel_id: &str, kind: &str) -> PIIResult {
+        PIIResult {
+            message_id: message_id.into(),
+            channel_id: channel_id.into(),
+            author_id: "me".into(),
+            detected_types: vec![kind.into()],
+            snippet: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_pii_findings_group_by_type_and_scope() {
+        let results = vec![
+            pii("1", "c1", "Email"),
+            pii("2", "c2", "Email"),
+            pii("3", "dm", "Email"),
+        ];
+        let mut channel_guilds = HashMap::new();
+        channel_guilds.insert("c1".to_string(), Some("g1".to_string()));
+        channel_guilds.insert("c2".to_string(), Some("g2".to_string()));
+        channel_guilds.insert("dm".to_string(), None);
+
+        let findings = ExposureScorer::pii_findings(&results, &channel_guilds);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].title,
+            "3 messages containing Email in 2 servers and 1 DMs"
+        );
+        assert!(findings[0].remediation.is_some());
+    }
+
+    #[test]
+    fn test_purge_remediations_deserialize() {
+        let results = vec![
+            pii("1", "c1", "Email"),
+            pii("2", "c1", "SSN (US)"),
+            pii("3", "c2", "Email"),
+        ];
+        let mut findings =
+            ExposureScorer::footprint_findings(10, 2, &["c1".to_string(), "c2".to_string()]);
+        findings.extend(ExposureScorer::pii_findings(&results, &HashMap::new()));
+        assert_eq!(findings.len(), 4);
+
+        for finding in &findings {
+            let remediation = finding.remediation.as_ref().unwrap();
+            assert_eq!(remediation.command, "bulk_delete_messages");
+            let options: PurgeOptions =
+                serde_json::from_value(remediation.args["options"].clone()).unwrap();
+            assert!(!options.simulation);
+            assert!(!options.channel_ids.is_empty());
+        }
+
+        // PII purges target only the flagged messages, per channel.
+        let email = findings
+            .iter()
+            .find(|f| f.title.contains("Email"))
+            .and_then(|f| f.remediation.as_ref())
+            .unwrap();
+        let options: PurgeOptions = serde_json::from_value(email.args["options"].clone()).unwrap();
+        let message_ids = options.message_ids.unwrap();
+        assert_eq!(options.channel_ids, vec!["c1", "c2"]);
+        assert_eq!(message_ids["c1"], vec!["1"]);
+        assert_eq!(message_ids["c2"], vec!["3"]);
+    }
+
+    #[test]
+    fn test_report_caps_each_category() {
+        let findings = (0..10)
+            .map(|i| ExposureFinding {
+                category: ExposureCategory::Sessions,
+                title: format!("finding {}", i),
+                points: 5.0,
+                remediation: None,
+            })
+            .collect();
+
+        let report = ExposureScorer::build_report(findings, Vec::new());
+        assert_eq!(report.score, 10);
+        assert_eq!(report.categories.len(), 1);
+        assert_eq!(report.findings.len(), 10);
+    }
+}
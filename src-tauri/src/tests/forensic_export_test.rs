@@ -0,0 +1,154 @@
+// src-tauri/src/tests/forensic_export_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::forensics::export::{
+        ExportForensics, HASH_PROPERTY, MerkleBuilder, canonical_json, custody_root, record_hash,
+    };
+    use serde_json::{Map, Value, json};
+    use sha2::{Digest, Sha256};
+
+    fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([0x01]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+
+    /// Level-by-level reference: pair from the left, carry an odd node up.
+    fn reference_root(leaves: &[[u8; 32]]) -> String {
+        if leaves.is_empty() {
+            return hex::encode(Sha256::digest(b""));
+        }
+        let mut level = leaves.to_vec();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [l, r] => node(l, r),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        hex::encode(level[0])
+    }
+
+    fn signed(mut record: Value) -> Value {
+        record[HASH_PROPERTY] = Value::String(hex::encode(record_hash(&record)));
+        record
+    }
+
+    fn header(records: usize) -> Map<String, Value> {
+        let doc = json!({
+            "@context": { "@vocab": "https://schema.org/" },
+            "@type": "Dataset",
+            "creator": { "@type": "Person", "identifier": "1", "name": "collector" },
+            "timeSource": { "clock": "system", "startTime": "2026-01-01T00:00:00+00:00" },
+            "integrity": { "recordCount": records, "endTime": "2026-01-01T00:05:00+00:00" },
+        });
+        doc.as_object().unwrap().clone()
+    }
+
+    /// Seals `records` under `header` the way the exporter does.
+    fn sealed(records: &[Value], mut header: Map<String, Value>) -> Map<String, Value> {
+        let mut merkle = MerkleBuilder::default();
+        for r in records {
+            merkle.push(record_hash(r));
+        }
+        let root = custody_root(&merkle.root(), &header);
+        header["integrity"]["merkleRoot"] = Value::String(root);
+        header
+    }
+
+    fn write_export(
+        name: &str,
+        records: &[Value],
+        header: &Map<String, Value>,
+    ) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("forensic_{}_{}.jsonld", name, std::process::id()));
+        let mut doc = header.clone();
+        doc.insert("@graph".into(), Value::Array(records.to_vec()));
+        std::fs::write(&path, Value::Object(doc).to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_merkle_matches_level_by_level() {
+        for count in 0..8u8 {
+            let leaves: Vec<[u8; 32]> = (0..count).map(|i| Sha256::digest([i]).into()).collect();
+            let mut builder = MerkleBuilder::default();
+            for leaf in &leaves {
+                builder.push(*leaf);
+            }
+            assert_eq!(builder.root(), reference_root(&leaves), "{} leaves", count);
+        }
+    }
+
+    #[test]
+    fn test_record_hash_ignores_key_order_and_own_digest() {
+        let a = json!({"b": 1, "a": {"y": [1, 2], "x": "s"}});
+        let b = json!({"a": {"x": "s", "y": [1, 2]}, "b": 1});
+        assert_eq!(canonical_json(&a), r#"{"a":{"x":"s","y":[1,2]},"b":1}"#);
+        assert_eq!(record_hash(&a), record_hash(&b));
+        assert_eq!(record_hash(&a), record_hash(&signed(b)));
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let records: Vec<Value> = (1..=3)
+            .map(|i| signed(json!({"@id": format!("discord:message:{}", i), "text": "hi"})))
+            .collect();
+        let header = sealed(&records, header(3));
+
+        let path = write_export("valid", &records, &header);
+        let result = ExportForensics::verify_json_ld(&path.to_string_lossy()).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.records, 3);
+        let _ = std::fs::remove_file(&path);
+
+        let mut edited = records.clone();
+        edited[1]["text"] = json!("edited");
+        let path = write_export("tampered", &edited, &header);
+        let result = ExportForensics::verify_json_ld(&path.to_string_lossy()).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.tampered, vec!["discord:message:2".to_string()]);
+        let _ = std::fs::remove_file(&path);
+
+        let path = write_export("dropped", &records[..2], &header);
+        let result = ExportForensics::verify_json_ld(&path.to_string_lossy()).unwrap();
+        assert!(result.tampered.is_empty());
+        assert!(!result.valid);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_verify_covers_custody_metadata() {
+        let records = vec![signed(json!({"@id": "discord:message:1", "text": "hi"}))];
+        let header = sealed(&records, header(1));
+
+        let mut edited = header.clone();
+        edited["creator"]["identifier"] = json!("2");
+        let path = write_export("creator", &records, &edited);
+        let result = ExportForensics::verify_json_ld(&path.to_string_lossy()).unwrap();
+        assert!(result.tampered.is_empty());
+        assert!(!result.valid);
+        let _ = std::fs::remove_file(&path);
+
+        let mut edited = header.clone();
+        edited["integrity"]["endTime"] = json!("2026-01-02T00:00:00+00:00");
+        let path = write_export("end_time", &records, &edited);
+        let result = ExportForensics::verify_json_ld(&path.to_string_lossy()).unwrap();
+        assert!(!result.valid);
+        let _ = std::fs::remove_file(&path);
+
+        let mut edited = header;
+        edited.insert("producer".into(), json!({ "name": "other" }));
+        let path = write_export("producer", &records, &edited);
+        let result = ExportForensics::verify_json_ld(&path.to_string_lossy()).unwrap();
+        assert!(!result.valid);
+        let _ = std::fs::remove_file(&path);
+    }
+}
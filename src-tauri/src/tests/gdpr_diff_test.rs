@@ -0,0 +1,56 @@
+// src-tauri/src/tests/gdpr_diff_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::gdpr::diff::{PackageDiff, PackageSnapshot};
+    use std::collections::HashSet;
+
+    fn set(items: &[&str]) -> HashSet<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff_between_packages() {
+        let mut old = PackageSnapshot {
+            guilds: set(&["g1", "g2"]),
+            connections: set(&["steam:1"]),
+            ..Default::default()
+        };
+        old.messages.insert("c1".into(), set(&["m1", "m2"]));
+
+        let mut new = PackageSnapshot {
+            guilds: set(&["g2", "g3"]),
+            connections: set(&["steam:1"]),
+            ..Default::default()
+        };
+        new.messages.insert("c1".into(), set(&["m2", "m3"]));
+
+        let diff = PackageDiff::between(&old, &new);
+        assert_eq!(diff.messages_added, 1);
+        assert_eq!(diff.messages_removed, 1);
+        assert_eq!(diff.messages[0].removed, vec!["m1"]);
+        assert_eq!(diff.guilds.added, vec!["g3"]);
+        assert_eq!(diff.guilds.removed, vec!["g1"]);
+        assert!(diff.connections.added.is_empty() && diff.connections.removed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_ignores_channels_not_fully_known() {
+        let mut package = PackageSnapshot::default();
+        package
+            .messages
+            .insert("scanned".into(), set(&["m1", "m2"]));
+        package.messages.insert("unscanned".into(), set(&["m3"]));
+
+        let mut cache = PackageSnapshot {
+            complete_channels: Some(set(&["scanned"])),
+            ..Default::default()
+        };
+        cache.messages.insert("scanned".into(), set(&["m2"]));
+
+        let diff = PackageDiff::between(&package, &cache);
+        assert_eq!(diff.messages.len(), 1);
+        assert_eq!(diff.messages[0].channel_id, "scanned");
+        assert_eq!(diff.messages_removed, 1);
+    }
+}
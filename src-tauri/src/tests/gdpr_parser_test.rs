@@ -0,0 +1,169 @@
+// src-tauri/src/tests/gdpr_parser_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::error::AppError;
+    use crate::core::gdpr::activity::ActivityLog;
+    use crate::core::gdpr::parser::{
+        CsvRecords, PackageEntry, attachment_from_url, parse_activity_line, parse_channel_json,
+        parse_servers_index, parse_timestamp, read_messages_csv, read_messages_json,
+    };
+
+    #[test]
+    fn test_classify_package_paths() {
+        assert_eq!(
+            PackageEntry::classify("messages/c123/messages.csv"),
+            PackageEntry::MessagesCsv {
+                channel_id: "123".into()
+            }
+        );
+        assert_eq!(
+            PackageEntry::classify("Messages/c456/messages.json"),
+            PackageEntry::MessagesJson {
+                channel_id: "456".into()
+            }
+        );
+        assert_eq!(
+            PackageEntry::classify("servers/789/guild.json"),
+            PackageEntry::GuildMeta {
+                guild_id: "789".into()
+            }
+        );
+        assert_eq!(
+            PackageEntry::classify("account/user.json"),
+            PackageEntry::Account
+        );
+        assert_eq!(
+            PackageEntry::classify("activity/analytics/events-2021-00000-of-00001.json"),
+            PackageEntry::Activity {
+                category: "analytics".into()
+            }
+        );
+        assert_eq!(
+            PackageEntry::classify("account/avatar.png"),
+            PackageEntry::Other
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        assert_eq!(
+            parse_timestamp("2021-01-01 00:00:00.000000+00:00"),
+            1609459200000
+        );
+        assert_eq!(parse_timestamp("2021-01-01T00:00:00+00:00"), 1609459200000);
+        assert_eq!(parse_timestamp("2021-01-01 00:00:00"), 1609459200000);
+        assert_eq!(parse_timestamp("garbage"), 0);
+    }
+
+    #[test]
+    fn test_read_messages_csv_handles_quoted_fields() {
+        let csv = "ID,Timestamp,Contents,Attachments\r\n\
+            1,2021-01-01 00:00:00+00:00,\"hello, \"\"world\"\"\nsecond line\",\r\n\
+            2,2021-01-01 00:00:01+00:00,,https://cdn.discordapp.com/attachments/1/2/a.png\r\n";
+        let mut messages = Vec::new();
+        read_messages_csv(csv.as_bytes(), |m| {
+            messages.push(m);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "hello, \"world\"\nsecond line");
+        assert!(messages[0].attachments.is_empty());
+        assert_eq!(messages[1].timestamp, 1609459201000);
+        assert_eq!(messages[1].attachments.len(), 1);
+    }
+
+    #[test]
+    fn test_read_messages_json() {
+        let json = r#"[{"ID": 10, "Timestamp": "2021-01-01 00:00:00", "Contents": "hi", "Attachments": ""}]"#;
+        let mut messages = Vec::new();
+        read_messages_json(json.as_bytes(), |m| {
+            messages.push(m);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, "10");
+        assert_eq!(messages[0].content, "hi");
+    }
+
+    #[test]
+    fn test_read_messages_json_stops_at_visitor_error() {
+        let json = r#"[{"ID": 1, "Timestamp": ""}, {"ID": 2, "Timestamp": ""}]"#;
+        let mut seen = 0;
+        let err = read_messages_json(json.as_bytes(), |_| {
+            seen += 1;
+            Err(AppError::new("full", "cache_full"))
+        })
+        .unwrap_err();
+
+        assert_eq!(seen, 1);
+        assert_eq!(err.error_code, "cache_full");
+    }
+
+    #[test]
+    fn test_parse_channel_and_servers() {
+        let channel = parse_channel_json(
+            r#"{"id": "5", "type": "GUILD_TEXT", "name": "general", "guild": {"id": "9", "name": "Home"}}"#,
+        )
+        .unwrap();
+        assert_eq!(channel.guild_id.as_deref(), Some("9"));
+        assert_eq!(channel.channel_type, 0);
+
+        let dm = parse_channel_json(r#"{"id": "6", "type": 1, "recipients": ["1", "2"]}"#).unwrap();
+        assert_eq!(dm.recipients, vec!["1".to_string(), "2".to_string()]);
+
+        assert_eq!(parse_servers_index(r#"{"9": "Home"}"#).len(), 1);
+        assert_eq!(
+            parse_servers_index(r#"[{"id": "9", "name": "Home"}]"#)[0].0,
+            "9"
+        );
+    }
+
+    #[test]
+    fn test_attachment_from_url() {
+        assert_eq!(
+            attachment_from_url("https://cdn.discordapp.com/attachments/1/2/a.png?ex=1"),
+            Some(("2".to_string(), "a.png".to_string()))
+        );
+        assert_eq!(attachment_from_url("https://example.com/x.png"), None);
+    }
+
+    #[test]
+    fn test_csv_records_stream_across_small_buffers() {
+        let csv = "a,\"multi\nline, quoted\"\nb,c\n";
+        let reader = std::io::BufReader::with_capacity(4, csv.as_bytes());
+        let records: Vec<Vec<String>> = CsvRecords::new(reader).flatten().collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0][1], "multi\nline, quoted");
+        assert_eq!(records[1], vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_read_messages_csv_rejects_invalid_utf8() {
+        let bytes: &[u8] = b"ID,Timestamp,Contents\n1,2021-01-01 00:00:00,\xff\xfe\n";
+        assert!(read_messages_csv(bytes, |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_parse_activity_line() {
+        let line = r#"{"event_type": "app_opened", "event_id": "abc", "timestamp": "\"2021-01-01T00:00:00.000Z\"", "os": "Windows", "city": "Berlin", "ip": ""}"#;
+        let event = parse_activity_line(line).unwrap();
+
+        assert_eq!(event.event_type, "app_opened");
+        assert_eq!(event.event_id.as_deref(), Some("abc"));
+        assert_eq!(event.timestamp, 1609459200000);
+
+        let observed = ActivityLog::observed_values(&event);
+        assert!(observed.contains(&("os", "Windows".to_string())));
+        assert!(observed.contains(&("city", "Berlin".to_string())));
+        assert!(!observed.iter().any(|(field, _)| *field == "ip"));
+
+        assert!(parse_activity_line("{\"no_type\": 1}").is_none());
+        assert!(parse_activity_line("not json").is_none());
+    }
+}
@@ -0,0 +1,41 @@
+// src-tauri/src/tests/guild_archive_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::export::archive::{
+        ArchivedChannel, can_have_threads, is_message_channel,
+    };
+
+    #[test]
+    fn test_channel_type_selection() {
+        // Text, voice, announcement, threads and stage hold messages.
+        for kind in [0, 2, 5, 10, 11, 12, 13] {
+            assert!(is_message_channel(kind), "type {}", kind);
+        }
+        // Categories, directories, forums and media only hold threads or nothing.
+        for kind in [4, 14, 15, 16] {
+            assert!(!is_message_channel(kind), "type {}", kind);
+        }
+        assert!(can_have_threads(15));
+        assert!(can_have_threads(0));
+        assert!(!can_have_threads(2));
+        assert!(!can_have_threads(11));
+    }
+
+    #[test]
+    fn test_manifest_channel_uses_discord_type_key() {
+        let channel = ArchivedChannel {
+            id: "1".into(),
+            name: "general".into(),
+            kind: 0,
+            parent_id: None,
+            messages: 3,
+            pins: 1,
+            files: vec!["channels/1.jsonl".into(), "channels/1.html".into()],
+        };
+        let json = serde_json::to_value(&channel).unwrap();
+        assert_eq!(json["type"], 0);
+        assert!(json.get("kind").is_none());
+        assert_eq!(json["files"][1], "channels/1.html");
+    }
+}
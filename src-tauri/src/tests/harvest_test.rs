@@ -0,0 +1,70 @@
+// src-tauri/src/tests/harvest_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::core::gdpr::harvest::{
+        HARVEST_COOLDOWN_SECS, HarvestRecord, HarvestStatus, HarvestTracker,
+    };
+    use serde_json::json;
+
+    fn pending(identity_id: &str) -> HarvestRecord {
+        HarvestRecord {
+            identity_id: identity_id.to_string(),
+            harvest_id: None,
+            status: HarvestStatus::Pending,
+            requested_at: 1_700_000_000,
+            completed_at: None,
+            last_polled: None,
+            next_request_at: 1_700_000_000 + HARVEST_COOLDOWN_SECS,
+            imported_path: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_status_marks_ready() {
+        let mut records = vec![pending("1"), pending("2")];
+
+        let still_pending = json!({ "harvest_id": "h1", "completed_at": null });
+        assert!(!HarvestTracker::apply_status(
+            &mut records,
+            "1",
+            &still_pending,
+            10
+        ));
+        assert_eq!(records[0].status, HarvestStatus::Pending);
+        assert_eq!(records[0].harvest_id.as_deref(), Some("h1"));
+        assert_eq!(records[0].last_polled, Some(10));
+
+        let done = json!({ "harvest_id": "h1", "completed_at": "2023-11-20T10:00:00+00:00" });
+        assert!(HarvestTracker::apply_status(&mut records, "1", &done, 20));
+        assert_eq!(records[0].status, HarvestStatus::Ready);
+        assert_eq!(records[0].completed_at, Some(1_700_474_400));
+        assert_eq!(records[1].status, HarvestStatus::Pending);
+
+        // Already ready: no second notification.
+        assert!(!HarvestTracker::apply_status(&mut records, "1", &done, 30));
+    }
+
+    #[test]
+    fn test_find_package_checks_owner_and_skips_imported() {
+        let dir = std::env::temp_dir().join(format!("harvest_find_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["mine.zip", "other.zip", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let mine = dir.join("mine.zip").to_string_lossy().to_string();
+        let owned = |path: &str| path.ends_with("mine.zip");
+
+        assert_eq!(
+            HarvestTracker::find_package(&dir, 0, &[], owned),
+            Some(dir.join("mine.zip"))
+        );
+        assert_eq!(HarvestTracker::find_package(&dir, 0, &[mine], owned), None);
+        assert_eq!(
+            HarvestTracker::find_package(&dir, i64::MAX, &[], |_| true),
+            None
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,24 @@
 // src-tauri/src/tests/mod.rs
+pub mod analytics_test;
+pub mod archive_import_test;
+pub mod attachment_store_test;
+pub mod cache_test;
 pub mod ci_test;
+pub mod container_test;
+pub mod correlation_test;
 pub mod crypto_test;
+pub mod discord_routes_test;
 pub mod error_test;
+pub mod export_format_test;
+pub mod exposure_test;
 pub mod fingerprint_test;
+pub mod forensic_export_test;
+pub mod gdpr_diff_test;
+pub mod gdpr_parser_test;
+pub mod guild_archive_test;
+pub mod harvest_test;
 pub mod logger_test;
+pub mod package_purge_test;
+pub mod permissions_test;
+pub mod purge_options_test;
+pub mod reactions_test;
@@ -0,0 +1,68 @@
+// src-tauri/src/tests/package_purge_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::bulk::package::{
+        DeleteOutcome, PackagePurgeOptions, dm_recipient, select_targets,
+    };
+    use crate::api::discord::gdpr::DiscoveredMessage;
+    use crate::auth::types::DiscordError;
+    use crate::core::error::AppError;
+
+    fn discord_error(code: u32) -> Result<(), AppError> {
+        Err(AppError {
+            discord_code: Some(code),
+            semantic_error: Some(DiscordError::from_code(code)),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_delete_outcome_classification() {
+        assert_eq!(DeleteOutcome::from_result(&Ok(())), DeleteOutcome::Deleted);
+        assert_eq!(
+            DeleteOutcome::from_result(&discord_error(10008)),
+            DeleteOutcome::AlreadyGone
+        );
+        assert_eq!(
+            DeleteOutcome::from_result(&discord_error(10003)),
+            DeleteOutcome::Inaccessible
+        );
+        assert_eq!(
+            DeleteOutcome::from_result(&discord_error(50001)),
+            DeleteOutcome::Inaccessible
+        );
+        assert_eq!(
+            DeleteOutcome::from_result(&discord_error(20016)),
+            DeleteOutcome::Failed
+        );
+    }
+
+    #[test]
+    fn test_select_targets_respects_time_window() {
+        let messages: Vec<DiscoveredMessage> = [("1", 100), ("2", 200), ("3", 300)]
+            .iter()
+            .map(|(id, timestamp)| DiscoveredMessage {
+                id: id.to_string(),
+                timestamp: *timestamp,
+            })
+            .collect();
+
+        let all = select_targets(&messages, &PackagePurgeOptions::default());
+        assert_eq!(all.len(), 3);
+
+        let window = PackagePurgeOptions {
+            start_time: Some(150),
+            end_time: Some(300),
+            ..Default::default()
+        };
+        assert_eq!(select_targets(&messages, &window), vec!["2", "3"]);
+    }
+
+    #[test]
+    fn test_dm_recipient_skips_own_id() {
+        let recipients = vec!["me".to_string(), "friend".to_string()];
+        assert_eq!(dm_recipient(&recipients, "me").as_deref(), Some("friend"));
+        assert_eq!(dm_recipient(&["me".to_string()], "me"), None);
+    }
+}
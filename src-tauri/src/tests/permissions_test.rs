@@ -0,0 +1,89 @@
+// src-tauri/src/tests/permissions_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::bulk::permissions::{
+        ADMINISTRATOR, BULK_DELETE_MAX_AGE_MS, MANAGE_MESSAGES, base_permissions, bulk_deletable,
+        channel_permissions, snowflake_millis,
+    };
+    use serde_json::json;
+
+    const GUILD: &str = "100";
+    const USER: &str = "200";
+
+    #[test]
+    fn test_snowflake_age() {
+        // Example from Discord's reference documentation.
+        let id = "175928847299117063";
+        let created = 1_462_015_105_796;
+        assert_eq!(snowflake_millis(id), Some(created));
+        assert_eq!(snowflake_millis("not-an-id"), None);
+
+        assert!(bulk_deletable(id, created + 1000));
+        assert!(bulk_deletable(id, created + BULK_DELETE_MAX_AGE_MS - 1));
+        assert!(!bulk_deletable(id, created + BULK_DELETE_MAX_AGE_MS));
+        assert!(!bulk_deletable("not-an-id", created));
+    }
+
+    #[test]
+    fn test_base_permissions() {
+        let roles = vec![
+            json!({ "id": GUILD, "permissions": "1024" }),
+            json!({ "id": "1", "permissions": MANAGE_MESSAGES.to_string() }),
+            json!({ "id": "2", "permissions": ADMINISTRATOR.to_string() }),
+        ];
+
+        assert_eq!(base_permissions(GUILD, false, &roles, &[]), 1024);
+        assert_eq!(
+            base_permissions(GUILD, false, &roles, &["1".to_string()]),
+            1024 | MANAGE_MESSAGES
+        );
+        assert_eq!(
+            base_permissions(GUILD, false, &roles, &["2".to_string()]),
+            u64::MAX
+        );
+        assert_eq!(base_permissions(GUILD, true, &[], &[]), u64::MAX);
+    }
+
+    #[test]
+    fn test_channel_overwrite_order() {
+        let roles = vec!["1".to_string(), "2".to_string()];
+        let everyone_deny =
+            json!({ "id": GUILD, "allow": "0", "deny": MANAGE_MESSAGES.to_string() });
+        let role_allow = json!({ "id": "1", "allow": MANAGE_MESSAGES.to_string(), "deny": "0" });
+        let role_deny = json!({ "id": "2", "allow": "0", "deny": MANAGE_MESSAGES.to_string() });
+        let member_deny = json!({ "id": USER, "allow": "0", "deny": MANAGE_MESSAGES.to_string() });
+
+        let base = MANAGE_MESSAGES;
+        assert_eq!(
+            channel_permissions(base, GUILD, USER, &roles, &[everyone_deny.clone()]),
+            0
+        );
+        // Role allows win over role denies, and both over @everyone.
+        assert_eq!(
+            channel_permissions(
+                base,
+                GUILD,
+                USER,
+                &roles,
+                &[everyone_deny.clone(), role_allow.clone(), role_deny]
+            ),
+            MANAGE_MESSAGES
+        );
+        assert_eq!(
+            channel_permissions(
+                base,
+                GUILD,
+                USER,
+                &roles,
+                &[everyone_deny.clone(), role_allow, member_deny]
+            ),
+            0
+        );
+        // Administrators ignore overwrites.
+        assert_eq!(
+            channel_permissions(u64::MAX, GUILD, USER, &roles, &[everyone_deny]),
+            u64::MAX
+        );
+    }
+}
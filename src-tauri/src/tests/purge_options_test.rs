@@ -0,0 +1,56 @@
+// src-tauri/src/tests/purge_options_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::bulk::messages::{PinnedMode, PurgeOptions};
+    use serde_json::json;
+
+    fn options(extra: serde_json::Value) -> PurgeOptions {
+        let mut base = json!({
+            "channelIds": ["1"],
+            "purgeReactions": false,
+            "simulation": true,
+            "onlyAttachments": false,
+            "closeEmptyDms": false,
+        });
+        for (key, value) in extra.as_object().unwrap() {
+            base[key] = value.clone();
+        }
+        serde_json::from_value(base).unwrap()
+    }
+
+    #[test]
+    fn test_pinned_mode_defaults_to_include() {
+        assert_eq!(options(json!({})).pinned, PinnedMode::Include);
+        assert_eq!(
+            options(json!({ "pinnedMode": "unpin_then_delete" })).pinned,
+            PinnedMode::UnpinThenDelete
+        );
+        assert!(serde_json::from_value::<PinnedMode>(json!("sometimes")).is_err());
+    }
+
+    #[test]
+    fn test_pinned_mode_selects_targets() {
+        assert!(PinnedMode::Include.allows(true) && PinnedMode::Include.allows(false));
+        assert!(!PinnedMode::Skip.allows(true) && PinnedMode::Skip.allows(false));
+        assert!(PinnedMode::Only.allows(true) && !PinnedMode::Only.allows(false));
+        assert!(PinnedMode::UnpinThenDelete.allows(true));
+        assert!(PinnedMode::UnpinThenDelete.allows(false));
+    }
+
+    #[test]
+    fn test_failed_pin_fetch_skips_channel() {
+        for mode in [
+            PinnedMode::Skip,
+            PinnedMode::Only,
+            PinnedMode::UnpinThenDelete,
+        ] {
+            assert!(mode.usable_pins(None).is_none(), "{:?}", mode);
+            assert_eq!(
+                mode.usable_pins(Some(vec![json!({ "id": "1" })])),
+                Some(vec![json!({ "id": "1" })])
+            );
+        }
+        assert_eq!(PinnedMode::Include.usable_pins(None), Some(Vec::new()));
+    }
+}
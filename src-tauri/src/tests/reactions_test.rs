@@ -0,0 +1,61 @@
+// src-tauri/src/tests/reactions_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::api::discord::bulk::reactions::{emoji_matches, own_reactions};
+    use crate::core::cache::reaction_key;
+    use serde_json::json;
+
+    #[test]
+    fn test_reaction_keys() {
+        assert_eq!(
+            reaction_key(&json!({ "id": null, "name": "👍" })).as_deref(),
+            Some("👍")
+        );
+        assert_eq!(
+            reaction_key(&json!({ "id": "42", "name": "blob" })).as_deref(),
+            Some("blob:42")
+        );
+        assert_eq!(
+            reaction_key(&json!({ "id": "42", "name": null })).as_deref(),
+            Some("_:42")
+        );
+        assert!(reaction_key(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_emoji_filter_forms() {
+        assert!(emoji_matches("blob:42", &[]));
+        for filter in [
+            "blob",
+            ":blob:",
+            "42",
+            "blob:42",
+            "<:blob:42>",
+            "<a:blob:42>",
+        ] {
+            assert!(
+                emoji_matches("blob:42", &[filter.to_string()]),
+                "{}",
+                filter
+            );
+        }
+        assert!(!emoji_matches("blob:42", &["cat".to_string()]));
+        assert!(emoji_matches("👍", &["👍".to_string()]));
+        assert!(!emoji_matches("👍", &["👎".to_string()]));
+    }
+
+    #[test]
+    fn test_only_our_reactions_are_selected() {
+        let msg = json!({
+            "reactions": [
+                { "me": true, "count": 2, "emoji": { "id": null, "name": "👍" } },
+                { "me": false, "count": 1, "emoji": { "id": null, "name": "👎" } },
+                { "me": true, "count": 1, "emoji": { "id": "42", "name": "blob" } }
+            ]
+        });
+        assert_eq!(own_reactions(&msg, &[]), vec!["👍", "blob:42"]);
+        assert_eq!(own_reactions(&msg, &["blob".to_string()]), vec!["blob:42"]);
+        assert!(own_reactions(&json!({}), &[]).is_empty());
+    }
+}